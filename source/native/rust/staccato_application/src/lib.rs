@@ -6,8 +6,9 @@ use sdl3_sys::init::{
     SDL_SetAppMetadataProperty,
 };
 use staccato_core::fallible::Fallible;
-use staccato_core::tickable::Tickable;
+use staccato_core::tickable::{FrameTime, Tickable};
 use staccato_hal::error::SdlError;
+use staccato_hal::sdl_user_event::discard_pending_payloads;
 use staccato_hal::wgpu_window::WgpuWindow;
 use std::convert::Infallible;
 use std::ffi::CString;
@@ -27,6 +28,7 @@ pub struct ApplicationGuard {
 
 impl Drop for ApplicationGuard {
     fn drop(&mut self) {
+        discard_pending_payloads();
         unsafe { SDL_Quit() }
     }
 }
@@ -140,11 +142,11 @@ impl Tickable for ApplicationWindow<'_> {
         todo!()
     }
 
-    fn update(&mut self, _elapse_ns: u64) -> Result<(), Self::Error> {
+    fn update(&mut self, _time: &FrameTime) -> Result<(), Self::Error> {
         todo!()
     }
 
-    fn post_update(&mut self, _elapse_ns: u64) -> Result<(), Self::Error> {
+    fn post_update(&mut self, _time: &FrameTime) -> Result<(), Self::Error> {
         todo!()
     }
 }
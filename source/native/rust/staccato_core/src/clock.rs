@@ -0,0 +1,158 @@
+/// Named clocks driven by the ticker.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClockChannel {
+    /// Wall clock, should stay unscaled and running.
+    Real = 0,
+    /// Gameplay and physics, drives `fixed_update`.
+    Game = 1,
+    /// Menus and HUD, keeps running while the game is paused.
+    Ui = 2,
+}
+
+impl ClockChannel {
+    pub const COUNT: usize = 3;
+
+    pub const ALL: [ClockChannel; Self::COUNT] = [Self::Real, Self::Game, Self::Ui];
+}
+
+/// A scalable, pausable clock advanced by real time deltas.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Clock {
+    total_ns: u64,
+    delta_ns: u64,
+    scale: f64,
+    paused: bool,
+    /// sub-nanosecond leftover of scaled advances
+    remainder: f64,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self {
+            total_ns: 0,
+            delta_ns: 0,
+            scale: 1.0,
+            paused: false,
+            remainder: 0.0,
+        }
+    }
+}
+
+impl Clock {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Advance by `real_ns * scale`, or by nothing while paused. Returns the scaled delta.
+    pub fn advance(&mut self, real_ns: u64) -> u64 {
+        self.delta_ns = if self.paused {
+            0
+        } else if self.scale == 1.0 {
+            real_ns
+        } else {
+            let scaled = real_ns as f64 * self.scale + self.remainder;
+            if scaled.is_finite() && scaled < u64::MAX as f64 {
+                let whole = scaled.floor();
+                self.remainder = scaled - whole;
+                whole as u64
+            } else {
+                // a huge scale saturates instead of poisoning the remainder
+                self.remainder = 0.0;
+                u64::MAX
+            }
+        };
+
+        self.total_ns = self.total_ns.saturating_add(self.delta_ns);
+        self.delta_ns
+    }
+
+    /// Scaled time passed since the clock was created.
+    pub fn total_ns(&self) -> u64 {
+        self.total_ns
+    }
+
+    /// Jump to `ns`, ignoring pause and scale.
+    pub fn set_total_ns(&mut self, ns: u64) {
+        self.total_ns = ns;
+        self.remainder = 0.0;
+    }
+
+    /// Scaled delta of the last `advance`.
+    pub fn delta_ns(&self) -> u64 {
+        self.delta_ns
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Negative scales are treated as zero, NaN and infinite scales are ignored.
+    pub fn set_scale(&mut self, scale: f64) {
+        if scale.is_finite() {
+            self.scale = scale.max(0.0);
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+}
+
+/// One clock per `ClockChannel`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Clocks {
+    clocks: [Clock; ClockChannel::COUNT],
+}
+
+impl Clocks {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn get(&self, channel: ClockChannel) -> &Clock {
+        &self.clocks[channel as usize]
+    }
+
+    pub fn get_mut(&mut self, channel: ClockChannel) -> &mut Clock {
+        &mut self.clocks[channel as usize]
+    }
+
+    /// Advance every clock by the same real delta, returns the scaled deltas indexed by channel.
+    pub fn advance(&mut self, real_ns: u64) -> [u64; ClockChannel::COUNT] {
+        let mut deltas = [0; ClockChannel::COUNT];
+        for (delta, clock) in deltas.iter_mut().zip(self.clocks.iter_mut()) {
+            *delta = clock.advance(real_ns);
+        }
+        deltas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_non_finite_scales() {
+        let mut clock = Clock::new();
+        clock.set_scale(0.5);
+        clock.set_scale(f64::INFINITY);
+        clock.set_scale(f64::NAN);
+        assert_eq!(clock.scale(), 0.5);
+        assert_eq!(clock.advance(3), 1);
+        assert_eq!(clock.advance(3), 2);
+
+        clock.set_scale(f64::MAX);
+        assert_eq!(clock.advance(10), u64::MAX);
+        clock.set_scale(1.5);
+        assert_eq!(clock.advance(2), 3);
+    }
+}
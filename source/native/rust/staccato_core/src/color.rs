@@ -0,0 +1,73 @@
+/// A linear RGBA color, each channel from 0.0 to 1.0.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[repr(C)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color {
+    pub const TRANSPARENT: Self = Self::new(0.0, 0.0, 0.0, 0.0);
+    pub const BLACK: Self = Self::new(0.0, 0.0, 0.0, 1.0);
+    pub const WHITE: Self = Self::new(1.0, 1.0, 1.0, 1.0);
+    pub const RED: Self = Self::new(1.0, 0.0, 0.0, 1.0);
+    pub const GREEN: Self = Self::new(0.0, 1.0, 0.0, 1.0);
+    pub const BLUE: Self = Self::new(0.0, 0.0, 1.0, 1.0);
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+    /// From 8-bit sRGB channels, like the colors of an image editor.
+    #[must_use]
+    pub fn from_srgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        fn linear(channel: u8) -> f32 {
+            let c = f32::from(channel) / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        }
+        Self::new(linear(r), linear(g), linear(b), f32::from(a) / 255.0)
+    }
+
+    #[must_use]
+    pub fn with_alpha(self, a: f32) -> Self {
+        Self { a, ..self }
+    }
+
+    /// The color with its RGB multiplied by alpha.
+    #[must_use]
+    pub fn premultiplied(self) -> Self {
+        Self::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    #[must_use]
+    pub fn to_array(self) -> [f32; 4] {
+        [self.r, self.g, self.b, self.a]
+    }
+
+    /// Each channel rounded to 8 bits without color space conversion.
+    #[must_use]
+    pub fn to_rgba8(self) -> [u8; 4] {
+        self.to_array()
+            .map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_srgb_channels() {
+        let color = Color::from_srgba8(255, 0, 188, 128);
+        assert_eq!(color.r, 1.0);
+        assert_eq!(color.g, 0.0);
+        assert!((color.b - 0.5029).abs() < 0.001);
+        assert_eq!(color.to_rgba8(), [255, 0, 128, 128]);
+    }
+}
@@ -0,0 +1,93 @@
+use bitflags::bitflags;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+/// 手柄按键，数值与 SDL_GamepadButton 一致
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum GamepadButton {
+    /// 下方按键（例如 Xbox 的 A、PlayStation 的 Cross）
+    South = 0,
+    /// 右方按键（例如 Xbox 的 B、PlayStation 的 Circle）
+    East = 1,
+    /// 左方按键（例如 Xbox 的 X、PlayStation 的 Square）
+    West = 2,
+    /// 上方按键（例如 Xbox 的 Y、PlayStation 的 Triangle）
+    North = 3,
+    Back = 4,
+    Guide = 5,
+    Start = 6,
+    LeftStick = 7,
+    RightStick = 8,
+    LeftShoulder = 9,
+    RightShoulder = 10,
+    DpadUp = 11,
+    DpadDown = 12,
+    DpadLeft = 13,
+    DpadRight = 14,
+    /// 额外按键（例如 Xbox Series X 的分享键、Switch Pro 的截图键）
+    Misc1 = 15,
+    RightPaddle1 = 16,
+    LeftPaddle1 = 17,
+    RightPaddle2 = 18,
+    LeftPaddle2 = 19,
+    /// PS4/PS5 触摸板按下
+    Touchpad = 20,
+    Misc2 = 21,
+    Misc3 = 22,
+    Misc4 = 23,
+    Misc5 = 24,
+    Misc6 = 25,
+}
+
+impl GamepadButton {
+    pub const COUNT: usize = 26;
+}
+
+/// 手柄轴，数值与 SDL_GamepadAxis 一致
+///
+/// 摇杆范围为 `[-32768, 32767]`，扳机范围为 `[0, 32767]`
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum GamepadAxis {
+    LeftX = 0,
+    LeftY = 1,
+    RightX = 2,
+    RightY = 3,
+    LeftTrigger = 4,
+    RightTrigger = 5,
+}
+
+impl GamepadAxis {
+    pub const COUNT: usize = 6;
+}
+
+/// 传感器类型，数值与 SDL_SensorType 一致
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum SensorType {
+    Unknown = 0,
+    /// 加速度计，单位 m/s²
+    Accelerometer = 1,
+    /// 陀螺仪，单位 rad/s
+    Gyroscope = 2,
+    /// 左 Joy-Con 加速度计
+    AccelerometerLeft = 3,
+    /// 左 Joy-Con 陀螺仪
+    GyroscopeLeft = 4,
+    /// 右 Joy-Con 加速度计
+    AccelerometerRight = 5,
+    /// 右 Joy-Con 陀螺仪
+    GyroscopeRight = 6,
+}
+
+bitflags! {
+    /// 摇杆帽（方向键）状态
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct JoystickHat: u8 {
+        const CENTERED = 0x00;
+        const UP = 0x01;
+        const RIGHT = 0x02;
+        const DOWN = 0x04;
+        const LEFT = 0x08;
+    }
+}
@@ -1,9 +1,13 @@
+pub mod clock;
+pub mod color;
 pub mod fallible;
 pub mod frect;
+pub mod gamepad;
 pub mod id;
 pub mod keycode;
 pub mod keymod;
 pub mod mouse;
+pub mod pen;
 pub mod rect;
 pub mod scancode;
 pub mod spatial;
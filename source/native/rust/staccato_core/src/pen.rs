@@ -0,0 +1,38 @@
+use bitflags::bitflags;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+bitflags! {
+    /// 笔的输入状态，数值与 SDL_PenInputFlags 一致
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct PenInputFlags: u32 {
+        /// 笔尖接触到了表面
+        const DOWN = 1 << 0;
+        const BUTTON_1 = 1 << 1;
+        const BUTTON_2 = 1 << 2;
+        const BUTTON_3 = 1 << 3;
+        const BUTTON_4 = 1 << 4;
+        const BUTTON_5 = 1 << 5;
+        /// 正在使用橡皮擦端
+        const ERASER_TIP = 1 << 30;
+    }
+}
+
+/// 笔的轴，数值与 SDL_PenAxis 一致
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, FromPrimitive, ToPrimitive)]
+pub enum PenAxis {
+    /// 压力，范围 `[0, 1]`
+    Pressure = 0,
+    /// 水平倾斜角度，范围 `[-90, 90]`
+    TiltX = 1,
+    /// 垂直倾斜角度，范围 `[-90, 90]`
+    TiltY = 2,
+    /// 与表面的距离，范围 `[0, 1]`
+    Distance = 3,
+    /// 旋转角度，范围 `[-180, 180)`
+    Rotation = 4,
+    /// 滑块位置，范围 `[0, 1]`
+    Slider = 5,
+    /// 切向压力，范围 `[-1, 1]`
+    TangentialPressure = 6,
+}
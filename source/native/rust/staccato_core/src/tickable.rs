@@ -1,17 +1,38 @@
+use crate::clock::ClockChannel;
 use crate::fallible::Fallible;
 use std::fmt::Debug;
 
+/// 一帧的时间信息，传递给 `update` 与 `post_update`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FrameTime {
+    /// 各个时钟在这一帧经过的时间（纳秒），以 `ClockChannel` 为下标
+    pub deltas: [u64; ClockChannel::COUNT],
+    /// 固定步长累加器中剩余时间占一个步长的比例，范围 `[0, 1)`，用于在两次物理状态之间插值
+    pub alpha: f64,
+}
+
+impl FrameTime {
+    /// 指定时钟在这一帧经过的时间（纳秒）
+    pub fn elapse_ns(&self, channel: ClockChannel) -> u64 {
+        self.deltas[channel as usize]
+    }
+}
+
 /// 定义逻辑生命周期
 pub trait Tickable: Fallible + Debug {
     /// 准备阶段：处理输入、同步网络、重置状态
+    ///
+    /// `elapse_ns` 为真实时钟经过的时间
     fn pre_update(&mut self, elapse_ns: u64) -> Result<(), Self::Error>;
 
     /// 物理阶段：固定步长更新
+    ///
+    /// `elapse_ns` 为游戏时钟上的固定步长，游戏时钟暂停时不会被调用
     fn fixed_update(&mut self, elapse_ns: u64) -> Result<(), Self::Error>;
 
     /// 逻辑阶段：处理业务逻辑
-    fn update(&mut self, elapse_ns: u64) -> Result<(), Self::Error>;
+    fn update(&mut self, time: &FrameTime) -> Result<(), Self::Error>;
 
     /// 后处理阶段：相机跟随、动画同步、渲染前最后的调整
-    fn post_update(&mut self, elapse_ns: u64) -> Result<(), Self::Error>;
+    fn post_update(&mut self, time: &FrameTime) -> Result<(), Self::Error>;
 }
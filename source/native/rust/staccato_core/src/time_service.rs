@@ -1,3 +1,4 @@
+use crate::clock::Clock;
 use std::fmt::Debug;
 use std::time::Instant;
 
@@ -30,3 +31,84 @@ impl TimeService for StdTimeService {
         elapsed.as_nanos().try_into().unwrap()
     }
 }
+
+/// A clock that only moves when told to. Used by tests and replays.
+#[derive(Debug, Clone, Default)]
+pub struct ManualTimeService {
+    clock: Clock,
+}
+
+impl ManualTimeService {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn starting_at(ns: u64) -> Self {
+        let mut time = Self::new();
+        time.set(ns);
+        time
+    }
+
+    /// Jump to `ns`, ignoring pause and scale.
+    pub fn set(&mut self, ns: u64) {
+        self.clock.set_total_ns(ns);
+    }
+
+    /// Move forward by `ns * scale`, does nothing while paused.
+    pub fn advance(&mut self, ns: u64) {
+        self.clock.advance(ns);
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.clock.scale()
+    }
+
+    /// Negative scales are treated as zero, NaN and infinite scales are ignored.
+    pub fn set_scale(&mut self, scale: f64) {
+        self.clock.set_scale(scale);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.clock.is_paused()
+    }
+
+    pub fn pause(&mut self) {
+        self.clock.pause();
+    }
+
+    pub fn resume(&mut self) {
+        self.clock.resume();
+    }
+}
+
+impl TimeService for ManualTimeService {
+    fn get_timestamp_ns(&self) -> u64 {
+        self.clock.total_ns()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manual_clock_honors_pause_and_scale() {
+        let mut time = ManualTimeService::starting_at(100);
+
+        time.advance(50);
+        assert_eq!(time.get_timestamp_ns(), 150);
+
+        time.pause();
+        time.advance(50);
+        assert_eq!(time.get_timestamp_ns(), 150);
+
+        time.resume();
+        time.set_scale(0.5);
+        time.advance(3);
+        time.advance(3);
+        assert_eq!(time.get_timestamp_ns(), 153);
+
+        time.set(0);
+        assert_eq!(time.get_timestamp_ns(), 0);
+    }
+}
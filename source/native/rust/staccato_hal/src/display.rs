@@ -0,0 +1,182 @@
+use crate::error::SdlError;
+use sdl3_sys::rect::SDL_Rect;
+use sdl3_sys::stdinc::SDL_free;
+use sdl3_sys::video::{
+    SDL_DisplayID, SDL_DisplayMode, SDL_DisplayOrientation, SDL_GetCurrentDisplayMode,
+    SDL_GetCurrentDisplayOrientation, SDL_GetDesktopDisplayMode, SDL_GetDisplayBounds,
+    SDL_GetDisplayContentScale, SDL_GetDisplayName, SDL_GetDisplayUsableBounds, SDL_GetDisplays,
+    SDL_GetFullscreenDisplayModes, SDL_GetNaturalDisplayOrientation, SDL_GetPrimaryDisplay,
+};
+use smol_str::{SmolStr, ToSmolStr};
+use staccato_core::fallible::Fallible;
+use staccato_core::rect::{Rect, Size};
+use staccato_platform_api::display::{DisplayId, DisplayMode, DisplayOrientation, DisplayService};
+use std::ffi::CStr;
+
+pub(crate) fn display_mode_from_sdl(mode: &SDL_DisplayMode) -> DisplayMode {
+    DisplayMode {
+        display_id: mode.displayID.0.into(),
+        format: mode.format.0 as u32,
+        size: Size::new(mode.w, mode.h),
+        pixel_density: mode.pixel_density,
+        refresh_rate: mode.refresh_rate,
+        refresh_rate_numerator: mode.refresh_rate_numerator,
+        refresh_rate_denominator: mode.refresh_rate_denominator,
+    }
+}
+
+pub(crate) fn display_orientation_from_sdl(
+    orientation: SDL_DisplayOrientation,
+) -> DisplayOrientation {
+    match orientation {
+        SDL_DisplayOrientation::LANDSCAPE => DisplayOrientation::Landscape,
+        SDL_DisplayOrientation::LANDSCAPE_FLIPPED => DisplayOrientation::LandscapeFlipped,
+        SDL_DisplayOrientation::PORTRAIT => DisplayOrientation::Portrait,
+        SDL_DisplayOrientation::PORTRAIT_FLIPPED => DisplayOrientation::PortraitFlipped,
+        _ => DisplayOrientation::Unknown,
+    }
+}
+
+fn sdl_display_id(display_id: DisplayId) -> SDL_DisplayID {
+    SDL_DisplayID(display_id as u32)
+}
+
+fn rect_from_sdl(rect: &SDL_Rect) -> Rect {
+    Rect::new(rect.x, rect.y, rect.w, rect.h)
+}
+
+/// `DisplayService` backed by SDL. Must stay on the main thread.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SdlDisplayService;
+
+impl SdlDisplayService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Fallible for SdlDisplayService {
+    type Error = SdlError;
+}
+
+impl DisplayService for SdlDisplayService {
+    fn displays(&self) -> Result<Vec<DisplayId>, Self::Error> {
+        let mut count = 0;
+        unsafe {
+            let displays = SDL_GetDisplays(&mut count);
+            if displays.is_null() {
+                return Err(SdlError::sdl_err("failed to get displays"));
+            }
+
+            let result =
+                std::slice::from_raw_parts(displays, usize::try_from(count).unwrap_or_default())
+                    .iter()
+                    .map(|display| display.0.into())
+                    .collect();
+            SDL_free(displays.cast());
+            Ok(result)
+        }
+    }
+
+    fn primary_display(&self) -> Result<DisplayId, Self::Error> {
+        let display = unsafe { SDL_GetPrimaryDisplay() };
+        if display.0 == 0 {
+            return Err(SdlError::sdl_err("failed to get primary display"));
+        }
+        Ok(display.0.into())
+    }
+
+    fn name(&self, display_id: DisplayId) -> Result<SmolStr, Self::Error> {
+        unsafe {
+            let name = SDL_GetDisplayName(sdl_display_id(display_id));
+            if name.is_null() {
+                return Err(SdlError::sdl_err("failed to get display name"));
+            }
+            Ok(CStr::from_ptr(name).to_string_lossy().to_smolstr())
+        }
+    }
+
+    fn bounds(&self, display_id: DisplayId) -> Result<Rect, Self::Error> {
+        let mut rect = SDL_Rect {
+            x: 0,
+            y: 0,
+            w: 0,
+            h: 0,
+        };
+        unsafe {
+            if !SDL_GetDisplayBounds(sdl_display_id(display_id), &mut rect) {
+                return Err(SdlError::sdl_err("failed to get display bounds"));
+            }
+        }
+        Ok(rect_from_sdl(&rect))
+    }
+
+    fn usable_bounds(&self, display_id: DisplayId) -> Result<Rect, Self::Error> {
+        let mut rect = SDL_Rect {
+            x: 0,
+            y: 0,
+            w: 0,
+            h: 0,
+        };
+        unsafe {
+            if !SDL_GetDisplayUsableBounds(sdl_display_id(display_id), &mut rect) {
+                return Err(SdlError::sdl_err("failed to get display usable bounds"));
+            }
+        }
+        Ok(rect_from_sdl(&rect))
+    }
+
+    fn content_scale(&self, display_id: DisplayId) -> Result<f32, Self::Error> {
+        let scale = unsafe { SDL_GetDisplayContentScale(sdl_display_id(display_id)) };
+        if scale <= 0.0 {
+            return Err(SdlError::sdl_err("failed to get display content scale"));
+        }
+        Ok(scale)
+    }
+
+    fn orientation(&self, display_id: DisplayId) -> DisplayOrientation {
+        display_orientation_from_sdl(unsafe {
+            SDL_GetCurrentDisplayOrientation(sdl_display_id(display_id))
+        })
+    }
+
+    fn natural_orientation(&self, display_id: DisplayId) -> DisplayOrientation {
+        display_orientation_from_sdl(unsafe {
+            SDL_GetNaturalDisplayOrientation(sdl_display_id(display_id))
+        })
+    }
+
+    fn desktop_mode(&self, display_id: DisplayId) -> Result<DisplayMode, Self::Error> {
+        match unsafe { SDL_GetDesktopDisplayMode(sdl_display_id(display_id)).as_ref() } {
+            Some(mode) => Ok(display_mode_from_sdl(mode)),
+            None => Err(SdlError::sdl_err("failed to get desktop display mode")),
+        }
+    }
+
+    fn current_mode(&self, display_id: DisplayId) -> Result<DisplayMode, Self::Error> {
+        match unsafe { SDL_GetCurrentDisplayMode(sdl_display_id(display_id)).as_ref() } {
+            Some(mode) => Ok(display_mode_from_sdl(mode)),
+            None => Err(SdlError::sdl_err("failed to get current display mode")),
+        }
+    }
+
+    fn fullscreen_modes(&self, display_id: DisplayId) -> Result<Vec<DisplayMode>, Self::Error> {
+        let mut count = 0;
+        unsafe {
+            let modes = SDL_GetFullscreenDisplayModes(sdl_display_id(display_id), &mut count);
+            if modes.is_null() {
+                return Err(SdlError::sdl_err("failed to get fullscreen display modes"));
+            }
+
+            // the modes live in the same allocation as the array, already sorted by SDL
+            let result =
+                std::slice::from_raw_parts(modes, usize::try_from(count).unwrap_or_default())
+                    .iter()
+                    .filter_map(|mode| mode.as_ref())
+                    .map(display_mode_from_sdl)
+                    .collect();
+            SDL_free(modes.cast());
+            Ok(result)
+        }
+    }
+}
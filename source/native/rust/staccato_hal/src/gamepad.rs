@@ -0,0 +1,189 @@
+use crate::error::SdlError;
+use num_traits::ToPrimitive;
+use sdl3_sys::gamepad::{
+    SDL_AddGamepadMapping, SDL_CloseGamepad, SDL_Gamepad, SDL_GamepadAxis, SDL_GamepadButton,
+    SDL_GamepadConnected, SDL_GetGamepadAxis, SDL_GetGamepadButton, SDL_GetGamepadID,
+    SDL_GetGamepadMapping, SDL_GetGamepadName, SDL_GetGamepads, SDL_IsGamepad, SDL_OpenGamepad,
+    SDL_RumbleGamepad, SDL_RumbleGamepadTriggers, SDL_SetGamepadSensorEnabled,
+};
+use sdl3_sys::joystick::SDL_JoystickID;
+use sdl3_sys::sensor::SDL_SensorType;
+use sdl3_sys::stdinc::SDL_free;
+use smol_str::{SmolStr, ToSmolStr};
+use staccato_core::fallible::Fallible;
+use staccato_core::gamepad::{GamepadAxis, GamepadButton, SensorType};
+use staccato_core::id::HasId;
+use staccato_shared::id::JoystickId;
+use std::ffi::{CStr, CString};
+use std::ptr::NonNull;
+
+fn sdl_joystick_id(id: JoystickId) -> Result<SDL_JoystickID, SdlError> {
+    u32::try_from(id)
+        .map(SDL_JoystickID)
+        .map_err(|_| SdlError::sdl_err("joystick id out of range"))
+}
+
+/// An opened gamepad, closed on drop. Must stay on the main thread.
+#[derive(Debug)]
+pub struct Gamepad {
+    gamepad: NonNull<SDL_Gamepad>,
+    id: JoystickId,
+}
+
+impl HasId for Gamepad {
+    type Id = JoystickId;
+    fn id(&self) -> Self::Id {
+        self.id
+    }
+}
+
+impl Fallible for Gamepad {
+    type Error = SdlError;
+}
+
+impl Gamepad {
+    /// Ids of the gamepads currently connected.
+    pub fn connected() -> Result<Vec<JoystickId>, SdlError> {
+        let mut count = 0;
+        unsafe {
+            let ids = SDL_GetGamepads(&mut count);
+            if ids.is_null() {
+                return Err(SdlError::sdl_err("failed to get gamepads"));
+            }
+
+            let len = usize::try_from(count).unwrap_or_default();
+            let result = std::slice::from_raw_parts(ids, len)
+                .iter()
+                .map(|id| id.0.into())
+                .collect();
+            SDL_free(ids.cast());
+            Ok(result)
+        }
+    }
+
+    /// Whether the joystick has a gamepad mapping.
+    pub fn is_gamepad(id: JoystickId) -> bool {
+        match sdl_joystick_id(id) {
+            Ok(id) => unsafe { SDL_IsGamepad(id) },
+            Err(_) => false,
+        }
+    }
+
+    /// Add or update a mapping in the SDL_GameControllerDB format. Returns true if added.
+    pub fn add_mapping(mapping: &str) -> Result<bool, SdlError> {
+        let mapping =
+            CString::new(mapping).map_err(|_| SdlError::sdl_err("invalid mapping string"))?;
+        match unsafe { SDL_AddGamepadMapping(mapping.as_ptr()) } {
+            1 => Ok(true),
+            0 => Ok(false),
+            _ => Err(SdlError::sdl_err("failed to add gamepad mapping")),
+        }
+    }
+
+    pub fn open(id: JoystickId) -> Result<Self, SdlError> {
+        let gamepad = unsafe { SDL_OpenGamepad(sdl_joystick_id(id)?) };
+
+        match NonNull::new(gamepad) {
+            Some(gamepad) => Ok(Self { gamepad, id }),
+            None => Err(SdlError::sdl_err("failed to open gamepad")),
+        }
+    }
+
+    pub fn as_ptr(&self) -> *mut SDL_Gamepad {
+        self.gamepad.as_ptr()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        unsafe { SDL_GamepadConnected(self.as_ptr()) }
+    }
+
+    pub fn instance_id(&self) -> JoystickId {
+        unsafe { SDL_GetGamepadID(self.as_ptr()).0.into() }
+    }
+
+    pub fn name(&self) -> SmolStr {
+        unsafe {
+            let name = SDL_GetGamepadName(self.as_ptr());
+            if name.is_null() {
+                "".to_smolstr()
+            } else {
+                CStr::from_ptr(name).to_string_lossy().to_smolstr()
+            }
+        }
+    }
+
+    /// The current mapping in the SDL_GameControllerDB format, `None` if there is no mapping.
+    pub fn mapping(&self) -> Option<String> {
+        unsafe {
+            let mapping = SDL_GetGamepadMapping(self.as_ptr());
+            if mapping.is_null() {
+                return None;
+            }
+
+            let result = CStr::from_ptr(mapping).to_string_lossy().into_owned();
+            SDL_free(mapping.cast());
+            Some(result)
+        }
+    }
+
+    pub fn axis(&self, axis: GamepadAxis) -> i16 {
+        let axis = SDL_GamepadAxis(axis.to_i32().unwrap_or(-1));
+        unsafe { SDL_GetGamepadAxis(self.as_ptr(), axis) }
+    }
+
+    pub fn button(&self, button: GamepadButton) -> bool {
+        let button = SDL_GamepadButton(button.to_i32().unwrap_or(-1));
+        unsafe { SDL_GetGamepadButton(self.as_ptr(), button) }
+    }
+
+    /// Start a rumble effect, a new call replaces the previous one. Zero intensity stops it.
+    pub fn rumble(
+        &mut self,
+        low_frequency: u16,
+        high_frequency: u16,
+        duration_ms: u32,
+    ) -> Result<(), SdlError> {
+        unsafe {
+            if !SDL_RumbleGamepad(self.as_ptr(), low_frequency, high_frequency, duration_ms) {
+                return Err(SdlError::sdl_err("failed to rumble gamepad"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Rumble the triggers (Xbox One impulse triggers).
+    pub fn rumble_triggers(
+        &mut self,
+        left: u16,
+        right: u16,
+        duration_ms: u32,
+    ) -> Result<(), SdlError> {
+        unsafe {
+            if !SDL_RumbleGamepadTriggers(self.as_ptr(), left, right, duration_ms) {
+                return Err(SdlError::sdl_err("failed to rumble gamepad triggers"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Enable or disable `RawEvent::GamepadSensor` reporting for `sensor`.
+    pub fn set_sensor_enabled(
+        &mut self,
+        sensor: SensorType,
+        enabled: bool,
+    ) -> Result<(), SdlError> {
+        let sensor = SDL_SensorType(sensor.to_i32().unwrap_or(-1));
+        unsafe {
+            if !SDL_SetGamepadSensorEnabled(self.as_ptr(), sensor, enabled) {
+                return Err(SdlError::sdl_err("failed to set gamepad sensor state"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Gamepad {
+    fn drop(&mut self) {
+        unsafe { SDL_CloseGamepad(self.as_ptr()) }
+    }
+}
@@ -1,6 +1,15 @@
+pub mod display;
 pub mod error;
+pub mod gamepad;
+pub mod sdl_clipboard;
 pub mod sdl_event;
 pub mod sdl_event_source;
+pub mod sdl_time_service;
+pub mod sdl_user_event;
+pub mod surface_options;
+pub mod text_input;
 pub mod wgpu_context;
+pub mod wgpu_offscreen;
 pub mod wgpu_window;
+pub mod wgpu_windows;
 pub mod window;
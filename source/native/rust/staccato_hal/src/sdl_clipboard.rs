@@ -0,0 +1,211 @@
+use crate::error::SdlError;
+use sdl3_sys::clipboard::{
+    SDL_ClearClipboardData, SDL_GetClipboardData, SDL_GetClipboardMimeTypes, SDL_GetClipboardText,
+    SDL_GetPrimarySelectionText, SDL_HasClipboardData, SDL_HasClipboardText,
+    SDL_HasPrimarySelectionText, SDL_SetClipboardData, SDL_SetClipboardText,
+    SDL_SetPrimarySelectionText,
+};
+use sdl3_sys::init::{SDL_InitFlags, SDL_WasInit};
+use sdl3_sys::stdinc::SDL_free;
+use smol_str::{SmolStr, ToSmolStr};
+use staccato_core::fallible::Fallible;
+use staccato_platform_api::clipboard::{Clipboard, ClipboardContent};
+use std::ffi::{CStr, CString, c_char, c_void};
+
+/// Take ownership of a string allocated by SDL.
+unsafe fn take_sdl_string(ptr: *mut c_char, msg: &str) -> Result<String, SdlError> {
+    if ptr.is_null() {
+        return Err(SdlError::sdl_err(msg));
+    }
+
+    unsafe {
+        let text = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+        SDL_free(ptr.cast());
+        Ok(text)
+    }
+}
+
+/// Called by SDL when another application pastes, `userdata` is the offered `ClipboardContent`.
+unsafe extern "C" fn clipboard_data(
+    userdata: *mut c_void,
+    mime_type: *const c_char,
+    size: *mut usize,
+) -> *const c_void {
+    let content = unsafe { &*userdata.cast::<ClipboardContent>() };
+    let data = if mime_type.is_null() {
+        None
+    } else {
+        unsafe { CStr::from_ptr(mime_type) }
+            .to_str()
+            .ok()
+            .and_then(|mime_type| content.get(mime_type))
+    };
+
+    // the data lives until `clipboard_cleanup`
+    match data {
+        Some(data) => {
+            unsafe { *size = data.len() };
+            data.as_ptr().cast()
+        }
+        None => {
+            unsafe { *size = 0 };
+            std::ptr::null()
+        }
+    }
+}
+
+unsafe extern "C" fn clipboard_cleanup(userdata: *mut c_void) {
+    drop(unsafe { Box::from_raw(userdata.cast::<ClipboardContent>()) });
+}
+
+/// `Clipboard` backed by SDL. Must stay on the main thread.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SdlClipboard;
+
+impl SdlClipboard {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Fallible for SdlClipboard {
+    type Error = SdlError;
+}
+
+impl Clipboard for SdlClipboard {
+    fn text(&self) -> Result<String, Self::Error> {
+        unsafe { take_sdl_string(SDL_GetClipboardText(), "failed to get clipboard text") }
+    }
+
+    fn set_text(&mut self, text: &str) -> Result<(), Self::Error> {
+        let text = CString::new(text).map_err(|_| SdlError::sdl_err("invalid clipboard text"))?;
+        unsafe {
+            if !SDL_SetClipboardText(text.as_ptr()) {
+                return Err(SdlError::sdl_err("failed to set clipboard text"));
+            }
+        }
+        Ok(())
+    }
+
+    fn has_text(&self) -> bool {
+        unsafe { SDL_HasClipboardText() }
+    }
+
+    fn primary_selection_text(&self) -> Result<String, Self::Error> {
+        unsafe {
+            take_sdl_string(
+                SDL_GetPrimarySelectionText(),
+                "failed to get primary selection text",
+            )
+        }
+    }
+
+    fn set_primary_selection_text(&mut self, text: &str) -> Result<(), Self::Error> {
+        let text =
+            CString::new(text).map_err(|_| SdlError::sdl_err("invalid primary selection text"))?;
+        unsafe {
+            if !SDL_SetPrimarySelectionText(text.as_ptr()) {
+                return Err(SdlError::sdl_err("failed to set primary selection text"));
+            }
+        }
+        Ok(())
+    }
+
+    fn has_primary_selection_text(&self) -> bool {
+        unsafe { SDL_HasPrimarySelectionText() }
+    }
+
+    fn data(&self, mime_type: &str) -> Result<Option<Vec<u8>>, Self::Error> {
+        let mime_type =
+            CString::new(mime_type).map_err(|_| SdlError::sdl_err("invalid mime type"))?;
+
+        unsafe {
+            if !SDL_HasClipboardData(mime_type.as_ptr()) {
+                return Ok(None);
+            }
+
+            let mut size = 0;
+            let data = SDL_GetClipboardData(mime_type.as_ptr(), &mut size);
+            if data.is_null() {
+                return Err(SdlError::sdl_err("failed to get clipboard data"));
+            }
+
+            let result = std::slice::from_raw_parts(data.cast::<u8>(), size).to_vec();
+            SDL_free(data);
+            Ok(Some(result))
+        }
+    }
+
+    fn set_data(&mut self, content: ClipboardContent) -> Result<(), Self::Error> {
+        if content.is_empty() {
+            return self.clear();
+        }
+
+        let mime_types = content
+            .mime_types()
+            .map(CString::new)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| SdlError::sdl_err("invalid mime type"))?;
+        // SDL copies the list
+        let mut mime_type_ptrs: Vec<*const c_char> = mime_types
+            .iter()
+            .map(|mime_type| mime_type.as_ptr())
+            .collect();
+
+        // SDL only returns without taking `userdata` when video is not initialized, the
+        // parameters are always valid here
+        if unsafe { SDL_WasInit(SDL_InitFlags::VIDEO) } != SDL_InitFlags::VIDEO {
+            return Err(SdlError::sdl_err("video subsystem is not initialized"));
+        }
+        let userdata = Box::into_raw(Box::new(content));
+
+        unsafe {
+            if !SDL_SetClipboardData(
+                Some(clipboard_data),
+                Some(clipboard_cleanup),
+                userdata.cast(),
+                mime_type_ptrs.as_mut_ptr(),
+                mime_type_ptrs.len(),
+            ) {
+                // SDL owns `userdata` past the check above and frees it through `clipboard_cleanup`
+                return Err(SdlError::sdl_err("failed to set clipboard data"));
+            }
+        }
+        Ok(())
+    }
+
+    fn has_data(&self, mime_type: &str) -> bool {
+        match CString::new(mime_type) {
+            Ok(mime_type) => unsafe { SDL_HasClipboardData(mime_type.as_ptr()) },
+            Err(_) => false,
+        }
+    }
+
+    fn mime_types(&self) -> Result<Vec<SmolStr>, Self::Error> {
+        let mut count = 0;
+        unsafe {
+            let mime_types = SDL_GetClipboardMimeTypes(&mut count);
+            if mime_types.is_null() {
+                return Err(SdlError::sdl_err("failed to get clipboard mime types"));
+            }
+
+            // the strings live in the same allocation as the array
+            let result = std::slice::from_raw_parts(mime_types, count)
+                .iter()
+                .filter(|mime_type| !mime_type.is_null())
+                .map(|mime_type| CStr::from_ptr(*mime_type).to_string_lossy().to_smolstr())
+                .collect();
+            SDL_free(mime_types.cast());
+            Ok(result)
+        }
+    }
+
+    fn clear(&mut self) -> Result<(), Self::Error> {
+        unsafe {
+            if !SDL_ClearClipboardData() {
+                return Err(SdlError::sdl_err("failed to clear clipboard"));
+            }
+        }
+        Ok(())
+    }
+}
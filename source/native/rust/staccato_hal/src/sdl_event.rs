@@ -1,15 +1,23 @@
 use std::ffi::{CStr, c_char};
+use std::sync::Arc;
 
+use crate::display::display_orientation_from_sdl;
+use crate::sdl_user_event::{is_staccato_user_event, take_payload};
 use num_traits::cast::FromPrimitive;
 use sdl3_sys::events::{SDL_Event, SDL_EventType};
+use sdl3_sys::video::SDL_DisplayOrientation;
+use smol_str::{SmolStr, ToSmolStr};
 use staccato_core::frect::FPoint;
+use staccato_core::gamepad::{GamepadAxis, GamepadButton, JoystickHat, SensorType};
 use staccato_core::keycode::KeyCode;
 use staccato_core::keymod::Keymod;
 use staccato_core::mouse::{Button, MouseWheelDirection};
+use staccato_core::pen::{PenAxis, PenInputFlags};
 use staccato_core::rect::Point;
 use staccato_core::scancode::Scancode;
 use staccato_shared::event::{
-    AppEvent, DeviceOperation, Event, InlineText, RawEvent, UserOperation, WindowEvent,
+    AppEvent, DeviceOperation, DisplayEvent, Event, GamepadDeviceOperation, GesturePhase,
+    InlineText, RawEvent, TouchOperation, UserOperation, WindowEvent,
 };
 
 fn inline_text_from_ptr(ptr: *const c_char) -> InlineText {
@@ -21,6 +29,30 @@ fn inline_text_from_ptr(ptr: *const c_char) -> InlineText {
     InlineText::from_bytes(bytes)
 }
 
+/// Copy a whole C string, null becomes an empty string.
+fn smol_str_from_ptr(ptr: *const c_char) -> SmolStr {
+    if ptr.is_null() {
+        return SmolStr::default();
+    }
+
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .to_smolstr()
+}
+
+/// Copy an array of C strings, null entries become empty strings.
+unsafe fn strings_from_ptr(ptr: *const *const c_char, count: i32) -> Arc<[SmolStr]> {
+    if ptr.is_null() {
+        return Vec::new().into();
+    }
+
+    let count = usize::try_from(count).unwrap_or_default();
+    unsafe { std::slice::from_raw_parts(ptr, count) }
+        .iter()
+        .map(|text| smol_str_from_ptr(*text))
+        .collect()
+}
+
 fn button_from_index(button: u8) -> Button {
     match button {
         1 => Button::Left,
@@ -32,6 +64,38 @@ fn button_from_index(button: u8) -> Button {
     }
 }
 
+fn user_operation(down: bool) -> UserOperation {
+    if down {
+        UserOperation::Down
+    } else {
+        UserOperation::Up
+    }
+}
+
+/// Like `translate_event`, but texts longer than `INLINE_TEXT_MAX` spill into several
+/// `TextInput` events instead of being truncated.
+pub fn translate_event_into(sdl: SDL_Event, out: &mut Vec<Event>) {
+    if SDL_EventType(unsafe { sdl.r#type }) == SDL_EventType::TEXT_INPUT {
+        let sdl = unsafe { &sdl.text };
+
+        if !sdl.text.is_null() {
+            let bytes = unsafe { CStr::from_ptr(sdl.text).to_bytes() };
+            for text in InlineText::chunks(bytes) {
+                out.push(Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::TextInput {
+                        window_id: sdl.windowID.0.into(),
+                        text,
+                    },
+                });
+            }
+            return;
+        }
+    }
+
+    out.push(translate_event(sdl));
+}
+
 pub fn translate_event(sdl: SDL_Event) -> Event {
     let sdl_type = SDL_EventType(unsafe { sdl.r#type });
 
@@ -203,7 +267,7 @@ pub fn translate_event(sdl: SDL_Event) -> Event {
             }
             SDL_EventType::TEXT_EDITING => {
                 let sdl = &sdl.edit;
-                let text = inline_text_from_ptr(sdl.text);
+                let text = smol_str_from_ptr(sdl.text);
 
                 Event {
                     ns_timestamp: sdl.timestamp,
@@ -227,6 +291,21 @@ pub fn translate_event(sdl: SDL_Event) -> Event {
                     },
                 }
             }
+            SDL_EventType::TEXT_EDITING_CANDIDATES => {
+                let sdl = &sdl.edit_candidates;
+
+                let candidates = strings_from_ptr(sdl.candidates, sdl.num_candidates);
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::TextEditingCandidates {
+                        window_id: sdl.windowID.0.into(),
+                        candidates,
+                        selected: sdl.selected_candidate,
+                        horizontal: sdl.horizontal,
+                    },
+                }
+            }
             SDL_EventType::MOUSE_MOTION => {
                 let sdl = &sdl.motion;
 
@@ -280,6 +359,275 @@ pub fn translate_event(sdl: SDL_Event) -> Event {
                     },
                 }
             }
+            SDL_EventType::JOYSTICK_ADDED | SDL_EventType::JOYSTICK_REMOVED => {
+                let sdl = &sdl.jdevice;
+                let operation = if sdl_type == SDL_EventType::JOYSTICK_ADDED {
+                    DeviceOperation::Added
+                } else {
+                    DeviceOperation::Removed
+                };
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::JoystickDevice {
+                        joystick_id: sdl.which.0.into(),
+                        operation,
+                    },
+                }
+            }
+            SDL_EventType::JOYSTICK_AXIS_MOTION => {
+                let sdl = &sdl.jaxis;
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::JoystickAxisMotion {
+                        joystick_id: sdl.which.0.into(),
+                        axis: sdl.axis,
+                        value: sdl.value,
+                    },
+                }
+            }
+            SDL_EventType::JOYSTICK_HAT_MOTION => {
+                let sdl = &sdl.jhat;
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::JoystickHatMotion {
+                        joystick_id: sdl.which.0.into(),
+                        hat: sdl.hat,
+                        state: JoystickHat::from_bits_truncate(sdl.value),
+                    },
+                }
+            }
+            SDL_EventType::JOYSTICK_BUTTON_DOWN | SDL_EventType::JOYSTICK_BUTTON_UP => {
+                let sdl = &sdl.jbutton;
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::JoystickButton {
+                        joystick_id: sdl.which.0.into(),
+                        button: sdl.button,
+                        down: sdl.down,
+                        user_operation: user_operation(sdl.down),
+                    },
+                }
+            }
+            SDL_EventType::GAMEPAD_ADDED
+            | SDL_EventType::GAMEPAD_REMOVED
+            | SDL_EventType::GAMEPAD_REMAPPED => {
+                let sdl = &sdl.gdevice;
+                let operation = match sdl_type {
+                    SDL_EventType::GAMEPAD_ADDED => GamepadDeviceOperation::Added,
+                    SDL_EventType::GAMEPAD_REMOVED => GamepadDeviceOperation::Removed,
+                    _ => GamepadDeviceOperation::Remapped,
+                };
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::GamepadDevice {
+                        joystick_id: sdl.which.0.into(),
+                        operation,
+                    },
+                }
+            }
+            SDL_EventType::GAMEPAD_AXIS_MOTION => {
+                let sdl = &sdl.gaxis;
+
+                match GamepadAxis::from_u8(sdl.axis) {
+                    Some(axis) => Event {
+                        ns_timestamp: sdl.timestamp,
+                        raw: RawEvent::GamepadAxisMotion {
+                            joystick_id: sdl.which.0.into(),
+                            axis,
+                            value: sdl.value,
+                        },
+                    },
+                    None => Event {
+                        ns_timestamp: sdl.timestamp,
+                        raw: RawEvent::Unknown {
+                            type_id: sdl.r#type.into(),
+                        },
+                    },
+                }
+            }
+            SDL_EventType::GAMEPAD_BUTTON_DOWN | SDL_EventType::GAMEPAD_BUTTON_UP => {
+                let sdl = &sdl.gbutton;
+
+                match GamepadButton::from_u8(sdl.button) {
+                    Some(button) => Event {
+                        ns_timestamp: sdl.timestamp,
+                        raw: RawEvent::GamepadButton {
+                            joystick_id: sdl.which.0.into(),
+                            button,
+                            down: sdl.down,
+                            user_operation: user_operation(sdl.down),
+                        },
+                    },
+                    None => Event {
+                        ns_timestamp: sdl.timestamp,
+                        raw: RawEvent::Unknown {
+                            type_id: sdl.r#type.into(),
+                        },
+                    },
+                }
+            }
+            SDL_EventType::GAMEPAD_TOUCHPAD_DOWN
+            | SDL_EventType::GAMEPAD_TOUCHPAD_MOTION
+            | SDL_EventType::GAMEPAD_TOUCHPAD_UP => {
+                let sdl = &sdl.gtouchpad;
+                let operation = match sdl_type {
+                    SDL_EventType::GAMEPAD_TOUCHPAD_DOWN => TouchOperation::Down,
+                    SDL_EventType::GAMEPAD_TOUCHPAD_UP => TouchOperation::Up,
+                    _ => TouchOperation::Motion,
+                };
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::GamepadTouchpad {
+                        joystick_id: sdl.which.0.into(),
+                        touchpad: sdl.touchpad,
+                        finger: sdl.finger,
+                        position: FPoint::new(sdl.x, sdl.y),
+                        pressure: sdl.pressure,
+                        operation,
+                    },
+                }
+            }
+            SDL_EventType::GAMEPAD_SENSOR_UPDATE => {
+                let sdl = &sdl.gsensor;
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::GamepadSensor {
+                        joystick_id: sdl.which.0.into(),
+                        sensor: SensorType::from_i32(sdl.sensor).unwrap_or(SensorType::Unknown),
+                        data: sdl.data,
+                        sensor_ns_timestamp: sdl.sensor_timestamp,
+                    },
+                }
+            }
+            SDL_EventType::FINGER_DOWN
+            | SDL_EventType::FINGER_UP
+            | SDL_EventType::FINGER_MOTION
+            | SDL_EventType::FINGER_CANCELED => {
+                let sdl = &sdl.tfinger;
+                let operation = match sdl_type {
+                    SDL_EventType::FINGER_DOWN => TouchOperation::Down,
+                    SDL_EventType::FINGER_UP => TouchOperation::Up,
+                    SDL_EventType::FINGER_MOTION => TouchOperation::Motion,
+                    _ => TouchOperation::Canceled,
+                };
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::Finger {
+                        window_id: sdl.windowID.0.into(),
+                        touch_id: sdl.touchID.0,
+                        finger_id: sdl.fingerID.0,
+                        position: FPoint::new(sdl.x, sdl.y),
+                        relative: FPoint::new(sdl.dx, sdl.dy),
+                        pressure: sdl.pressure,
+                        operation,
+                    },
+                }
+            }
+            SDL_EventType::PINCH_BEGIN | SDL_EventType::PINCH_UPDATE | SDL_EventType::PINCH_END => {
+                let sdl = &sdl.pinch;
+                let phase = match sdl_type {
+                    SDL_EventType::PINCH_BEGIN => GesturePhase::Begin,
+                    SDL_EventType::PINCH_UPDATE => GesturePhase::Update,
+                    _ => GesturePhase::End,
+                };
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::Pinch {
+                        window_id: sdl.windowID.0.into(),
+                        scale: sdl.scale,
+                        phase,
+                    },
+                }
+            }
+            SDL_EventType::PEN_PROXIMITY_IN | SDL_EventType::PEN_PROXIMITY_OUT => {
+                let sdl = &sdl.pproximity;
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::PenProximity {
+                        window_id: sdl.windowID.0.into(),
+                        pen_id: sdl.which.0.into(),
+                        entered: sdl_type == SDL_EventType::PEN_PROXIMITY_IN,
+                    },
+                }
+            }
+            SDL_EventType::PEN_DOWN | SDL_EventType::PEN_UP => {
+                let sdl = &sdl.ptouch;
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::PenTouch {
+                        window_id: sdl.windowID.0.into(),
+                        pen_id: sdl.which.0.into(),
+                        state: PenInputFlags::from_bits_truncate(sdl.pen_state.0),
+                        position: FPoint::new(sdl.x, sdl.y),
+                        eraser: sdl.eraser,
+                        down: sdl.down,
+                        user_operation: user_operation(sdl.down),
+                    },
+                }
+            }
+            SDL_EventType::PEN_MOTION => {
+                let sdl = &sdl.pmotion;
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::PenMotion {
+                        window_id: sdl.windowID.0.into(),
+                        pen_id: sdl.which.0.into(),
+                        state: PenInputFlags::from_bits_truncate(sdl.pen_state.0),
+                        position: FPoint::new(sdl.x, sdl.y),
+                    },
+                }
+            }
+            SDL_EventType::PEN_BUTTON_DOWN | SDL_EventType::PEN_BUTTON_UP => {
+                let sdl = &sdl.pbutton;
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::PenButton {
+                        window_id: sdl.windowID.0.into(),
+                        pen_id: sdl.which.0.into(),
+                        state: PenInputFlags::from_bits_truncate(sdl.pen_state.0),
+                        position: FPoint::new(sdl.x, sdl.y),
+                        button: sdl.button,
+                        down: sdl.down,
+                        user_operation: user_operation(sdl.down),
+                    },
+                }
+            }
+            SDL_EventType::PEN_AXIS => {
+                let sdl = &sdl.paxis;
+
+                match PenAxis::from_i32(sdl.axis.0) {
+                    Some(axis) => Event {
+                        ns_timestamp: sdl.timestamp,
+                        raw: RawEvent::PenAxis {
+                            window_id: sdl.windowID.0.into(),
+                            pen_id: sdl.which.0.into(),
+                            state: PenInputFlags::from_bits_truncate(sdl.pen_state.0),
+                            position: FPoint::new(sdl.x, sdl.y),
+                            axis,
+                            value: sdl.value,
+                        },
+                    },
+                    None => Event {
+                        ns_timestamp: sdl.timestamp,
+                        raw: RawEvent::Unknown {
+                            type_id: sdl.r#type.into(),
+                        },
+                    },
+                }
+            }
             SDL_EventType::WINDOW_CLOSE_REQUESTED => {
                 let sdl = &sdl.window;
 
@@ -341,7 +689,7 @@ pub fn translate_event(sdl: SDL_Event) -> Event {
                     SDL_EventType::WINDOW_HIT_TEST => WindowEvent::HitTest,
                     SDL_EventType::WINDOW_ICCPROF_CHANGED => WindowEvent::IccProfileChanged,
                     SDL_EventType::WINDOW_DISPLAY_CHANGED => WindowEvent::DisplayChanged {
-                        display_id: sdl.data1 as u32,
+                        display_id: (sdl.data1 as u32).into(),
                     },
                     SDL_EventType::WINDOW_DISPLAY_SCALE_CHANGED => WindowEvent::DisplayScaleChanged,
                     SDL_EventType::WINDOW_SAFE_AREA_CHANGED => WindowEvent::SafeAreaChanged,
@@ -361,6 +709,66 @@ pub fn translate_event(sdl: SDL_Event) -> Event {
                     },
                 }
             }
+            SDL_EventType::DISPLAY_ORIENTATION
+            | SDL_EventType::DISPLAY_ADDED
+            | SDL_EventType::DISPLAY_REMOVED
+            | SDL_EventType::DISPLAY_MOVED
+            | SDL_EventType::DISPLAY_DESKTOP_MODE_CHANGED
+            | SDL_EventType::DISPLAY_CURRENT_MODE_CHANGED
+            | SDL_EventType::DISPLAY_CONTENT_SCALE_CHANGED => {
+                let sdl = &sdl.display;
+                let event = match sdl_type {
+                    SDL_EventType::DISPLAY_ORIENTATION => DisplayEvent::OrientationChanged {
+                        orientation: display_orientation_from_sdl(SDL_DisplayOrientation(
+                            sdl.data1,
+                        )),
+                    },
+                    SDL_EventType::DISPLAY_ADDED => DisplayEvent::Added,
+                    SDL_EventType::DISPLAY_REMOVED => DisplayEvent::Removed,
+                    SDL_EventType::DISPLAY_MOVED => DisplayEvent::Moved,
+                    SDL_EventType::DISPLAY_DESKTOP_MODE_CHANGED => DisplayEvent::DesktopModeChanged,
+                    SDL_EventType::DISPLAY_CURRENT_MODE_CHANGED => DisplayEvent::CurrentModeChanged,
+                    _ => DisplayEvent::ContentScaleChanged,
+                };
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::Display {
+                        display_id: sdl.displayID.0.into(),
+                        event,
+                    },
+                }
+            }
+            SDL_EventType::CLIPBOARD_UPDATE => {
+                let sdl = &sdl.clipboard;
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw: RawEvent::ClipboardUpdate {
+                        owner: sdl.owner,
+                        mime_types: strings_from_ptr(sdl.mime_types, sdl.num_mime_types),
+                    },
+                }
+            }
+            _ if is_staccato_user_event(&sdl.user) => {
+                let sdl = &sdl.user;
+
+                let raw = match take_payload(sdl) {
+                    Some(payload) => RawEvent::Custom {
+                        window_id: sdl.windowID.0.into(),
+                        payload,
+                    },
+                    // the payload was flushed or discarded
+                    None => RawEvent::Unknown {
+                        type_id: sdl.r#type.into(),
+                    },
+                };
+
+                Event {
+                    ns_timestamp: sdl.timestamp,
+                    raw,
+                }
+            }
             _ => {
                 let sdl = &sdl.common;
 
@@ -1,4 +1,4 @@
-use crate::sdl_event::translate_event;
+use crate::sdl_event::translate_event_into;
 use sdl3_sys::events::SDL_EVENT_POLL_SENTINEL;
 use sdl3_sys::everything::SDL_PollEvent;
 use staccato_shared::event::Event;
@@ -22,9 +22,7 @@ impl EventSource for SdlEventSource {
                     break;
                 }
 
-                let event = translate_event(event);
-
-                self.buffer.push(event);
+                translate_event_into(event, &mut self.buffer);
             }
         }
 
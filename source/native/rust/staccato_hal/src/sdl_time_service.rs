@@ -0,0 +1,18 @@
+use sdl3_sys::timer::SDL_GetTicksNS;
+use staccato_core::time_service::TimeService;
+
+/// `TimeService` backed by `SDL_GetTicksNS`, the same clock SDL stamps events with.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SdlTimeService;
+
+impl SdlTimeService {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl TimeService for SdlTimeService {
+    fn get_timestamp_ns(&self) -> u64 {
+        unsafe { SDL_GetTicksNS() }
+    }
+}
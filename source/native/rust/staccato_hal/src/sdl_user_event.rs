@@ -0,0 +1,164 @@
+use crate::error::SdlError;
+use sdl3_sys::events::{
+    SDL_Event, SDL_EventType, SDL_FlushEvent, SDL_PushEvent, SDL_RegisterEvents, SDL_UserEvent,
+};
+use sdl3_sys::video::SDL_WindowID;
+use staccato_shared::event::CustomPayload;
+use staccato_shared::id::WindowId;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// Marks SDL user events whose `data1` and `data2` point to a payload slot.
+pub const STACCATO_USER_EVENT_CODE: i32 = 0x5354_4143;
+
+/// Number of custom events that can wait in the SDL queue at the same time.
+pub const USER_EVENT_SLOTS: usize = 1024;
+
+#[derive(Debug)]
+struct PayloadSlot {
+    /// bumped on every push so a stale event can not take a newer payload
+    generation: u32,
+    event_type: u32,
+    payload: Option<CustomPayload>,
+}
+
+/// Payloads of the events in the SDL queue, preallocated so pushing does not allocate.
+static SLOTS: [Mutex<PayloadSlot>; USER_EVENT_SLOTS] = [const {
+    Mutex::new(PayloadSlot {
+        generation: 0,
+        event_type: 0,
+        payload: None,
+    })
+}; USER_EVENT_SLOTS];
+
+/// Where the next push starts looking for a free slot.
+static NEXT_SLOT: AtomicUsize = AtomicUsize::new(0);
+
+fn lock(index: usize) -> Option<MutexGuard<'static, PayloadSlot>> {
+    // a slot holds no invariant a panic could break
+    SLOTS
+        .get(index)
+        .map(|slot| slot.lock().unwrap_or_else(|poisoned| poisoned.into_inner()))
+}
+
+/// Move `payload` into a free slot, returns the slot index and generation.
+fn claim_slot(event_type: u32, payload: CustomPayload) -> Option<(usize, u32)> {
+    let mut payload = Some(payload);
+    for _ in 0..USER_EVENT_SLOTS {
+        let index = NEXT_SLOT.fetch_add(1, Ordering::Relaxed) % USER_EVENT_SLOTS;
+        let Some(Ok(mut slot)) = SLOTS.get(index).map(Mutex::try_lock) else {
+            continue;
+        };
+        if slot.payload.is_none() {
+            slot.generation = slot.generation.wrapping_add(1);
+            slot.event_type = event_type;
+            slot.payload = payload.take();
+            return Some((index, slot.generation));
+        }
+    }
+    None
+}
+
+fn release_slot(index: usize, generation: u32) -> Option<CustomPayload> {
+    let mut slot = lock(index)?;
+    if slot.generation != generation {
+        return None;
+    }
+    slot.payload.take()
+}
+
+/// An SDL user event type reserved by `SDL_RegisterEvents`.
+///
+/// `push` is thread-safe, events pushed from worker threads wake up the main loop
+/// and come out of `SdlEventSource` as `RawEvent::Custom`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SdlUserEventType {
+    event_type: u32,
+}
+
+impl SdlUserEventType {
+    pub fn register() -> Result<Self, SdlError> {
+        let event_type = unsafe { SDL_RegisterEvents(1) };
+
+        if event_type == 0 {
+            return Err(SdlError::sdl_err("failed to register user event type"));
+        }
+
+        Ok(Self { event_type })
+    }
+
+    pub fn event_type(&self) -> u32 {
+        self.event_type
+    }
+
+    /// Post `payload` to the SDL event queue. `window_id` may be 0 if not related to a window.
+    ///
+    /// The payload waits in one of `USER_EVENT_SLOTS` preallocated slots until the event is
+    /// translated, so inline payloads are never allocated. Fails if all slots are in use.
+    pub fn push(&self, window_id: WindowId, payload: CustomPayload) -> Result<(), SdlError> {
+        let window_id =
+            u32::try_from(window_id).map_err(|_| SdlError::sdl_err("window id out of range"))?;
+        let (index, generation) = claim_slot(self.event_type, payload)
+            .ok_or_else(|| SdlError::sdl_err("all user event slots are in use"))?;
+
+        let mut event: SDL_Event = unsafe { std::mem::zeroed() };
+        event.user = SDL_UserEvent {
+            r#type: self.event_type,
+            reserved: 0,
+            timestamp: 0,
+            windowID: SDL_WindowID(window_id),
+            code: STACCATO_USER_EVENT_CODE,
+            // plus one so the pointer is never null
+            data1: std::ptr::without_provenance_mut(index + 1),
+            data2: std::ptr::without_provenance_mut(generation as usize),
+        };
+
+        if !unsafe { SDL_PushEvent(&mut event) } {
+            release_slot(index, generation);
+            return Err(SdlError::sdl_err("failed to push user event"));
+        }
+
+        Ok(())
+    }
+
+    /// Remove the pending events of this type from the SDL queue and drop their payloads.
+    pub fn flush(&self) {
+        unsafe { SDL_FlushEvent(self.event_type) };
+
+        for index in 0..USER_EVENT_SLOTS {
+            if let Some(mut slot) = lock(index)
+                && slot.event_type == self.event_type
+            {
+                slot.payload = None;
+            }
+        }
+    }
+}
+
+/// Drop the payloads of every event not translated yet, call it when shutting down SDL.
+///
+/// Events still in the queue come out as `RawEvent::Unknown` afterwards.
+pub fn discard_pending_payloads() {
+    for index in 0..USER_EVENT_SLOTS {
+        if let Some(mut slot) = lock(index) {
+            slot.payload = None;
+        }
+    }
+}
+
+/// Whether `event` was pushed by `SdlUserEventType::push`.
+pub(crate) fn is_staccato_user_event(event: &SDL_UserEvent) -> bool {
+    event.r#type >= SDL_EventType::USER.0
+        && event.r#type < SDL_EventType::LAST.0
+        && event.code == STACCATO_USER_EVENT_CODE
+        && !event.data1.is_null()
+}
+
+/// Take the payload out of an event accepted by `is_staccato_user_event`.
+///
+/// `None` if the payload was already taken or discarded.
+pub(crate) fn take_payload(event: &SDL_UserEvent) -> Option<CustomPayload> {
+    let index = (event.data1.addr()).checked_sub(1)?;
+    let generation = u32::try_from(event.data2.addr()).ok()?;
+    release_slot(index, generation)
+}
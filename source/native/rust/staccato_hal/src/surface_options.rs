@@ -0,0 +1,137 @@
+use wgpu::{
+    CompositeAlphaMode, PresentMode, SurfaceCapabilities, SurfaceConfiguration, TextureFormat,
+    TextureUsages,
+};
+
+/// How presenting waits for the display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VsyncMode {
+    /// Wait for the vertical blank, never tears.
+    #[default]
+    On,
+    /// Wait for the vertical blank unless the frame is late, late frames may tear.
+    Adaptive,
+    /// Never block, the newest frame replaces the queued one. No tearing, more power.
+    Mailbox,
+    /// Present right away, tears.
+    Off,
+}
+
+impl VsyncMode {
+    /// Present modes to try in order. `Fifo` is always supported, so it ends every list.
+    pub fn present_modes(self) -> &'static [PresentMode] {
+        match self {
+            VsyncMode::On => &[PresentMode::Fifo],
+            VsyncMode::Adaptive => &[PresentMode::FifoRelaxed, PresentMode::Fifo],
+            VsyncMode::Mailbox => &[
+                PresentMode::Mailbox,
+                PresentMode::Immediate,
+                PresentMode::Fifo,
+            ],
+            VsyncMode::Off => &[
+                PresentMode::Immediate,
+                PresentMode::Mailbox,
+                PresentMode::Fifo,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FormatPreference {
+    /// An sRGB format, shaders write linear colors.
+    #[default]
+    Srgb,
+    /// A non-sRGB format, shaders write encoded colors.
+    Linear,
+    /// A 16-bit float or 10-bit format if the display supports one, sRGB otherwise.
+    Hdr,
+}
+
+impl FormatPreference {
+    fn select(self, formats: &[TextureFormat]) -> Option<TextureFormat> {
+        let srgb = || formats.iter().find(|format| format.is_srgb());
+        let linear = || formats.iter().find(|format| !format.is_srgb());
+        let hdr = || {
+            [TextureFormat::Rgba16Float, TextureFormat::Rgb10a2Unorm]
+                .iter()
+                .find(|format| formats.contains(format))
+        };
+
+        match self {
+            FormatPreference::Srgb => srgb().or_else(linear),
+            FormatPreference::Linear => linear().or_else(srgb),
+            FormatPreference::Hdr => hdr().or_else(srgb).or_else(linear),
+        }
+        .copied()
+    }
+}
+
+/// How a `WgpuWindow` configures its surface.
+///
+/// Every field falls back to something supported, see `WgpuWindow::set_surface_options`
+/// to change them at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SurfaceOptions {
+    pub vsync: VsyncMode,
+    pub format: FormatPreference,
+    /// `Auto` if the requested mode is not supported.
+    pub alpha_mode: CompositeAlphaMode,
+    /// Frames the CPU may queue ahead of the GPU, 1 for the lowest latency.
+    pub frame_latency: u32,
+}
+
+impl Default for SurfaceOptions {
+    fn default() -> Self {
+        Self {
+            vsync: VsyncMode::On,
+            format: FormatPreference::Srgb,
+            alpha_mode: CompositeAlphaMode::Auto,
+            frame_latency: 2,
+        }
+    }
+}
+
+impl SurfaceOptions {
+    /// `None` if the surface supports no format at all, i.e. not the adapter.
+    pub fn configuration(
+        &self,
+        caps: &SurfaceCapabilities,
+        width: u32,
+        height: u32,
+    ) -> Option<SurfaceConfiguration> {
+        let format = self.format.select(&caps.formats)?;
+
+        let present_mode = self
+            .vsync
+            .present_modes()
+            .iter()
+            .copied()
+            .find(|mode| caps.present_modes.contains(mode))
+            .unwrap_or(PresentMode::Fifo);
+
+        let alpha_mode = if caps.alpha_modes.contains(&self.alpha_mode) {
+            self.alpha_mode
+        } else {
+            CompositeAlphaMode::Auto
+        };
+
+        // still let the shaders write linear colors through an sRGB view
+        let view_formats = if self.format == FormatPreference::Srgb && !format.is_srgb() {
+            vec![format.add_srgb_suffix()]
+        } else {
+            vec![]
+        };
+
+        Some(SurfaceConfiguration {
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            format,
+            width,
+            height,
+            present_mode,
+            alpha_mode,
+            view_formats,
+            desired_maximum_frame_latency: self.frame_latency.max(1),
+        })
+    }
+}
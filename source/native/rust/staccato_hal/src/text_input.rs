@@ -0,0 +1,23 @@
+use crate::error::SdlError;
+use sdl3_sys::hints::{SDL_HINT_IME_IMPLEMENTED_UI, SDL_SetHint};
+use std::ffi::CStr;
+
+/// Tell SDL which parts of the IME UI the game draws itself.
+///
+/// `RawEvent::TextEditingCandidates` is only sent if `candidates` is set, otherwise the system
+/// draws the candidate window. Call it before starting text input.
+pub fn set_ime_implemented_ui(composition: bool, candidates: bool) -> Result<(), SdlError> {
+    let value: &CStr = match (composition, candidates) {
+        (true, true) => c"composition,candidates",
+        (true, false) => c"composition",
+        (false, true) => c"candidates",
+        (false, false) => c"none",
+    };
+
+    unsafe {
+        if !SDL_SetHint(SDL_HINT_IME_IMPLEMENTED_UI, value.as_ptr()) {
+            return Err(SdlError::sdl_err("failed to set IME hint"));
+        }
+    }
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::surface_options::SurfaceOptions;
 use crate::wgpu_window::{WgpuWindow, WgpuWindowError};
 use crate::window::Window;
 use pollster::FutureExt;
@@ -10,6 +11,10 @@ use wgpu::{
 
 #[derive(Debug, Error)]
 pub enum ContextError {
+    #[error(
+        "no graphics adapter found (force_fallback_adapter:{force_fallback_adapter}), a software adapter may not be installed"
+    )]
+    NoAdapter { force_fallback_adapter: bool },
     #[error("failed to request adfapter:{0}")]
     RequestAdapterError(#[from] RequestAdapterError),
     #[error("failed to request device:{0}")]
@@ -20,6 +25,19 @@ pub enum ContextError {
     WgpuWindowError(#[from] WgpuWindowError),
 }
 
+fn request_adapter(
+    instance: &wgpu::Instance,
+    options: &RequestAdapterOptions<'_, '_>,
+) -> Result<wgpu::Adapter, ContextError> {
+    match instance.request_adapter(options).block_on() {
+        Ok(adapter) => Ok(adapter),
+        Err(RequestAdapterError::NotFound { .. }) => Err(ContextError::NoAdapter {
+            force_fallback_adapter: options.force_fallback_adapter,
+        }),
+        Err(err) => Err(err.into()),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct WgpuRenderContext {
     instance: wgpu::Instance,
@@ -29,13 +47,16 @@ pub struct WgpuRenderContext {
 }
 
 impl WgpuRenderContext {
+    /// Create a context without a window, e.g. for `OffscreenTarget`.
+    ///
+    /// Set `force_fallback_adapter` to render on the CPU, on machines without a GPU.
     pub fn new(
         instance_descriptor: &InstanceDescriptor,
         adapter_options: &RequestAdapterOptions<'_, '_>,
         device_descriptor: &DeviceDescriptor<'_>,
     ) -> Result<Self, ContextError> {
         let instance = wgpu::Instance::new(instance_descriptor);
-        let adapter = instance.request_adapter(adapter_options).block_on()?;
+        let adapter = request_adapter(&instance, adapter_options)?;
 
         let (device, queue) = adapter.request_device(device_descriptor).block_on()?;
 
@@ -47,11 +68,15 @@ impl WgpuRenderContext {
         })
     }
 
+    /// Create the context with an adapter that can present to `window`.
+    ///
+    /// Open more windows on the same device with `WgpuWindows::open`.
     pub fn new_with_window<'window>(
         window: Window,
         instance_descriptor: &InstanceDescriptor,
         adapter_options: &RequestAdapterOptions<'_, '_>,
         device_descriptor: &DeviceDescriptor<'_>,
+        surface_options: &SurfaceOptions,
     ) -> Result<(Self, WgpuWindow<'window>), ContextError> {
         let instance = wgpu::Instance::new(instance_descriptor);
 
@@ -63,7 +88,7 @@ impl WgpuRenderContext {
 
         adapter_options.compatible_surface = Some(surface_ref);
 
-        let adapter = instance.request_adapter(&adapter_options).block_on()?;
+        let adapter = request_adapter(&instance, &adapter_options)?;
 
         let (device, queue) = adapter.request_device(device_descriptor).block_on()?;
 
@@ -74,7 +99,8 @@ impl WgpuRenderContext {
             queue: Arc::new(queue),
         };
 
-        let window = WgpuWindow::from_window_and_surface(context.clone(), window, surface)?;
+        let window =
+            WgpuWindow::from_window_and_surface(context.clone(), window, surface, surface_options)?;
 
         Ok((context, window))
     }
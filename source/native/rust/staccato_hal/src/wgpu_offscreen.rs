@@ -0,0 +1,241 @@
+use crate::wgpu_context::WgpuRenderContext;
+use staccato_core::rect::Size;
+use std::sync::Arc;
+use std::sync::mpsc;
+use thiserror::Error;
+use wgpu::{
+    Buffer, BufferAsyncError, BufferDescriptor, BufferUsages, COPY_BYTES_PER_ROW_ALIGNMENT, Device,
+    Extent3d, MapMode, PollError, PollType, Queue, TexelCopyBufferInfo, TexelCopyBufferLayout,
+    Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureView,
+};
+
+#[derive(Debug, Error)]
+pub enum OffscreenError {
+    #[error("invalid offscreen target size:{width}x{height}")]
+    InvalidSize { width: i32, height: i32 },
+    #[error("can not read back pixels of format {0:?}")]
+    UnsupportedFormat(TextureFormat),
+    #[error("failed to map the readback buffer:{0}")]
+    BufferAsyncError(#[from] BufferAsyncError),
+    #[error("failed to wait for the device:{0}")]
+    PollError(#[from] PollError),
+}
+
+/// A texture to render into without a window, the pixels can be read back to the CPU.
+///
+/// Works with `SDL_VIDEODRIVER=dummy` and the fallback adapter, for golden-image tests:
+///
+/// ```ignore
+/// let context = WgpuRenderContext::new(
+///     &Default::default(),
+///     &RequestAdapterOptions {
+///         force_fallback_adapter: true,
+///         ..Default::default()
+///     },
+///     &Default::default(),
+/// )?;
+/// let mut target = OffscreenTarget::new(&context, Size::new(64, 64), TextureFormat::Rgba8UnormSrgb)?;
+/// // render into `target.view()` and submit
+/// let pixels = target.read_pixels()?;
+/// ```
+#[derive(Debug)]
+pub struct OffscreenTarget {
+    texture: Texture,
+    view: TextureView,
+    readback: Buffer,
+    device: Arc<Device>,
+    queue: Arc<Queue>,
+    size: Size,
+    /// bytes per row of `readback`, aligned for the copy
+    padded_row: u32,
+    /// bytes per row of the returned pixels
+    row: u32,
+}
+
+impl OffscreenTarget {
+    pub fn new(
+        context: &WgpuRenderContext,
+        size: Size,
+        format: TextureFormat,
+    ) -> Result<Self, OffscreenError> {
+        let invalid_size = || OffscreenError::InvalidSize {
+            width: size.width,
+            height: size.height,
+        };
+        let device = context.device().clone();
+        let limits = device.limits();
+        let dimension = |value: i32| {
+            u32::try_from(value)
+                .ok()
+                .filter(|value| (1..=limits.max_texture_dimension_2d).contains(value))
+        };
+        let width = dimension(size.width).ok_or_else(invalid_size)?;
+        let height = dimension(size.height).ok_or_else(invalid_size)?;
+
+        let texel = format
+            .block_copy_size(None)
+            .filter(|_| format.block_dimensions() == (1, 1))
+            .ok_or(OffscreenError::UnsupportedFormat(format))?;
+        let row = width.checked_mul(texel).ok_or_else(invalid_size)?;
+        let padded_row = row
+            .checked_next_multiple_of(COPY_BYTES_PER_ROW_ALIGNMENT)
+            .ok_or_else(invalid_size)?;
+        let readback_size = u64::from(padded_row) * u64::from(height);
+        if readback_size > limits.max_buffer_size {
+            return Err(invalid_size());
+        }
+
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("staccato offscreen target"),
+            size: Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::RENDER_ATTACHMENT
+                | TextureUsages::COPY_SRC
+                | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&Default::default());
+
+        let readback = device.create_buffer(&BufferDescriptor {
+            label: Some("staccato offscreen readback"),
+            size: readback_size,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        Ok(Self {
+            texture,
+            view,
+            readback,
+            device,
+            queue: context.queue().clone(),
+            size,
+            padded_row,
+            row,
+        })
+    }
+
+    pub fn texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    /// View of the whole texture, for render pass attachments.
+    pub fn view(&self) -> &TextureView {
+        &self.view
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn format(&self) -> TextureFormat {
+        self.texture.format()
+    }
+
+    /// Copy the texture to the CPU, rows are tightly packed from top to bottom.
+    ///
+    /// Blocks until the submitted work is done.
+    pub fn read_pixels(&self) -> Result<Vec<u8>, OffscreenError> {
+        let mut pixels = Vec::new();
+        self.read_pixels_into(&mut pixels)?;
+        Ok(pixels)
+    }
+
+    /// Like `read_pixels`, reusing the allocation of `pixels`.
+    pub fn read_pixels_into(&self, pixels: &mut Vec<u8>) -> Result<(), OffscreenError> {
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        encoder.copy_texture_to_buffer(
+            self.texture.as_image_copy(),
+            TexelCopyBufferInfo {
+                buffer: &self.readback,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(self.padded_row),
+                    rows_per_image: None,
+                },
+            },
+            self.texture.size(),
+        );
+        self.queue.submit([encoder.finish()]);
+
+        let slice = self.readback.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(MapMode::Read, move |result| {
+            // the receiver only goes away if polling failed
+            let _ = sender.send(result);
+        });
+        self.device.poll(PollType::wait_indefinitely())?;
+        receiver.recv().unwrap_or(Err(BufferAsyncError))?;
+
+        pixels.clear();
+        {
+            let mapped = slice.get_mapped_range();
+            for padded in mapped.chunks_exact(self.padded_row as usize) {
+                pixels.extend_from_slice(&padded[..self.row as usize]);
+            }
+        }
+        self.readback.unmap();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error;
+    use wgpu::{
+        Color, LoadOp, Operations, RenderPassColorAttachment, RenderPassDescriptor, StoreOp,
+    };
+
+    #[test]
+    #[ignore = "needs a graphics adapter, run with --ignored"]
+    fn reads_back_unpadded_rows() -> Result<(), Box<dyn Error>> {
+        let context = WgpuRenderContext::new(
+            &Default::default(),
+            &Default::default(),
+            &Default::default(),
+        )?;
+        let limit = context.device().limits().max_texture_dimension_2d;
+        assert!(matches!(
+            OffscreenTarget::new(
+                &context,
+                Size::new(i32::try_from(limit)? + 1, 1),
+                TextureFormat::Rgba8Unorm
+            ),
+            Err(OffscreenError::InvalidSize { .. })
+        ));
+
+        // 20 bytes per row, padded to 256 for the copy
+        let target = OffscreenTarget::new(&context, Size::new(5, 3), TextureFormat::Rgba8Unorm)?;
+        let mut encoder = context.device().create_command_encoder(&Default::default());
+        encoder.begin_render_pass(&RenderPassDescriptor {
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: target.view(),
+                depth_slice: None,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Clear(Color {
+                        r: 1.0,
+                        g: 0.0,
+                        b: 1.0,
+                        a: 1.0,
+                    }),
+                    store: StoreOp::Store,
+                },
+            })],
+            ..Default::default()
+        });
+        context.queue().submit([encoder.finish()]);
+
+        let pixels = target.read_pixels()?;
+        assert_eq!(pixels, [255, 0, 255, 255].repeat(5 * 3));
+        Ok(())
+    }
+}
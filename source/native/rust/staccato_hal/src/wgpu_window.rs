@@ -1,12 +1,20 @@
 use crate::error::SdlError;
+use crate::surface_options::{SurfaceOptions, VsyncMode};
 use crate::wgpu_context::WgpuRenderContext;
 use crate::window::Window;
 use eyre::{Context, Report};
-use staccato_core::spatial::HasSize;
+use staccato_core::fallible::Fallible;
+use staccato_core::rect::Size;
+use staccato_platform_api::window::{WindowBackend, WindowId};
+use staccato_shared::event::{Event, RawEvent, WindowEvent};
+use staccato_shared::event_dispatcher::EventHandler;
 use std::mem::ManuallyDrop;
 use std::sync::Arc;
 use thiserror::Error;
-use wgpu::{CreateSurfaceError, Device, Queue, Surface, SurfaceConfiguration, SurfaceTarget};
+use wgpu::{
+    CreateSurfaceError, Device, Queue, Surface, SurfaceCapabilities, SurfaceConfiguration,
+    SurfaceError, SurfaceTarget, SurfaceTexture, Texture, TextureView,
+};
 
 #[derive(Debug, Error)]
 pub enum WgpuWindowError {
@@ -14,6 +22,10 @@ pub enum WgpuWindowError {
     SdlError(#[from] SdlError),
     #[error("Get a create surface error:{0}")]
     CreateSurfaceError(#[from] CreateSurfaceError),
+    #[error("Get a surface error:{0}")]
+    SurfaceError(#[from] SurfaceError),
+    #[error("The surface is not supported by the adapter")]
+    UnsupportedSurface,
     #[error("Get an error when initialize wgpu and window:{0}")]
     Other(#[from] Report),
 }
@@ -25,6 +37,40 @@ pub struct WgpuWindow<'window> {
     device: Arc<Device>,
     queue: Arc<Queue>,
     config: ManuallyDrop<SurfaceConfiguration>,
+    caps: SurfaceCapabilities,
+    options: SurfaceOptions,
+    /// nothing is presented while minimized
+    minimized: bool,
+    /// the last frame was suboptimal, reconfigure after presenting it
+    needs_reconfigure: bool,
+}
+
+/// A surface texture acquired by `WgpuWindow::acquire_frame`.
+///
+/// Hand it back to `WgpuWindow::present` after submitting the work that draws into it,
+/// dropping it discards the frame.
+#[derive(Debug)]
+pub struct WgpuFrame {
+    texture: SurfaceTexture,
+    view: TextureView,
+}
+
+impl WgpuFrame {
+    pub fn texture(&self) -> &Texture {
+        &self.texture.texture
+    }
+
+    /// View of the whole texture, for render pass attachments.
+    pub fn view(&self) -> &TextureView {
+        &self.view
+    }
+
+    pub fn size(&self) -> Size {
+        Size::new(
+            self.texture.texture.width() as i32,
+            self.texture.texture.height() as i32,
+        )
+    }
 }
 
 impl Drop for WgpuWindow<'_> {
@@ -37,92 +83,81 @@ impl Drop for WgpuWindow<'_> {
     }
 }
 
+/// Surface size of `window` in pixels, at least 1x1.
+fn surface_size(window: &Window) -> Result<(u32, u32), WgpuWindowError> {
+    let size = window.pixel_size();
+    let width = size
+        .width
+        .max(1)
+        .try_into()
+        .wrap_err("failed to convert windows width to u32")?;
+    let height = size
+        .height
+        .max(1)
+        .try_into()
+        .wrap_err("failed to convert windows height to u32")?;
+    Ok((width, height))
+}
+
 impl<'window> WgpuWindow<'window> {
     pub fn from_window(
         context: WgpuRenderContext,
         window: Window,
+        options: &SurfaceOptions,
     ) -> Result<Self, WgpuWindowError> {
-        let window = Box::from(window);
-
         let surface = context
             .instance()
             .create_surface(SurfaceTarget::Window(window.window().handler()))?;
 
-        let caps = surface.get_capabilities(context.adapter());
-        let mut size = window.get_size();
-        size.width = size.width.max(1);
-        size.height = size.height.max(1);
-        let config = wgpu::SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: caps.formats[0],
-            width: size
-                .width
-                .try_into()
-                .wrap_err("failed to convert windows width to u32")?,
-            height: size
-                .height
-                .try_into()
-                .wrap_err("failed to convert windows height to u32")?,
-            present_mode: wgpu::PresentMode::Fifo,
-            alpha_mode: caps.alpha_modes[0],
-            view_formats: vec![],
-            desired_maximum_frame_latency: 2,
-        };
-
-        surface.configure(context.device(), &config);
-
-        Ok(Self {
-            window: ManuallyDrop::new(window),
-            surface: ManuallyDrop::new(surface),
-            config: ManuallyDrop::new(config),
-            device: context.device().clone(),
-            queue: context.queue().clone(),
-        })
+        Self::from_window_and_surface(context, window, surface, options)
     }
 
     pub fn from_window_and_surface(
         context: WgpuRenderContext,
         window: Window,
         surface: Surface<'window>,
+        options: &SurfaceOptions,
     ) -> Result<Self, WgpuWindowError> {
         let window = Box::from(window);
 
         let caps = surface.get_capabilities(context.adapter());
-        let mut size = window.get_size();
-        size.width = size.width.max(1);
-        size.height = size.height.max(1);
-        let config = SurfaceConfiguration {
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            format: caps.formats[0],
-            width: size
-                .width
-                .try_into()
-                .wrap_err("failed to convert windows width to u32")?,
-            height: size
-                .height
-                .try_into()
-                .wrap_err("failed to convert windows height to u32")?,
-            present_mode: wgpu::PresentMode::Fifo,
-            alpha_mode: caps.alpha_modes[0],
-            view_formats: vec![],
-            desired_maximum_frame_latency: 2,
-        };
+        let (width, height) = surface_size(&window)?;
+        let config = options
+            .configuration(&caps, width, height)
+            .ok_or(WgpuWindowError::UnsupportedSurface)?;
 
         surface.configure(context.device(), &config);
 
         Ok(Self {
+            minimized: window.window().is_minimized(),
             window: ManuallyDrop::new(window),
             surface: ManuallyDrop::new(surface),
             config: ManuallyDrop::new(config),
+            caps,
+            options: *options,
             device: context.device().clone(),
             queue: context.queue().clone(),
+            needs_reconfigure: false,
         })
     }
 
+    pub fn id(&self) -> WindowId {
+        self.window.window().id()
+    }
+
     pub fn window(&self) -> &Window {
         &self.window
     }
 
+    pub fn window_mut(&mut self) -> &mut Window {
+        &mut self.window
+    }
+
+    /// Whether `acquire_frame` currently skips frames.
+    pub fn is_minimized(&self) -> bool {
+        self.minimized
+    }
+
     pub fn device(&self) -> &Device {
         &self.device
     }
@@ -134,4 +169,139 @@ impl<'window> WgpuWindow<'window> {
     pub fn surface(&self) -> &Surface<'_> {
         &self.surface
     }
+
+    pub fn config(&self) -> &SurfaceConfiguration {
+        &self.config
+    }
+
+    /// Size of the surface in pixels.
+    pub fn surface_size(&self) -> Size {
+        Size::new(self.config.width as i32, self.config.height as i32)
+    }
+
+    /// Reconfigure the surface if the pixel size of the window changed.
+    ///
+    /// Called by `handle` on `PixelSizeChanged`, call it after `Window::update` otherwise.
+    pub fn resize_surface(&mut self) -> Result<(), WgpuWindowError> {
+        let (width, height) = surface_size(&self.window)?;
+        if (width, height) == (self.config.width, self.config.height) {
+            return Ok(());
+        }
+
+        self.config.width = width;
+        self.config.height = height;
+        self.surface.configure(&self.device, &self.config);
+        Ok(())
+    }
+
+    pub fn surface_options(&self) -> &SurfaceOptions {
+        &self.options
+    }
+
+    /// Reconfigure the surface with `options`, unsupported choices fall back like on creation.
+    pub fn set_surface_options(&mut self, options: SurfaceOptions) -> Result<(), WgpuWindowError> {
+        let config = options
+            .configuration(&self.caps, self.config.width, self.config.height)
+            .ok_or(WgpuWindowError::UnsupportedSurface)?;
+
+        self.surface.configure(&self.device, &config);
+        *self.config = config;
+        self.options = options;
+        Ok(())
+    }
+
+    pub fn set_vsync(&mut self, vsync: VsyncMode) -> Result<(), WgpuWindowError> {
+        self.set_surface_options(SurfaceOptions {
+            vsync,
+            ..self.options
+        })
+    }
+
+    /// Query the pixel size again and configure the surface even if it did not change.
+    fn reconfigure(&mut self) -> Result<(), WgpuWindowError> {
+        self.window.update()?;
+        let (width, height) = surface_size(&self.window)?;
+        self.config.width = width;
+        self.config.height = height;
+        self.surface.configure(&self.device, &self.config);
+        self.needs_reconfigure = false;
+        Ok(())
+    }
+
+    /// Get the next surface texture to draw into.
+    ///
+    /// Returns `None` when there is nothing to present to: the window is minimized or
+    /// the platform timed out. Skip rendering for this frame then. A lost or outdated surface
+    /// is reconfigured and acquired once more.
+    pub fn acquire_frame(&mut self) -> Result<Option<WgpuFrame>, WgpuWindowError> {
+        if self.minimized {
+            return Ok(None);
+        }
+
+        let texture = match self.surface.get_current_texture() {
+            Ok(texture) => texture,
+            Err(SurfaceError::Lost | SurfaceError::Outdated) => {
+                self.reconfigure()?;
+                match self.surface.get_current_texture() {
+                    Ok(texture) => texture,
+                    // still racing a resize, the next event brings the right size
+                    Err(SurfaceError::Outdated | SurfaceError::Timeout) => return Ok(None),
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            Err(SurfaceError::Timeout) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        if texture.suboptimal {
+            self.needs_reconfigure = true;
+        }
+
+        let view = texture.texture.create_view(&Default::default());
+        Ok(Some(WgpuFrame { texture, view }))
+    }
+
+    /// Present `frame` to the window.
+    pub fn present(&mut self, frame: WgpuFrame) -> Result<(), WgpuWindowError> {
+        // the view must not outlive the presented texture
+        drop(frame.view);
+        frame.texture.present();
+
+        if self.needs_reconfigure {
+            self.reconfigure()?;
+        }
+        Ok(())
+    }
+}
+
+impl Fallible for WgpuWindow<'_> {
+    type Error = WgpuWindowError;
+}
+
+impl EventHandler for WgpuWindow<'_> {
+    /// Keep the window metrics and the surface size up to date, never consumes the event.
+    fn handle(&mut self, event: &Event) -> Result<bool, Self::Error> {
+        self.window.handle(event)?;
+
+        let RawEvent::Window { window_id, event } = &event.raw else {
+            return Ok(false);
+        };
+        if *window_id != self.id() {
+            return Ok(false);
+        }
+
+        match event {
+            WindowEvent::Resized { .. } | WindowEvent::PixelSizeChanged { .. } => {
+                self.resize_surface()?
+            }
+            WindowEvent::Minimized => self.minimized = true,
+            WindowEvent::Restored | WindowEvent::Maximized => {
+                self.minimized = false;
+                // the size may have changed while minimized
+                self.reconfigure()?;
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
 }
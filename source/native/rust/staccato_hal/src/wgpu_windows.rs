@@ -0,0 +1,128 @@
+use crate::surface_options::SurfaceOptions;
+use crate::wgpu_context::WgpuRenderContext;
+use crate::wgpu_window::{WgpuWindow, WgpuWindowError};
+use crate::window::Window;
+use staccato_core::fallible::Fallible;
+use staccato_platform_api::window::WindowId;
+use staccato_shared::event::{Event, RawEvent};
+use staccato_shared::event_dispatcher::EventHandler;
+
+/// Windows sharing the instance, device and queue of one `WgpuRenderContext`.
+///
+/// Window events are routed to the window they belong to, `WindowClose` destroys that
+/// window's surface and then the window while the others stay open.
+#[derive(Debug)]
+pub struct WgpuWindows<'window> {
+    // declared first, so the windows are gone before the context
+    windows: Vec<WgpuWindow<'window>>,
+    context: WgpuRenderContext,
+}
+
+impl Drop for WgpuWindows<'_> {
+    fn drop(&mut self) {
+        // newest first, tool windows before the main window
+        while self.windows.pop().is_some() {}
+    }
+}
+
+impl<'window> WgpuWindows<'window> {
+    pub fn new(context: WgpuRenderContext) -> Self {
+        Self {
+            windows: Vec::new(),
+            context,
+        }
+    }
+
+    pub fn context(&self) -> &WgpuRenderContext {
+        &self.context
+    }
+
+    /// Create a surface for `window` on the shared device.
+    ///
+    /// The adapter was picked for the first window, call this for secondary windows.
+    pub fn open(
+        &mut self,
+        window: Window,
+        options: &SurfaceOptions,
+    ) -> Result<WindowId, WgpuWindowError> {
+        let window = WgpuWindow::from_window(self.context.clone(), window, options)?;
+        Ok(self.insert(window))
+    }
+
+    /// Add a window created from this context, e.g. by `WgpuRenderContext::new_with_window`.
+    pub fn insert(&mut self, window: WgpuWindow<'window>) -> WindowId {
+        let id = window.id();
+        self.windows.push(window);
+        id
+    }
+
+    /// Destroy the window, returns false if there is no window with `id`.
+    pub fn close(&mut self, id: WindowId) -> bool {
+        match self.index_of(id) {
+            Some(index) => {
+                // keep the opening order, it is also the teardown order
+                self.windows.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, id: WindowId) -> Option<&WgpuWindow<'window>> {
+        self.windows.iter().find(|window| window.id() == id)
+    }
+
+    pub fn get_mut(&mut self, id: WindowId) -> Option<&mut WgpuWindow<'window>> {
+        self.windows.iter_mut().find(|window| window.id() == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &WgpuWindow<'window>> {
+        self.windows.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut WgpuWindow<'window>> {
+        self.windows.iter_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    fn index_of(&self, id: WindowId) -> Option<usize> {
+        self.windows.iter().position(|window| window.id() == id)
+    }
+}
+
+impl<'window> From<(WgpuRenderContext, WgpuWindow<'window>)> for WgpuWindows<'window> {
+    fn from((context, window): (WgpuRenderContext, WgpuWindow<'window>)) -> Self {
+        let mut windows = Self::new(context);
+        windows.insert(window);
+        windows
+    }
+}
+
+impl Fallible for WgpuWindows<'_> {
+    type Error = WgpuWindowError;
+}
+
+impl EventHandler for WgpuWindows<'_> {
+    /// Route window events by ID, never consumes the event.
+    fn handle(&mut self, event: &Event) -> Result<bool, Self::Error> {
+        match event.raw {
+            RawEvent::Window { window_id, .. } => {
+                if let Some(window) = self.get_mut(window_id) {
+                    window.handle(event)?;
+                }
+            }
+            RawEvent::WindowClose { id } => {
+                self.close(id);
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+}
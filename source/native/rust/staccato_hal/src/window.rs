@@ -1,19 +1,44 @@
+use crate::display::display_mode_from_sdl;
 use crate::error::SdlError;
-use ::staccato_core::rect::Size;
+use ::staccato_core::rect::{Point, Rect, Size};
 use ::std::ptr::NonNull;
 use raw_window_handle::{
     AppKitDisplayHandle, AppKitWindowHandle, DisplayHandle, HandleError, RawDisplayHandle,
     RawWindowHandle, Win32WindowHandle, WindowsDisplayHandle,
 };
+use sdl3_sys::keyboard::{
+    SDL_ClearComposition, SDL_PROP_TEXTINPUT_AUTOCORRECT_BOOLEAN,
+    SDL_PROP_TEXTINPUT_CAPITALIZATION_NUMBER, SDL_PROP_TEXTINPUT_MULTILINE_BOOLEAN,
+    SDL_PROP_TEXTINPUT_TYPE_NUMBER, SDL_SetTextInputArea, SDL_StartTextInputWithProperties,
+    SDL_StopTextInput, SDL_TextInputActive,
+};
+use sdl3_sys::pixels::SDL_PixelFormat;
+use sdl3_sys::properties::{
+    SDL_CreateProperties, SDL_DestroyProperties, SDL_SetBooleanProperty, SDL_SetNumberProperty,
+};
+use sdl3_sys::rect::SDL_Rect;
+use sdl3_sys::surface::{SDL_CreateSurfaceFrom, SDL_DestroySurface};
 use sdl3_sys::video::{
-    SDL_DestroyWindow, SDL_PROP_WINDOW_WIN32_HWND_POINTER, SDL_PROP_WINDOW_WIN32_INSTANCE_POINTER,
-    SDL_ShowWindow,
+    SDL_DestroyWindow, SDL_DisplayID, SDL_DisplayMode, SDL_GetClosestFullscreenDisplayMode,
+    SDL_GetDisplayForWindow, SDL_GetWindowDisplayScale, SDL_GetWindowFlags,
+    SDL_GetWindowFullscreenMode, SDL_GetWindowMaximumSize, SDL_GetWindowMinimumSize,
+    SDL_GetWindowOpacity, SDL_GetWindowPosition, SDL_GetWindowSizeInPixels, SDL_MaximizeWindow,
+    SDL_MinimizeWindow, SDL_PROP_WINDOW_WIN32_HWND_POINTER, SDL_PROP_WINDOW_WIN32_INSTANCE_POINTER,
+    SDL_RaiseWindow, SDL_RestoreWindow, SDL_SetWindowAlwaysOnTop, SDL_SetWindowFullscreen,
+    SDL_SetWindowFullscreenMode, SDL_SetWindowIcon, SDL_SetWindowMaximumSize,
+    SDL_SetWindowMinimumSize, SDL_SetWindowOpacity, SDL_SetWindowPosition, SDL_ShowWindow,
+    SDL_WindowFlags,
 };
 use smol_str::{SmolStr, ToSmolStr};
 use staccato_core::fallible::Fallible;
 use staccato_core::id::HasId;
 use staccato_core::spatial::{HasSize, Resizable};
-use staccato_platform_api::window::{WindowBackend, WindowId};
+use staccato_platform_api::text_input::TextInputOptions;
+use staccato_platform_api::window::{
+    FullscreenMode, WindowBackend, WindowIcon, WindowId, WindowMetrics,
+};
+use staccato_shared::event::{Event, RawEvent, WindowEvent};
+use staccato_shared::event_dispatcher::EventHandler;
 #[cfg(target_os = "macos")]
 use std::ffi::c_void;
 
@@ -36,13 +61,37 @@ pub struct WindowHandler {
 pub struct WindowOption {
     pub title: String,
     pub size: Size,
+    /// `None` lets the platform place the window.
+    pub position: Option<Point>,
+    pub minimum_size: Option<Size>,
+    pub maximum_size: Option<Size>,
+    pub fullscreen: FullscreenMode,
+    pub always_on_top: bool,
+    pub opacity: f32,
+    pub icon: Option<WindowIcon>,
+}
+
+impl Default for WindowOption {
+    fn default() -> Self {
+        Self {
+            title: "Staccato".into(),
+            size: Size::new(1280, 720),
+            position: None,
+            minimum_size: None,
+            maximum_size: None,
+            fullscreen: FullscreenMode::Windowed,
+            always_on_top: false,
+            opacity: 1.0,
+            icon: None,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct Window {
     window: WindowHandler,
     title: SmolStr,
-    size: Size,
+    metrics: WindowMetrics,
 }
 
 impl HasId for Window {
@@ -55,8 +104,9 @@ impl HasId for Window {
 impl HasSize for Window {
     type SizeType = Size;
 
+    /// In logical units, see `WindowBackend::pixel_size` for rendering.
     fn get_size(&self) -> Self::SizeType {
-        self.size
+        self.metrics.size
     }
 }
 
@@ -70,10 +120,34 @@ impl Fallible for Window {
     type Error = SdlError;
 }
 
+impl EventHandler for Window {
+    /// Keep the cached metrics up to date, never consumes the event.
+    fn handle(&mut self, event: &Event) -> Result<bool, Self::Error> {
+        let RawEvent::Window { window_id, event } = &event.raw else {
+            return Ok(false);
+        };
+        if *window_id != self.window.id {
+            return Ok(false);
+        }
+
+        match event {
+            WindowEvent::Resized { size } => self.metrics.size = Size::new(size.x, size.y),
+            WindowEvent::PixelSizeChanged { size } => {
+                self.metrics.pixel_size = Size::new(size.x, size.y)
+            }
+            WindowEvent::DisplayScaleChanged | WindowEvent::DisplayChanged { .. } => {
+                self.metrics.display_scale = self.window.display_scale()?
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+}
+
 impl Window {
     pub fn update(&mut self) -> Result<(), SdlError> {
         self.title = self.window.title()?;
-        self.size = self.window.size()?;
+        self.metrics = self.window.metrics()?;
         Ok(())
     }
 
@@ -88,11 +162,11 @@ impl Window {
     pub fn new(option: WindowOption) -> Result<Self, SdlError> {
         let window = WindowHandler::new(option)?;
         let title = window.title()?;
-        let size = window.size()?;
+        let metrics = window.metrics()?;
         Ok(Self {
             window,
             title,
-            size,
+            metrics,
         })
     }
 }
@@ -106,6 +180,10 @@ impl WindowBackend for Window {
         self.window.set_title(title)
     }
 
+    fn metrics(&self) -> WindowMetrics {
+        self.metrics
+    }
+
     fn handler(&self) -> Box<dyn staccato_platform_api::window::WindowHandle + 'static> {
         self.window.handler()
     }
@@ -121,13 +199,141 @@ impl WindowBackend for Window {
     fn is_open(&self) -> bool {
         self.window.is_open
     }
+
+    fn start_text_input(&mut self, options: &TextInputOptions) -> Result<(), Self::Error> {
+        self.window.start_text_input(options)
+    }
+
+    fn stop_text_input(&mut self) -> Result<(), Self::Error> {
+        self.window.stop_text_input()
+    }
+
+    fn is_text_input_active(&self) -> bool {
+        self.window.is_text_input_active()
+    }
+
+    fn set_text_input_area(&mut self, area: Rect, cursor: i32) -> Result<(), Self::Error> {
+        self.window.set_text_input_area(area, cursor)
+    }
+
+    fn clear_composition(&mut self) -> Result<(), Self::Error> {
+        self.window.clear_composition()
+    }
+
+    fn position(&self) -> Result<Point, Self::Error> {
+        self.window.position()
+    }
+
+    fn set_position(&mut self, position: Point) -> Result<(), Self::Error> {
+        self.window.set_position(position)
+    }
+
+    fn minimum_size(&self) -> Result<Option<Size>, Self::Error> {
+        self.window.minimum_size()
+    }
+
+    fn set_minimum_size(&mut self, size: Option<Size>) -> Result<(), Self::Error> {
+        self.window.set_minimum_size(size)
+    }
+
+    fn maximum_size(&self) -> Result<Option<Size>, Self::Error> {
+        self.window.maximum_size()
+    }
+
+    fn set_maximum_size(&mut self, size: Option<Size>) -> Result<(), Self::Error> {
+        self.window.set_maximum_size(size)
+    }
+
+    fn minimize(&mut self) -> Result<(), Self::Error> {
+        self.window.minimize()
+    }
+
+    fn maximize(&mut self) -> Result<(), Self::Error> {
+        self.window.maximize()
+    }
+
+    fn restore(&mut self) -> Result<(), Self::Error> {
+        self.window.restore()
+    }
+
+    fn is_minimized(&self) -> bool {
+        self.window.is_minimized()
+    }
+
+    fn is_maximized(&self) -> bool {
+        self.window.is_maximized()
+    }
+
+    fn fullscreen(&self) -> FullscreenMode {
+        self.window.fullscreen()
+    }
+
+    fn set_fullscreen(&mut self, mode: FullscreenMode) -> Result<(), Self::Error> {
+        self.window.set_fullscreen(mode)
+    }
+
+    fn is_always_on_top(&self) -> bool {
+        self.window.is_always_on_top()
+    }
+
+    fn set_always_on_top(&mut self, on_top: bool) -> Result<(), Self::Error> {
+        self.window.set_always_on_top(on_top)
+    }
+
+    fn opacity(&self) -> Result<f32, Self::Error> {
+        self.window.opacity()
+    }
+
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), Self::Error> {
+        self.window.set_opacity(opacity)
+    }
+
+    fn set_icon(&mut self, icon: &WindowIcon) -> Result<(), Self::Error> {
+        self.window.set_icon(icon)
+    }
+
+    fn request_focus(&mut self) -> Result<(), Self::Error> {
+        self.window.request_focus()
+    }
 }
 
 impl WindowHandler {
     pub fn new(option: WindowOption) -> Result<Self, SdlError> {
-        let title = option.title;
-        let width = option.size.width;
-        let height = option.size.height;
+        // the window is destroyed by `Drop` if applying the options fails
+        let mut window = Self::create(&option.title, option.size)?;
+        window.apply(&option)?;
+        Ok(window)
+    }
+
+    fn apply(&mut self, option: &WindowOption) -> Result<(), SdlError> {
+        if let Some(position) = option.position {
+            self.set_position(position)?;
+        }
+        if option.minimum_size.is_some() {
+            self.set_minimum_size(option.minimum_size)?;
+        }
+        if option.maximum_size.is_some() {
+            self.set_maximum_size(option.maximum_size)?;
+        }
+        if let Some(icon) = &option.icon {
+            self.set_icon(icon)?;
+        }
+        // not every platform supports these, only touch them when asked to
+        if option.always_on_top {
+            self.set_always_on_top(true)?;
+        }
+        if option.opacity < 1.0 {
+            self.set_opacity(option.opacity)?;
+        }
+        if option.fullscreen != FullscreenMode::Windowed {
+            self.set_fullscreen(option.fullscreen)?;
+        }
+        Ok(())
+    }
+
+    fn create(title: &str, size: Size) -> Result<Self, SdlError> {
+        let width = size.width;
+        let height = size.height;
 
         let is_open = true;
 
@@ -272,6 +478,35 @@ impl WindowHandler {
         Ok(Size::new(w, h))
     }
 
+    pub fn pixel_size(&self) -> Result<Size, SdlError> {
+        self.checked_open()?;
+        let mut w = 0;
+        let mut h = 0;
+        unsafe {
+            if !SDL_GetWindowSizeInPixels(self.as_ptr(), &mut w, &mut h) {
+                return Err(SdlError::sdl_err("failed to get window size in pixels"));
+            }
+        }
+        Ok(Size::new(w, h))
+    }
+
+    pub fn display_scale(&self) -> Result<f32, SdlError> {
+        self.checked_open()?;
+        let scale = unsafe { SDL_GetWindowDisplayScale(self.as_ptr()) };
+        if scale <= 0.0 {
+            return Err(SdlError::sdl_err("failed to get window display scale"));
+        }
+        Ok(scale)
+    }
+
+    pub fn metrics(&self) -> Result<WindowMetrics, SdlError> {
+        Ok(WindowMetrics {
+            size: self.size()?,
+            pixel_size: self.pixel_size()?,
+            display_scale: self.display_scale()?,
+        })
+    }
+
     pub fn set_size(&mut self, size: Size) -> Result<(), SdlError> {
         self.checked_open()?;
         unsafe {
@@ -328,6 +563,319 @@ impl WindowHandler {
         Ok(())
     }
 
+    pub fn start_text_input(&mut self, options: &TextInputOptions) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            let props = SDL_CreateProperties();
+            if props.0 == 0 {
+                return Err(SdlError::sdl_err("failed to create text input properties"));
+            }
+
+            let started = SDL_SetNumberProperty(
+                props,
+                SDL_PROP_TEXTINPUT_TYPE_NUMBER,
+                options.input_type as i64,
+            ) && SDL_SetNumberProperty(
+                props,
+                SDL_PROP_TEXTINPUT_CAPITALIZATION_NUMBER,
+                options.capitalization as i64,
+            ) && SDL_SetBooleanProperty(
+                props,
+                SDL_PROP_TEXTINPUT_AUTOCORRECT_BOOLEAN,
+                options.autocorrect,
+            ) && SDL_SetBooleanProperty(
+                props,
+                SDL_PROP_TEXTINPUT_MULTILINE_BOOLEAN,
+                options.multiline,
+            ) && SDL_StartTextInputWithProperties(self.as_ptr(), props);
+
+            SDL_DestroyProperties(props);
+
+            if !started {
+                return Err(SdlError::sdl_err("failed to start text input"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn stop_text_input(&mut self) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            if !SDL_StopTextInput(self.as_ptr()) {
+                return Err(SdlError::sdl_err("failed to stop text input"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_text_input_active(&self) -> bool {
+        self.is_open && unsafe { SDL_TextInputActive(self.as_ptr()) }
+    }
+
+    pub fn set_text_input_area(&mut self, area: Rect, cursor: i32) -> Result<(), SdlError> {
+        self.checked_open()?;
+        let rect = SDL_Rect {
+            x: area.position.x,
+            y: area.position.y,
+            w: area.size.width,
+            h: area.size.height,
+        };
+        unsafe {
+            if !SDL_SetTextInputArea(self.as_ptr(), &rect, cursor) {
+                return Err(SdlError::sdl_err("failed to set text input area"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn clear_composition(&mut self) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            if !SDL_ClearComposition(self.as_ptr()) {
+                return Err(SdlError::sdl_err("failed to clear composition"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn position(&self) -> Result<Point, SdlError> {
+        self.checked_open()?;
+        let mut x = 0;
+        let mut y = 0;
+        unsafe {
+            if !SDL_GetWindowPosition(self.as_ptr(), &mut x, &mut y) {
+                return Err(SdlError::sdl_err("failed to get window position"));
+            }
+        }
+        Ok(Point::new(x, y))
+    }
+
+    pub fn set_position(&mut self, position: Point) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            if !SDL_SetWindowPosition(self.as_ptr(), position.x, position.y) {
+                return Err(SdlError::sdl_err("failed to set window position"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn minimum_size(&self) -> Result<Option<Size>, SdlError> {
+        self.checked_open()?;
+        let mut w = 0;
+        let mut h = 0;
+        unsafe {
+            if !SDL_GetWindowMinimumSize(self.as_ptr(), &mut w, &mut h) {
+                return Err(SdlError::sdl_err("failed to get window minimum size"));
+            }
+        }
+        // SDL reports no limit as zero
+        Ok((w > 0 || h > 0).then(|| Size::new(w, h)))
+    }
+
+    /// `None` removes the limit.
+    pub fn set_minimum_size(&mut self, size: Option<Size>) -> Result<(), SdlError> {
+        self.checked_open()?;
+        let size = size.unwrap_or(Size::new(0, 0));
+        unsafe {
+            if !SDL_SetWindowMinimumSize(self.as_ptr(), size.width, size.height) {
+                return Err(SdlError::sdl_err("failed to set window minimum size"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn maximum_size(&self) -> Result<Option<Size>, SdlError> {
+        self.checked_open()?;
+        let mut w = 0;
+        let mut h = 0;
+        unsafe {
+            if !SDL_GetWindowMaximumSize(self.as_ptr(), &mut w, &mut h) {
+                return Err(SdlError::sdl_err("failed to get window maximum size"));
+            }
+        }
+        Ok((w > 0 || h > 0).then(|| Size::new(w, h)))
+    }
+
+    /// `None` removes the limit.
+    pub fn set_maximum_size(&mut self, size: Option<Size>) -> Result<(), SdlError> {
+        self.checked_open()?;
+        let size = size.unwrap_or(Size::new(0, 0));
+        unsafe {
+            if !SDL_SetWindowMaximumSize(self.as_ptr(), size.width, size.height) {
+                return Err(SdlError::sdl_err("failed to set window maximum size"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn minimize(&mut self) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            if !SDL_MinimizeWindow(self.as_ptr()) {
+                return Err(SdlError::sdl_err("failed to minimize window"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn maximize(&mut self) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            if !SDL_MaximizeWindow(self.as_ptr()) {
+                return Err(SdlError::sdl_err("failed to maximize window"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn restore(&mut self) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            if !SDL_RestoreWindow(self.as_ptr()) {
+                return Err(SdlError::sdl_err("failed to restore window"));
+            }
+        }
+        Ok(())
+    }
+
+    fn has_flag(&self, flag: SDL_WindowFlags) -> bool {
+        self.is_open && unsafe { SDL_GetWindowFlags(self.as_ptr()).0 & flag.0 != 0 }
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        self.has_flag(SDL_WindowFlags::MINIMIZED)
+    }
+
+    pub fn is_maximized(&self) -> bool {
+        self.has_flag(SDL_WindowFlags::MAXIMIZED)
+    }
+
+    pub fn fullscreen(&self) -> FullscreenMode {
+        if !self.has_flag(SDL_WindowFlags::FULLSCREEN) {
+            return FullscreenMode::Windowed;
+        }
+        // SDL has no exclusive mode set for borderless fullscreen windows
+        match unsafe { SDL_GetWindowFullscreenMode(self.as_ptr()).as_ref() } {
+            Some(mode) => FullscreenMode::Exclusive(display_mode_from_sdl(mode)),
+            None => FullscreenMode::Borderless,
+        }
+    }
+
+    pub fn set_fullscreen(&mut self, mode: FullscreenMode) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            match mode {
+                FullscreenMode::Windowed => {
+                    if !SDL_SetWindowFullscreen(self.as_ptr(), false) {
+                        return Err(SdlError::sdl_err("failed to leave fullscreen"));
+                    }
+                }
+                FullscreenMode::Borderless => {
+                    if !SDL_SetWindowFullscreenMode(self.as_ptr(), std::ptr::null())
+                        || !SDL_SetWindowFullscreen(self.as_ptr(), true)
+                    {
+                        return Err(SdlError::sdl_err("failed to enter borderless fullscreen"));
+                    }
+                }
+                FullscreenMode::Exclusive(requested) => {
+                    let display_id = if requested.display_id == 0 {
+                        SDL_GetDisplayForWindow(self.as_ptr())
+                    } else {
+                        SDL_DisplayID(requested.display_id as u32)
+                    };
+
+                    let mut closest: SDL_DisplayMode = std::mem::zeroed();
+                    if !SDL_GetClosestFullscreenDisplayMode(
+                        display_id,
+                        requested.size.width,
+                        requested.size.height,
+                        requested.refresh_rate,
+                        requested.pixel_density != 1.0,
+                        &mut closest,
+                    ) {
+                        return Err(SdlError::sdl_err("no matching fullscreen display mode"));
+                    }
+
+                    if !SDL_SetWindowFullscreenMode(self.as_ptr(), &closest)
+                        || !SDL_SetWindowFullscreen(self.as_ptr(), true)
+                    {
+                        return Err(SdlError::sdl_err("failed to enter exclusive fullscreen"));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_always_on_top(&self) -> bool {
+        self.has_flag(SDL_WindowFlags::ALWAYS_ON_TOP)
+    }
+
+    pub fn set_always_on_top(&mut self, on_top: bool) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            if !SDL_SetWindowAlwaysOnTop(self.as_ptr(), on_top) {
+                return Err(SdlError::sdl_err("failed to set window always on top"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn opacity(&self) -> Result<f32, SdlError> {
+        self.checked_open()?;
+        let opacity = unsafe { SDL_GetWindowOpacity(self.as_ptr()) };
+        if opacity < 0.0 {
+            return Err(SdlError::sdl_err("failed to get window opacity"));
+        }
+        Ok(opacity)
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            if !SDL_SetWindowOpacity(self.as_ptr(), opacity.clamp(0.0, 1.0)) {
+                return Err(SdlError::sdl_err("failed to set window opacity"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_icon(&mut self, icon: &WindowIcon) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            // SDL only reads the pixels, the surface does not outlive this call
+            let surface = SDL_CreateSurfaceFrom(
+                icon.size().width,
+                icon.size().height,
+                SDL_PixelFormat::RGBA32,
+                icon.pixels().as_ptr().cast_mut().cast(),
+                icon.pitch(),
+            );
+            if surface.is_null() {
+                return Err(SdlError::sdl_err("failed to create window icon surface"));
+            }
+
+            let set = SDL_SetWindowIcon(self.as_ptr(), surface);
+            SDL_DestroySurface(surface);
+
+            if !set {
+                return Err(SdlError::sdl_err("failed to set window icon"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn request_focus(&mut self) -> Result<(), SdlError> {
+        self.checked_open()?;
+        unsafe {
+            if !SDL_RaiseWindow(self.as_ptr()) {
+                return Err(SdlError::sdl_err("failed to raise window"));
+            }
+        }
+        Ok(())
+    }
+
     fn checked_open(&self) -> Result<(), SdlError> {
         if !self.is_open {
             Err(SdlError::sdl_err("try to operate a closed window"))
@@ -0,0 +1,93 @@
+use smol_str::SmolStr;
+use staccato_core::fallible::Fallible;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// MIME type of plain UTF-8 text.
+pub const TEXT_MIME_TYPE: &str = "text/plain;charset=utf-8";
+
+/// Data offered to the clipboard, one entry per MIME type.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClipboardContent {
+    entries: Vec<(SmolStr, Arc<[u8]>)>,
+}
+
+impl ClipboardContent {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add or replace the data of `mime_type`.
+    pub fn with(mut self, mime_type: &str, data: impl Into<Arc<[u8]>>) -> Self {
+        self.insert(mime_type, data);
+        self
+    }
+
+    /// Add or replace the data of `mime_type`.
+    pub fn insert(&mut self, mime_type: &str, data: impl Into<Arc<[u8]>>) {
+        let data = data.into();
+        match self.entries.iter_mut().find(|(mime, _)| mime == mime_type) {
+            Some(entry) => entry.1 = data,
+            None => self.entries.push((mime_type.into(), data)),
+        }
+    }
+
+    pub fn get(&self, mime_type: &str) -> Option<&[u8]> {
+        self.entries
+            .iter()
+            .find(|(mime, _)| mime == mime_type)
+            .map(|(_, data)| &data[..])
+    }
+
+    pub fn mime_types(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|(mime, _)| mime.as_str())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// The system clipboard. `RawEvent::ClipboardUpdate` is sent when its content changes.
+///
+/// Must be used on the main thread.
+pub trait Clipboard: Debug + Fallible {
+    /// Empty if the clipboard holds no text.
+    fn text(&self) -> Result<String, Self::Error>;
+    fn set_text(&mut self, text: &str) -> Result<(), Self::Error>;
+    fn has_text(&self) -> bool;
+
+    /// The X11/Wayland primary selection, set by selecting text and pasted with the middle
+    /// mouse button. Other platforms keep it inside the application.
+    fn primary_selection_text(&self) -> Result<String, Self::Error>;
+    fn set_primary_selection_text(&mut self, text: &str) -> Result<(), Self::Error>;
+    fn has_primary_selection_text(&self) -> bool;
+
+    /// `None` if there is no data for `mime_type`.
+    fn data(&self, mime_type: &str) -> Result<Option<Vec<u8>>, Self::Error>;
+    /// Replace the clipboard content, other applications can read every entry of `content`.
+    fn set_data(&mut self, content: ClipboardContent) -> Result<(), Self::Error>;
+    fn has_data(&self, mime_type: &str) -> bool;
+    /// MIME types currently offered by the clipboard.
+    fn mime_types(&self) -> Result<Vec<SmolStr>, Self::Error>;
+    fn clear(&mut self) -> Result<(), Self::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_replaces_existing_mime_type() {
+        let content = ClipboardContent::new()
+            .with(TEXT_MIME_TYPE, &b"old"[..])
+            .with("image/png", vec![1, 2, 3])
+            .with(TEXT_MIME_TYPE, &b"new"[..]);
+
+        assert_eq!(content.get(TEXT_MIME_TYPE), Some(&b"new"[..]));
+        assert_eq!(
+            content.mime_types().collect::<Vec<_>>(),
+            [TEXT_MIME_TYPE, "image/png"]
+        );
+    }
+}
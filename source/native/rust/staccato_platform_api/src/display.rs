@@ -0,0 +1,117 @@
+use smol_str::SmolStr;
+use staccato_core::fallible::Fallible;
+use staccato_core::rect::{Rect, Size};
+pub use staccato_shared::event::DisplayOrientation;
+pub use staccato_shared::id::DisplayId;
+use std::fmt::Debug;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayMode {
+    pub display_id: DisplayId,
+    /// backend specific pixel format, 0 if any
+    pub format: u32,
+    /// in logical units
+    pub size: Size,
+    /// pixels per logical unit, e.g. 2.0 for a HiDPI mode
+    pub pixel_density: f32,
+    /// in Hz, 0.0 if unknown
+    pub refresh_rate: f32,
+    /// exact refresh rate is `numerator / denominator`, both 0 if unknown
+    pub refresh_rate_numerator: i32,
+    pub refresh_rate_denominator: i32,
+}
+
+impl DisplayMode {
+    /// Size in pixels.
+    pub fn pixel_size(&self) -> Size {
+        Size::new(
+            (self.size.width as f32 * self.pixel_density).round() as i32,
+            (self.size.height as f32 * self.pixel_density).round() as i32,
+        )
+    }
+}
+
+/// Everything known about one display, see `DisplayService::info`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayInfo {
+    pub id: DisplayId,
+    pub name: SmolStr,
+    /// in desktop coordinates
+    pub bounds: Rect,
+    /// `bounds` without task bars, docks and menu bars
+    pub usable_bounds: Rect,
+    /// UI scale the user picked, e.g. 1.5 for 150%
+    pub content_scale: f32,
+    pub orientation: DisplayOrientation,
+    pub natural_orientation: DisplayOrientation,
+    pub desktop_mode: DisplayMode,
+    pub current_mode: DisplayMode,
+}
+
+impl DisplayInfo {
+    /// Refresh rate of the current mode in Hz, 0.0 if unknown.
+    pub fn refresh_rate(&self) -> f32 {
+        self.current_mode.refresh_rate
+    }
+}
+
+/// Lists the connected displays. `RawEvent::Display` is sent when they change.
+///
+/// Must be used on the main thread.
+pub trait DisplayService: Debug + Fallible {
+    fn displays(&self) -> Result<Vec<DisplayId>, Self::Error>;
+    fn primary_display(&self) -> Result<DisplayId, Self::Error>;
+    fn name(&self, display_id: DisplayId) -> Result<SmolStr, Self::Error>;
+    fn bounds(&self, display_id: DisplayId) -> Result<Rect, Self::Error>;
+    fn usable_bounds(&self, display_id: DisplayId) -> Result<Rect, Self::Error>;
+    fn content_scale(&self, display_id: DisplayId) -> Result<f32, Self::Error>;
+    fn orientation(&self, display_id: DisplayId) -> DisplayOrientation;
+    fn natural_orientation(&self, display_id: DisplayId) -> DisplayOrientation;
+    /// The mode of the desktop, used by windowed and borderless fullscreen windows.
+    fn desktop_mode(&self, display_id: DisplayId) -> Result<DisplayMode, Self::Error>;
+    /// Differs from `desktop_mode` while a window is in exclusive fullscreen.
+    fn current_mode(&self, display_id: DisplayId) -> Result<DisplayMode, Self::Error>;
+    /// Modes usable by `FullscreenMode::Exclusive`, largest and fastest first.
+    fn fullscreen_modes(&self, display_id: DisplayId) -> Result<Vec<DisplayMode>, Self::Error>;
+
+    fn info(&self, display_id: DisplayId) -> Result<DisplayInfo, Self::Error> {
+        Ok(DisplayInfo {
+            id: display_id,
+            name: self.name(display_id)?,
+            bounds: self.bounds(display_id)?,
+            usable_bounds: self.usable_bounds(display_id)?,
+            content_scale: self.content_scale(display_id)?,
+            orientation: self.orientation(display_id),
+            natural_orientation: self.natural_orientation(display_id),
+            desktop_mode: self.desktop_mode(display_id)?,
+            current_mode: self.current_mode(display_id)?,
+        })
+    }
+
+    /// `info` of every connected display.
+    fn all_info(&self) -> Result<Vec<DisplayInfo>, Self::Error> {
+        self.displays()?
+            .into_iter()
+            .map(|display_id| self.info(display_id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_size_applies_density() {
+        let mode = DisplayMode {
+            display_id: 1,
+            format: 0,
+            size: Size::new(1440, 900),
+            pixel_density: 2.0,
+            refresh_rate: 59.94,
+            refresh_rate_numerator: 60000,
+            refresh_rate_denominator: 1001,
+        };
+        assert_eq!(mode.pixel_size(), Size::new(2880, 1800));
+    }
+}
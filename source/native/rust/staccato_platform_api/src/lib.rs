@@ -1,3 +1,6 @@
+pub mod clipboard;
+pub mod display;
+pub mod text_input;
 pub mod window;
 
 pub fn add(left: u64, right: u64) -> u64 {
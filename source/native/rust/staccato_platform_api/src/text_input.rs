@@ -0,0 +1,48 @@
+/// Kind of text being edited, lets the platform pick a fitting on-screen keyboard.
+///
+/// Values match `SDL_TextInputType`.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextInputType {
+    #[default]
+    Text = 0,
+    Name = 1,
+    Email = 2,
+    Username = 3,
+    PasswordHidden = 4,
+    PasswordVisible = 5,
+    Number = 6,
+    NumberPasswordHidden = 7,
+    NumberPasswordVisible = 8,
+}
+
+/// Values match `SDL_Capitalization`.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Capitalization {
+    #[default]
+    None = 0,
+    Sentences = 1,
+    Words = 2,
+    Letters = 3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextInputOptions {
+    pub input_type: TextInputType,
+    pub capitalization: Capitalization,
+    pub autocorrect: bool,
+    /// whether return inserts a new line instead of finishing the input
+    pub multiline: bool,
+}
+
+impl Default for TextInputOptions {
+    fn default() -> Self {
+        Self {
+            input_type: TextInputType::Text,
+            capitalization: Capitalization::None,
+            autocorrect: true,
+            multiline: false,
+        }
+    }
+}
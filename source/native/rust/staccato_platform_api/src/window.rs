@@ -1,8 +1,11 @@
+use crate::display::DisplayMode;
+use crate::text_input::TextInputOptions;
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use smol_str::SmolStr;
 use staccato_core::fallible::Fallible;
+use staccato_core::frect::FPoint;
 use staccato_core::id::HasId;
-use staccato_core::rect::Size;
+use staccato_core::rect::{Point, Rect, Size};
 use staccato_core::spatial::Resizable;
 pub use staccato_shared::id::WindowId;
 use std::fmt::Debug;
@@ -11,13 +14,184 @@ pub trait WindowHandle: Send + Sync + HasWindowHandle + HasDisplayHandle {}
 
 impl<T: Send + Sync + HasWindowHandle + HasDisplayHandle> WindowHandle for T {}
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FullscreenMode {
+    #[default]
+    Windowed,
+    /// Cover the display at the desktop resolution.
+    Borderless,
+    /// Switch the display to the mode closest to the requested one.
+    ///
+    /// A `display_id` of 0 selects the display the window is on, a zero refresh rate or
+    /// pixel density matches any.
+    Exclusive(DisplayMode),
+}
+
+/// Logical size, pixel size and display scale of a window.
+///
+/// Window coordinates (sizes, mouse positions) are in logical units, rendering is in pixels.
+/// They differ on HiDPI displays, e.g. a 1280x720 window is 2560x1440 pixels at 200%.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowMetrics {
+    /// in logical units
+    pub size: Size,
+    /// size of the drawable area in pixels, use it for the render surface
+    pub pixel_size: Size,
+    /// pixel density times the content scale of the display, use it to scale the UI
+    pub display_scale: f32,
+}
+
+impl WindowMetrics {
+    /// Pixels per logical unit on each axis, 1.0 when either size is empty (e.g. minimized).
+    pub fn pixel_density(&self) -> FPoint {
+        let density = |pixels: i32, logical: i32| {
+            if pixels > 0 && logical > 0 {
+                pixels as f32 / logical as f32
+            } else {
+                1.0
+            }
+        };
+        FPoint::new(
+            density(self.pixel_size.width, self.size.width),
+            density(self.pixel_size.height, self.size.height),
+        )
+    }
+
+    /// Convert a point in window coordinates to pixel coordinates.
+    pub fn to_pixels(&self, point: FPoint) -> FPoint {
+        let density = self.pixel_density();
+        FPoint::new(point.x * density.x, point.y * density.y)
+    }
+
+    /// Convert a point in pixel coordinates to window coordinates.
+    pub fn to_window(&self, point: FPoint) -> FPoint {
+        let density = self.pixel_density();
+        FPoint::new(point.x / density.x, point.y / density.y)
+    }
+}
+
+/// Window icon as tightly packed RGBA8 pixels, row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowIcon {
+    size: Size,
+    pixels: Vec<u8>,
+}
+
+impl WindowIcon {
+    /// `None` if `size` is empty or `pixels` does not hold exactly `width * height` pixels.
+    pub fn new(size: Size, pixels: Vec<u8>) -> Option<Self> {
+        let width = usize::try_from(size.width).ok().filter(|w| *w > 0)?;
+        let height = usize::try_from(size.height).ok().filter(|h| *h > 0)?;
+        if width.checked_mul(height)?.checked_mul(4)? != pixels.len() {
+            return None;
+        }
+        Some(Self { size, pixels })
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Bytes per row.
+    pub fn pitch(&self) -> i32 {
+        self.size.width * 4
+    }
+}
+
 pub trait WindowBackend:
     Debug + HasId<Id = WindowId> + Resizable<SizeType = Size> + Fallible
 {
     fn title(&self) -> SmolStr;
+    fn metrics(&self) -> WindowMetrics;
+    /// Size of the drawable area in pixels.
+    fn pixel_size(&self) -> Size {
+        self.metrics().pixel_size
+    }
+    fn display_scale(&self) -> f32 {
+        self.metrics().display_scale
+    }
     fn set_title(&mut self, title: &str) -> Result<(), Self::Error>;
     fn handler(&self) -> Box<dyn WindowHandle + 'static>;
     fn hide(&mut self) -> Result<(), Self::Error>;
     fn show(&mut self) -> Result<(), Self::Error>;
     fn is_open(&self) -> bool;
+    /// Start sending `TextInput` and `TextEditing` events for this window,
+    /// shows the on-screen keyboard on platforms that have one.
+    fn start_text_input(&mut self, options: &TextInputOptions) -> Result<(), Self::Error>;
+    fn stop_text_input(&mut self) -> Result<(), Self::Error>;
+    fn is_text_input_active(&self) -> bool;
+    /// The edited text area in window coordinates, the IME places its candidate window
+    /// next to it. `cursor` is the cursor offset from `area.position.x`.
+    fn set_text_input_area(&mut self, area: Rect, cursor: i32) -> Result<(), Self::Error>;
+    /// Drop the current IME composition without committing it.
+    fn clear_composition(&mut self) -> Result<(), Self::Error>;
+    /// Position of the top left corner in desktop coordinates.
+    fn position(&self) -> Result<Point, Self::Error>;
+    fn set_position(&mut self, position: Point) -> Result<(), Self::Error>;
+    /// `None` if the window has no minimum size.
+    fn minimum_size(&self) -> Result<Option<Size>, Self::Error>;
+    fn set_minimum_size(&mut self, size: Option<Size>) -> Result<(), Self::Error>;
+    /// `None` if the window has no maximum size.
+    fn maximum_size(&self) -> Result<Option<Size>, Self::Error>;
+    fn set_maximum_size(&mut self, size: Option<Size>) -> Result<(), Self::Error>;
+    fn minimize(&mut self) -> Result<(), Self::Error>;
+    fn maximize(&mut self) -> Result<(), Self::Error>;
+    /// Undo `minimize` or `maximize`.
+    fn restore(&mut self) -> Result<(), Self::Error>;
+    fn is_minimized(&self) -> bool;
+    fn is_maximized(&self) -> bool;
+    fn fullscreen(&self) -> FullscreenMode;
+    fn set_fullscreen(&mut self, mode: FullscreenMode) -> Result<(), Self::Error>;
+    fn is_always_on_top(&self) -> bool;
+    fn set_always_on_top(&mut self, on_top: bool) -> Result<(), Self::Error>;
+    /// From 0.0 (transparent) to 1.0 (opaque).
+    fn opacity(&self) -> Result<f32, Self::Error>;
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), Self::Error>;
+    fn set_icon(&mut self, icon: &WindowIcon) -> Result<(), Self::Error>;
+    /// Raise the window and ask for input focus, the platform may only flash it instead.
+    fn request_focus(&mut self) -> Result<(), Self::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_between_window_and_pixels() {
+        let metrics = WindowMetrics {
+            size: Size::new(1280, 720),
+            pixel_size: Size::new(2560, 1440),
+            display_scale: 2.0,
+        };
+        assert_eq!(metrics.pixel_density(), FPoint::new(2.0, 2.0));
+        assert_eq!(
+            metrics.to_pixels(FPoint::new(10.0, 20.5)),
+            FPoint::new(20.0, 41.0)
+        );
+        assert_eq!(
+            metrics.to_window(FPoint::new(20.0, 41.0)),
+            FPoint::new(10.0, 20.5)
+        );
+
+        let minimized = WindowMetrics {
+            pixel_size: Size::new(0, 0),
+            ..metrics
+        };
+        assert_eq!(
+            minimized.to_window(FPoint::new(20.0, 41.0)),
+            FPoint::new(20.0, 41.0)
+        );
+    }
+
+    #[test]
+    fn icon_checks_pixel_count() {
+        assert!(WindowIcon::new(Size::new(2, 2), vec![0; 16]).is_some());
+        assert!(WindowIcon::new(Size::new(2, 2), vec![0; 12]).is_none());
+        assert!(WindowIcon::new(Size::new(0, 2), Vec::new()).is_none());
+        assert!(WindowIcon::new(Size::new(-1, -1), vec![0; 4]).is_none());
+    }
 }
@@ -0,0 +1,52 @@
+use crate::command::CommandBuffer;
+use crate::handle::{
+    BindGroupHandle, BufferHandle, PipelineHandle, SamplerHandle, ShaderHandle, TextureHandle,
+};
+use crate::resource::{
+    BindGroupDescriptor, BufferDescriptor, PipelineDescriptor, SamplerDescriptor, ShaderDescriptor,
+    TextureDescriptor, TextureFormat,
+};
+use staccato_core::fallible::Fallible;
+use staccato_core::rect::Size;
+use std::fmt::Debug;
+
+/// Executes `CommandBuffer`s and owns the resources their handles refer to.
+///
+/// Gameplay and render logic only see handles, so they run on any backend,
+/// including the GPU-less one used by tests.
+pub trait RenderBackend: Debug + Fallible {
+    fn create_texture(
+        &mut self,
+        descriptor: &TextureDescriptor,
+    ) -> Result<TextureHandle, Self::Error>;
+    fn destroy_texture(&mut self, texture: TextureHandle) -> Result<(), Self::Error>;
+    fn create_buffer(&mut self, descriptor: &BufferDescriptor)
+    -> Result<BufferHandle, Self::Error>;
+    fn destroy_buffer(&mut self, buffer: BufferHandle) -> Result<(), Self::Error>;
+    fn create_sampler(
+        &mut self,
+        descriptor: &SamplerDescriptor,
+    ) -> Result<SamplerHandle, Self::Error>;
+    fn destroy_sampler(&mut self, sampler: SamplerHandle) -> Result<(), Self::Error>;
+    fn create_shader(&mut self, descriptor: &ShaderDescriptor)
+    -> Result<ShaderHandle, Self::Error>;
+    fn destroy_shader(&mut self, shader: ShaderHandle) -> Result<(), Self::Error>;
+    fn create_pipeline(
+        &mut self,
+        descriptor: &PipelineDescriptor,
+    ) -> Result<PipelineHandle, Self::Error>;
+    fn destroy_pipeline(&mut self, pipeline: PipelineHandle) -> Result<(), Self::Error>;
+    fn create_bind_group(
+        &mut self,
+        descriptor: &BindGroupDescriptor,
+    ) -> Result<BindGroupHandle, Self::Error>;
+    fn destroy_bind_group(&mut self, bind_group: BindGroupHandle) -> Result<(), Self::Error>;
+
+    /// Format of `RenderTarget::Surface`, `None` without a surface.
+    fn surface_format(&self) -> Option<TextureFormat>;
+    /// Size of `RenderTarget::Surface` in pixels, `None` without a surface.
+    fn surface_size(&self) -> Option<Size>;
+
+    /// Execute `commands` in order, one call per frame.
+    fn submit(&mut self, commands: &CommandBuffer) -> Result<(), Self::Error>;
+}
@@ -0,0 +1,387 @@
+use crate::handle::{BindGroupHandle, BufferHandle, PipelineHandle, TextureHandle};
+use staccato_core::color::Color;
+use staccato_core::frect::FRect;
+use staccato_core::rect::{Point, Rect, Size};
+use std::ops::Range;
+
+/// Buffer offsets and sizes of writes and copies must be multiples of this.
+pub const COPY_BUFFER_ALIGNMENT: u64 = 4;
+
+/// What a render pass draws into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderTarget {
+    /// the frame of the window the backend presents to
+    Surface,
+    Texture(TextureHandle),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadOp {
+    /// keep what is already in the target
+    Load,
+    Clear(Color),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndexFormat {
+    Uint16,
+    Uint32,
+}
+
+/// Bytes stored in the data block of a `CommandBuffer`, see `CommandBuffer::data`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DataRange {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl DataRange {
+    pub fn range(self) -> Range<usize> {
+        self.offset..self.offset + self.len
+    }
+}
+
+/// One recorded command. Draw state is set inside a pass, writes and copies go outside.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderCommand {
+    BeginPass {
+        target: RenderTarget,
+        load: LoadOp,
+        /// depth attachment and the depth to clear it to, `None` to keep its content
+        depth: Option<(TextureHandle, Option<f32>)>,
+    },
+    EndPass,
+    SetViewport {
+        rect: FRect,
+        min_depth: f32,
+        max_depth: f32,
+    },
+    SetScissor {
+        rect: Rect,
+    },
+    SetPipeline {
+        pipeline: PipelineHandle,
+    },
+    SetBindGroup {
+        slot: u32,
+        bind_group: BindGroupHandle,
+    },
+    SetVertexBuffer {
+        slot: u32,
+        buffer: BufferHandle,
+        offset: u64,
+    },
+    SetIndexBuffer {
+        buffer: BufferHandle,
+        offset: u64,
+        format: IndexFormat,
+    },
+    Draw {
+        first_vertex: u32,
+        vertex_count: u32,
+        first_instance: u32,
+        instance_count: u32,
+    },
+    DrawIndexed {
+        first_index: u32,
+        index_count: u32,
+        base_vertex: i32,
+        first_instance: u32,
+        instance_count: u32,
+    },
+    WriteBuffer {
+        buffer: BufferHandle,
+        offset: u64,
+        data: DataRange,
+    },
+    /// tightly packed rows of `texture`'s format
+    WriteTexture {
+        texture: TextureHandle,
+        origin: Point,
+        size: Size,
+        data: DataRange,
+    },
+    CopyBufferToBuffer {
+        source: BufferHandle,
+        source_offset: u64,
+        destination: BufferHandle,
+        destination_offset: u64,
+        size: u64,
+    },
+    CopyTextureToTexture {
+        source: TextureHandle,
+        source_origin: Point,
+        destination: TextureHandle,
+        destination_origin: Point,
+        size: Size,
+    },
+}
+
+/// Commands of one frame, handed to `RenderBackend::submit`.
+///
+/// Commands are plain values in one `Vec` and the bytes of writes go to a second one.
+/// `clear` keeps both allocations, so a buffer reused every frame stops allocating once it
+/// reached its largest frame.
+///
+/// ```ignore
+/// commands
+///     .write_buffer(vertices, 0, bytes)
+///     .begin_pass(RenderTarget::Surface, LoadOp::Clear(Color::BLACK))
+///     .set_pipeline(pipeline)
+///     .set_vertex_buffer(0, vertices, 0)
+///     .draw(0..6, 0..1)
+///     .end_pass();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CommandBuffer {
+    commands: Vec<RenderCommand>,
+    data: Vec<u8>,
+}
+
+impl CommandBuffer {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Reserve room for `commands` commands and `data` bytes of writes.
+    pub fn with_capacity(commands: usize, data: usize) -> Self {
+        Self {
+            commands: Vec::with_capacity(commands),
+            data: Vec::with_capacity(data),
+        }
+    }
+
+    /// Forget the commands, keeping the allocations.
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.data.clear();
+    }
+
+    pub fn commands(&self) -> &[RenderCommand] {
+        &self.commands
+    }
+
+    /// The bytes of a `WriteBuffer` or `WriteTexture` command.
+    pub fn data(&self, range: DataRange) -> &[u8] {
+        self.data.get(range.range()).unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn push(&mut self, command: RenderCommand) -> &mut Self {
+        self.commands.push(command);
+        self
+    }
+
+    fn push_data(&mut self, bytes: &[u8]) -> DataRange {
+        let offset = self.data.len();
+        self.data.extend_from_slice(bytes);
+        DataRange {
+            offset,
+            len: bytes.len(),
+        }
+    }
+
+    pub fn begin_pass(&mut self, target: RenderTarget, load: LoadOp) -> &mut Self {
+        self.push(RenderCommand::BeginPass {
+            target,
+            load,
+            depth: None,
+        })
+    }
+
+    /// `clear_depth` of `None` keeps the content of `depth`.
+    pub fn begin_pass_with_depth(
+        &mut self,
+        target: RenderTarget,
+        load: LoadOp,
+        depth: TextureHandle,
+        clear_depth: Option<f32>,
+    ) -> &mut Self {
+        self.push(RenderCommand::BeginPass {
+            target,
+            load,
+            depth: Some((depth, clear_depth)),
+        })
+    }
+
+    pub fn end_pass(&mut self) -> &mut Self {
+        self.push(RenderCommand::EndPass)
+    }
+
+    pub fn set_viewport(&mut self, rect: FRect) -> &mut Self {
+        self.push(RenderCommand::SetViewport {
+            rect,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        })
+    }
+
+    pub fn set_scissor(&mut self, rect: Rect) -> &mut Self {
+        self.push(RenderCommand::SetScissor { rect })
+    }
+
+    pub fn set_pipeline(&mut self, pipeline: PipelineHandle) -> &mut Self {
+        self.push(RenderCommand::SetPipeline { pipeline })
+    }
+
+    pub fn set_bind_group(&mut self, slot: u32, bind_group: BindGroupHandle) -> &mut Self {
+        self.push(RenderCommand::SetBindGroup { slot, bind_group })
+    }
+
+    pub fn set_vertex_buffer(&mut self, slot: u32, buffer: BufferHandle, offset: u64) -> &mut Self {
+        self.push(RenderCommand::SetVertexBuffer {
+            slot,
+            buffer,
+            offset,
+        })
+    }
+
+    pub fn set_index_buffer(
+        &mut self,
+        buffer: BufferHandle,
+        offset: u64,
+        format: IndexFormat,
+    ) -> &mut Self {
+        self.push(RenderCommand::SetIndexBuffer {
+            buffer,
+            offset,
+            format,
+        })
+    }
+
+    pub fn draw(&mut self, vertices: Range<u32>, instances: Range<u32>) -> &mut Self {
+        self.push(RenderCommand::Draw {
+            first_vertex: vertices.start,
+            vertex_count: vertices.end.saturating_sub(vertices.start),
+            first_instance: instances.start,
+            instance_count: instances.end.saturating_sub(instances.start),
+        })
+    }
+
+    pub fn draw_indexed(
+        &mut self,
+        indices: Range<u32>,
+        base_vertex: i32,
+        instances: Range<u32>,
+    ) -> &mut Self {
+        self.push(RenderCommand::DrawIndexed {
+            first_index: indices.start,
+            index_count: indices.end.saturating_sub(indices.start),
+            base_vertex,
+            first_instance: instances.start,
+            instance_count: instances.end.saturating_sub(instances.start),
+        })
+    }
+
+    /// `bytes` are copied into the command buffer.
+    ///
+    /// `offset` and the length of `bytes` must be multiples of `COPY_BUFFER_ALIGNMENT`.
+    pub fn write_buffer(&mut self, buffer: BufferHandle, offset: u64, bytes: &[u8]) -> &mut Self {
+        let data = self.push_data(bytes);
+        self.push(RenderCommand::WriteBuffer {
+            buffer,
+            offset,
+            data,
+        })
+    }
+
+    /// `bytes` are tightly packed rows in the format of `texture`, copied into the command buffer.
+    pub fn write_texture(
+        &mut self,
+        texture: TextureHandle,
+        origin: Point,
+        size: Size,
+        bytes: &[u8],
+    ) -> &mut Self {
+        let data = self.push_data(bytes);
+        self.push(RenderCommand::WriteTexture {
+            texture,
+            origin,
+            size,
+            data,
+        })
+    }
+
+    /// Offsets and `size` must be multiples of `COPY_BUFFER_ALIGNMENT`.
+    pub fn copy_buffer_to_buffer(
+        &mut self,
+        source: BufferHandle,
+        source_offset: u64,
+        destination: BufferHandle,
+        destination_offset: u64,
+        size: u64,
+    ) -> &mut Self {
+        self.push(RenderCommand::CopyBufferToBuffer {
+            source,
+            source_offset,
+            destination,
+            destination_offset,
+            size,
+        })
+    }
+
+    pub fn copy_texture_to_texture(
+        &mut self,
+        source: TextureHandle,
+        source_origin: Point,
+        destination: TextureHandle,
+        destination_origin: Point,
+        size: Size,
+    ) -> &mut Self {
+        self.push(RenderCommand::CopyTextureToTexture {
+            source,
+            source_origin,
+            destination,
+            destination_origin,
+            size,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_commands_and_write_data() {
+        let buffer = BufferHandle::new(0, 0);
+        let pipeline = PipelineHandle::new(1, 0);
+        let mut commands = CommandBuffer::new();
+
+        commands
+            .write_buffer(buffer, 16, &[1, 2, 3, 4])
+            .begin_pass(RenderTarget::Surface, LoadOp::Clear(Color::BLACK))
+            .set_pipeline(pipeline)
+            .set_vertex_buffer(0, buffer, 0)
+            .draw(0..6, 0..1)
+            .end_pass();
+
+        assert_eq!(commands.len(), 6);
+        let RenderCommand::WriteBuffer { offset, data, .. } = commands.commands()[0] else {
+            unreachable!("expected a buffer write");
+        };
+        assert_eq!(offset, 16);
+        assert_eq!(commands.data(data), &[1, 2, 3, 4]);
+        assert_eq!(
+            commands.commands()[4],
+            RenderCommand::Draw {
+                first_vertex: 0,
+                vertex_count: 6,
+                first_instance: 0,
+                instance_count: 1,
+            }
+        );
+
+        let capacity = commands.commands.capacity();
+        commands.clear();
+        assert!(commands.is_empty());
+        assert_eq!(commands.commands.capacity(), capacity);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::command::COPY_BUFFER_ALIGNMENT;
+use crate::resource::TextureFormat;
+use smol_str::SmolStr;
+use staccato_core::rect::{Rect, Size};
+use thiserror::Error;
+
+/// Misuse of a resource or the command stream, rejected by every `RenderBackend`.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum RenderError {
+    #[error("{handle} was destroyed or never created")]
+    UseAfterDestroy { handle: SmolStr },
+    #[error("{handle} lacks the {usage} usage")]
+    MissingUsage {
+        handle: SmolStr,
+        usage: &'static str,
+    },
+    #[error("{handle} expects {expected:?} but got {found:?}")]
+    FormatMismatch {
+        handle: SmolStr,
+        expected: Option<TextureFormat>,
+        found: Option<TextureFormat>,
+    },
+    #[error("{handle} does not fit the bound pipeline: {message}")]
+    BindGroupMismatch {
+        handle: SmolStr,
+        message: &'static str,
+    },
+    #[error("scissor {rect:?} is outside the {target:?} target")]
+    ScissorOutOfTarget { rect: Rect, target: Size },
+    #[error("{handle}: {message}")]
+    OutOfBounds {
+        handle: SmolStr,
+        message: &'static str,
+    },
+    #[error("{handle}: {message}, must be a multiple of {COPY_BUFFER_ALIGNMENT}")]
+    Misaligned {
+        handle: SmolStr,
+        message: &'static str,
+    },
+    #[error("{handle}: {message}")]
+    Unsupported {
+        handle: SmolStr,
+        message: &'static str,
+    },
+    #[error("invalid command order: {0}")]
+    InvalidOrder(&'static str),
+    #[error("the backend has no surface")]
+    NoSurface,
+}
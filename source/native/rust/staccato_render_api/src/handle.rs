@@ -0,0 +1,69 @@
+use std::fmt::{Display, Formatter};
+
+/// Common interface of the handle types, for backends that store resources in slots.
+pub trait ResourceHandle: Copy + Eq + Display {
+    fn from_parts(index: u32, generation: u32) -> Self;
+    fn index(self) -> u32;
+    fn generation(self) -> u32;
+}
+
+macro_rules! handle {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {$(
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name {
+            index: u32,
+            generation: u32,
+        }
+
+        impl $name {
+            /// For backends: `generation` tells apart handles that reuse a destroyed slot.
+            pub fn new(index: u32, generation: u32) -> Self {
+                Self { index, generation }
+            }
+
+            pub fn index(self) -> u32 {
+                self.index
+            }
+
+            pub fn generation(self) -> u32 {
+                self.generation
+            }
+        }
+
+        impl ResourceHandle for $name {
+            fn from_parts(index: u32, generation: u32) -> Self {
+                Self::new(index, generation)
+            }
+
+            fn index(self) -> u32 {
+                self.index
+            }
+
+            fn generation(self) -> u32 {
+                self.generation
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                write!(f, concat!(stringify!($name), "({}v{})"), self.index, self.generation)
+            }
+        }
+    )*};
+}
+
+handle!(
+    /// A texture created by `RenderBackend::create_texture`.
+    TextureHandle,
+    /// A buffer created by `RenderBackend::create_buffer`.
+    BufferHandle,
+    /// A sampler created by `RenderBackend::create_sampler`.
+    SamplerHandle,
+    /// A shader module created by `RenderBackend::create_shader`.
+    ShaderHandle,
+    /// A render pipeline created by `RenderBackend::create_pipeline`.
+    PipelineHandle,
+    /// A bind group created by `RenderBackend::create_bind_group`.
+    BindGroupHandle,
+);
@@ -0,0 +1,73 @@
+use crate::error::RenderError;
+use crate::handle::ResourceHandle;
+use smol_str::ToSmolStr;
+use std::marker::PhantomData;
+
+/// Resources of one kind for a `RenderBackend`.
+///
+/// Handles of destroyed resources stay invalid when their slot is reused.
+#[derive(Debug)]
+pub struct ResourcePool<H, T> {
+    /// generation and resource of every slot
+    slots: Vec<(u32, Option<T>)>,
+    free: Vec<u32>,
+    handle: PhantomData<H>,
+}
+
+impl<H: ResourceHandle, T> Default for ResourcePool<H, T> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            handle: PhantomData,
+        }
+    }
+}
+
+impl<H: ResourceHandle, T> ResourcePool<H, T> {
+    pub fn insert(&mut self, value: T) -> H {
+        if let Some(index) = self.free.pop()
+            && let Some(slot) = self.slots.get_mut(index as usize)
+        {
+            slot.1 = Some(value);
+            return H::from_parts(index, slot.0);
+        }
+
+        self.slots.push((0, Some(value)));
+        H::from_parts(self.slots.len() as u32 - 1, 0)
+    }
+
+    pub fn get(&self, handle: H) -> Result<&T, RenderError> {
+        self.slots
+            .get(handle.index() as usize)
+            .filter(|(generation, _)| *generation == handle.generation())
+            .and_then(|(_, value)| value.as_ref())
+            .ok_or_else(|| RenderError::UseAfterDestroy {
+                handle: handle.to_smolstr(),
+            })
+    }
+
+    pub fn remove(&mut self, handle: H) -> Result<T, RenderError> {
+        self.get(handle)?;
+        let value = self
+            .slots
+            .get_mut(handle.index() as usize)
+            .and_then(|slot| {
+                slot.0 = slot.0.wrapping_add(1);
+                slot.1.take()
+            })
+            .ok_or_else(|| RenderError::UseAfterDestroy {
+                handle: handle.to_smolstr(),
+            })?;
+        self.free.push(handle.index());
+        Ok(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
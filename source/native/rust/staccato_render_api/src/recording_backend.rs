@@ -0,0 +1,961 @@
+use crate::backend::RenderBackend;
+use crate::command::{COPY_BUFFER_ALIGNMENT, CommandBuffer, LoadOp, RenderCommand, RenderTarget};
+use crate::handle::{
+    BindGroupHandle, BufferHandle, PipelineHandle, SamplerHandle, ShaderHandle, TextureHandle,
+};
+use crate::pool::ResourcePool;
+use crate::resource::{
+    BindGroupDescriptor, BindResource, BufferDescriptor, BufferUsage, PipelineDescriptor,
+    SamplerDescriptor, ShaderDescriptor, TextureDescriptor, TextureFormat, TextureUsage,
+};
+use smol_str::ToSmolStr;
+use staccato_core::fallible::Fallible;
+use staccato_core::rect::{Point, Size};
+use std::fmt::Write;
+
+pub use crate::error::RenderError;
+
+/// State of the pass being recorded.
+#[derive(Debug, Clone, Copy)]
+struct Pass {
+    size: Size,
+    format: TextureFormat,
+    depth_format: Option<TextureFormat>,
+    pipeline: Option<PipelineHandle>,
+    index_buffer: bool,
+}
+
+fn fits(offset: u64, len: u64, size: u64) -> bool {
+    offset.checked_add(len).is_some_and(|end| end <= size)
+}
+
+fn aligned(values: &[u64]) -> bool {
+    values
+        .iter()
+        .all(|value| value % COPY_BUFFER_ALIGNMENT == 0)
+}
+
+fn rect_fits(origin: Point, size: Size, target: Size) -> bool {
+    origin.x >= 0
+        && origin.y >= 0
+        && size.width >= 0
+        && size.height >= 0
+        && origin.x.saturating_add(size.width) <= target.width
+        && origin.y.saturating_add(size.height) <= target.height
+}
+
+/// A `RenderBackend` without a GPU, for tests.
+///
+/// It validates resource lifetimes and the command stream like a strict GPU backend would,
+/// and writes every call to a human-readable trace that tests can compare:
+///
+/// ```text
+/// frame 0
+///   write_buffer BufferHandle(0v0) +0 64 bytes
+///   begin_pass surface clear(0, 0, 0, 1)
+///   set_pipeline PipelineHandle(0v0)
+///   draw vertices 0..6 instances 0..1
+///   end_pass
+/// ```
+#[derive(Debug, Default)]
+pub struct RecordingBackend {
+    textures: ResourcePool<TextureHandle, TextureDescriptor>,
+    buffers: ResourcePool<BufferHandle, BufferDescriptor>,
+    samplers: ResourcePool<SamplerHandle, SamplerDescriptor>,
+    shaders: ResourcePool<ShaderHandle, ShaderDescriptor>,
+    pipelines: ResourcePool<PipelineHandle, PipelineDescriptor>,
+    bind_groups: ResourcePool<BindGroupHandle, BindGroupDescriptor>,
+    surface: Option<(Size, TextureFormat)>,
+    trace: String,
+    frame_count: u64,
+}
+
+impl RecordingBackend {
+    /// A backend without a surface, render into textures only.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_surface(size: Size, format: TextureFormat) -> Self {
+        Self {
+            surface: Some((size, format)),
+            ..Default::default()
+        }
+    }
+
+    /// Like a window resize.
+    pub fn set_surface_size(&mut self, size: Size) {
+        if let Some(surface) = &mut self.surface {
+            surface.0 = size;
+        }
+    }
+
+    pub fn trace(&self) -> &str {
+        &self.trace
+    }
+
+    /// Return the trace and start a new one.
+    pub fn take_trace(&mut self) -> String {
+        std::mem::take(&mut self.trace)
+    }
+
+    /// Number of submitted frames.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Number of resources not destroyed yet, to find leaks.
+    pub fn live_resources(&self) -> usize {
+        self.textures.len()
+            + self.buffers.len()
+            + self.samplers.len()
+            + self.shaders.len()
+            + self.pipelines.len()
+            + self.bind_groups.len()
+    }
+
+    pub fn texture(&self, texture: TextureHandle) -> Result<&TextureDescriptor, RenderError> {
+        self.textures.get(texture)
+    }
+
+    pub fn buffer(&self, buffer: BufferHandle) -> Result<&BufferDescriptor, RenderError> {
+        self.buffers.get(buffer)
+    }
+
+    fn line(&mut self, indent: bool, args: std::fmt::Arguments<'_>) {
+        if indent {
+            self.trace.push_str("  ");
+        }
+        // writing to a `String` can not fail
+        let _ = self.trace.write_fmt(args);
+        self.trace.push('\n');
+    }
+
+    fn texture_with(
+        &self,
+        texture: TextureHandle,
+        usage: TextureUsage,
+        name: &'static str,
+    ) -> Result<&TextureDescriptor, RenderError> {
+        let descriptor = self.textures.get(texture)?;
+        if !descriptor.usage.contains(usage) {
+            return Err(RenderError::MissingUsage {
+                handle: texture.to_smolstr(),
+                usage: name,
+            });
+        }
+        Ok(descriptor)
+    }
+
+    fn buffer_with(
+        &self,
+        buffer: BufferHandle,
+        usage: BufferUsage,
+        name: &'static str,
+    ) -> Result<&BufferDescriptor, RenderError> {
+        let descriptor = self.buffers.get(buffer)?;
+        if !descriptor.usage.contains(usage) {
+            return Err(RenderError::MissingUsage {
+                handle: buffer.to_smolstr(),
+                usage: name,
+            });
+        }
+        Ok(descriptor)
+    }
+
+    fn check_bind_group(&self, descriptor: &BindGroupDescriptor) -> Result<(), RenderError> {
+        self.pipelines.get(descriptor.pipeline)?;
+        for entry in &descriptor.entries {
+            match entry.resource {
+                BindResource::Buffer { buffer, .. } => {
+                    self.buffers.get(buffer)?;
+                }
+                BindResource::Texture(texture) => {
+                    self.texture_with(texture, TextureUsage::SAMPLED, "SAMPLED")?;
+                }
+                BindResource::Sampler(sampler) => {
+                    self.samplers.get(sampler)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn pass_target(&self, target: RenderTarget) -> Result<(Size, TextureFormat), RenderError> {
+        match target {
+            RenderTarget::Surface => self.surface.ok_or(RenderError::NoSurface),
+            RenderTarget::Texture(texture) => {
+                let descriptor =
+                    self.texture_with(texture, TextureUsage::RENDER_TARGET, "RENDER_TARGET")?;
+                Ok((descriptor.size, descriptor.format))
+            }
+        }
+    }
+
+    fn execute(
+        &mut self,
+        commands: &CommandBuffer,
+        command: &RenderCommand,
+        pass: &mut Option<Pass>,
+    ) -> Result<(), RenderError> {
+        let in_pass = |name: &'static str| pass.ok_or(RenderError::InvalidOrder(name));
+
+        match *command {
+            RenderCommand::BeginPass {
+                target,
+                load,
+                depth,
+            } => {
+                if pass.is_some() {
+                    return Err(RenderError::InvalidOrder("begin_pass inside a pass"));
+                }
+                let (size, format) = self.pass_target(target)?;
+
+                let depth_format = match depth {
+                    Some((texture, _)) => {
+                        let descriptor = self.texture_with(
+                            texture,
+                            TextureUsage::RENDER_TARGET,
+                            "RENDER_TARGET",
+                        )?;
+                        if !descriptor.format.is_depth() {
+                            return Err(RenderError::FormatMismatch {
+                                handle: texture.to_smolstr(),
+                                expected: None,
+                                found: Some(descriptor.format),
+                            });
+                        }
+                        if descriptor.size != size {
+                            return Err(RenderError::OutOfBounds {
+                                handle: texture.to_smolstr(),
+                                message: "depth attachment size differs from the target",
+                            });
+                        }
+                        Some(descriptor.format)
+                    }
+                    None => None,
+                };
+
+                *pass = Some(Pass {
+                    size,
+                    format,
+                    depth_format,
+                    pipeline: None,
+                    index_buffer: false,
+                });
+
+                let target = match target {
+                    RenderTarget::Surface => "surface".to_smolstr(),
+                    RenderTarget::Texture(texture) => texture.to_smolstr(),
+                };
+                let load = match load {
+                    LoadOp::Load => "load".to_smolstr(),
+                    LoadOp::Clear(color) => {
+                        format!("clear({}, {}, {}, {})", color.r, color.g, color.b, color.a).into()
+                    }
+                };
+                match depth {
+                    Some((texture, Some(clear))) => self.line(
+                        true,
+                        format_args!("begin_pass {target} {load} depth {texture} clear({clear})"),
+                    ),
+                    Some((texture, None)) => self.line(
+                        true,
+                        format_args!("begin_pass {target} {load} depth {texture} load"),
+                    ),
+                    None => self.line(true, format_args!("begin_pass {target} {load}")),
+                }
+            }
+            RenderCommand::EndPass => {
+                in_pass("end_pass outside a pass")?;
+                *pass = None;
+                self.line(true, format_args!("end_pass"));
+            }
+            RenderCommand::SetViewport {
+                rect,
+                min_depth,
+                max_depth,
+            } => {
+                in_pass("set_viewport outside a pass")?;
+                self.line(
+                    true,
+                    format_args!(
+                        "set_viewport {},{} {}x{} depth {min_depth}..{max_depth}",
+                        rect.position.x, rect.position.y, rect.size.width, rect.size.height
+                    ),
+                );
+            }
+            RenderCommand::SetScissor { rect } => {
+                let current = in_pass("set_scissor outside a pass")?;
+                if !rect_fits(rect.position, rect.size, current.size) {
+                    return Err(RenderError::ScissorOutOfTarget {
+                        rect,
+                        target: current.size,
+                    });
+                }
+                self.line(
+                    true,
+                    format_args!(
+                        "set_scissor {},{} {}x{}",
+                        rect.position.x, rect.position.y, rect.size.width, rect.size.height
+                    ),
+                );
+            }
+            RenderCommand::SetPipeline { pipeline } => {
+                let current = in_pass("set_pipeline outside a pass")?;
+                let descriptor = self.pipelines.get(pipeline)?;
+                if descriptor.color_format != current.format {
+                    return Err(RenderError::FormatMismatch {
+                        handle: pipeline.to_smolstr(),
+                        expected: Some(current.format),
+                        found: Some(descriptor.color_format),
+                    });
+                }
+                if descriptor.depth_format != current.depth_format {
+                    return Err(RenderError::FormatMismatch {
+                        handle: pipeline.to_smolstr(),
+                        expected: current.depth_format,
+                        found: descriptor.depth_format,
+                    });
+                }
+                self.shaders.get(descriptor.shader)?;
+
+                let label = descriptor.label.clone();
+                if let Some(pass) = pass {
+                    pass.pipeline = Some(pipeline);
+                }
+                self.line(true, format_args!("set_pipeline {pipeline} '{label}'"));
+            }
+            RenderCommand::SetBindGroup { slot, bind_group } => {
+                let current = in_pass("set_bind_group outside a pass")?;
+                let Some(pipeline) = current.pipeline else {
+                    return Err(RenderError::InvalidOrder(
+                        "set_bind_group before set_pipeline",
+                    ));
+                };
+                let descriptor = self.bind_groups.get(bind_group)?;
+                if descriptor.pipeline != pipeline {
+                    return Err(RenderError::BindGroupMismatch {
+                        handle: bind_group.to_smolstr(),
+                        message: "created for another pipeline",
+                    });
+                }
+                if descriptor.group != slot {
+                    return Err(RenderError::BindGroupMismatch {
+                        handle: bind_group.to_smolstr(),
+                        message: "created for another slot",
+                    });
+                }
+                self.check_bind_group(descriptor)?;
+                self.line(true, format_args!("set_bind_group {slot} {bind_group}"));
+            }
+            RenderCommand::SetVertexBuffer {
+                slot,
+                buffer,
+                offset,
+            } => {
+                in_pass("set_vertex_buffer outside a pass")?;
+                self.buffer_with(buffer, BufferUsage::VERTEX, "VERTEX")?;
+                self.line(
+                    true,
+                    format_args!("set_vertex_buffer {slot} {buffer} +{offset}"),
+                );
+            }
+            RenderCommand::SetIndexBuffer {
+                buffer,
+                offset,
+                format,
+            } => {
+                in_pass("set_index_buffer outside a pass")?;
+                self.buffer_with(buffer, BufferUsage::INDEX, "INDEX")?;
+                if let Some(pass) = pass {
+                    pass.index_buffer = true;
+                }
+                self.line(
+                    true,
+                    format_args!("set_index_buffer {buffer} +{offset} {format:?}"),
+                );
+            }
+            RenderCommand::Draw {
+                first_vertex,
+                vertex_count,
+                first_instance,
+                instance_count,
+            } => {
+                if in_pass("draw outside a pass")?.pipeline.is_none() {
+                    return Err(RenderError::InvalidOrder("draw without a pipeline"));
+                }
+                self.line(
+                    true,
+                    format_args!(
+                        "draw vertices {first_vertex}..{} instances {first_instance}..{}",
+                        u64::from(first_vertex) + u64::from(vertex_count),
+                        u64::from(first_instance) + u64::from(instance_count)
+                    ),
+                );
+            }
+            RenderCommand::DrawIndexed {
+                first_index,
+                index_count,
+                base_vertex,
+                first_instance,
+                instance_count,
+            } => {
+                let current = in_pass("draw_indexed outside a pass")?;
+                if current.pipeline.is_none() {
+                    return Err(RenderError::InvalidOrder("draw_indexed without a pipeline"));
+                }
+                if !current.index_buffer {
+                    return Err(RenderError::InvalidOrder(
+                        "draw_indexed without an index buffer",
+                    ));
+                }
+                self.line(
+                    true,
+                    format_args!(
+                        "draw_indexed indices {first_index}..{} base {base_vertex} instances {first_instance}..{}",
+                        u64::from(first_index) + u64::from(index_count),
+                        u64::from(first_instance) + u64::from(instance_count)
+                    ),
+                );
+            }
+            RenderCommand::WriteBuffer {
+                buffer,
+                offset,
+                data,
+            } => {
+                if pass.is_some() {
+                    return Err(RenderError::InvalidOrder("write_buffer inside a pass"));
+                }
+                let descriptor = self.buffer_with(buffer, BufferUsage::COPY_DST, "COPY_DST")?;
+                let len = commands.data(data).len() as u64;
+                if !aligned(&[offset, len]) {
+                    return Err(RenderError::Misaligned {
+                        handle: buffer.to_smolstr(),
+                        message: "write offset or size",
+                    });
+                }
+                if !fits(offset, len, descriptor.size) {
+                    return Err(RenderError::OutOfBounds {
+                        handle: buffer.to_smolstr(),
+                        message: "write past the end of the buffer",
+                    });
+                }
+                self.line(
+                    true,
+                    format_args!("write_buffer {buffer} +{offset} {len} bytes"),
+                );
+            }
+            RenderCommand::WriteTexture {
+                texture,
+                origin,
+                size,
+                data,
+            } => {
+                if pass.is_some() {
+                    return Err(RenderError::InvalidOrder("write_texture inside a pass"));
+                }
+                let descriptor = self.texture_with(texture, TextureUsage::COPY_DST, "COPY_DST")?;
+                if !rect_fits(origin, size, descriptor.size) {
+                    return Err(RenderError::OutOfBounds {
+                        handle: texture.to_smolstr(),
+                        message: "write outside the texture",
+                    });
+                }
+                let Some(bytes) = descriptor.format.bytes_per_pixel() else {
+                    return Err(RenderError::Unsupported {
+                        handle: texture.to_smolstr(),
+                        message: "depth textures can not be written",
+                    });
+                };
+                let expected =
+                    u64::from(bytes) * size.width.max(0) as u64 * size.height.max(0) as u64;
+                let len = commands.data(data).len() as u64;
+                if expected != len {
+                    return Err(RenderError::OutOfBounds {
+                        handle: texture.to_smolstr(),
+                        message: "data size does not match the written area",
+                    });
+                }
+                self.line(
+                    true,
+                    format_args!(
+                        "write_texture {texture} {},{} {}x{} {len} bytes",
+                        origin.x, origin.y, size.width, size.height
+                    ),
+                );
+            }
+            RenderCommand::CopyBufferToBuffer {
+                source,
+                source_offset,
+                destination,
+                destination_offset,
+                size,
+            } => {
+                if pass.is_some() {
+                    return Err(RenderError::InvalidOrder("copy inside a pass"));
+                }
+                let source_size = self
+                    .buffer_with(source, BufferUsage::COPY_SRC, "COPY_SRC")?
+                    .size;
+                let destination_size = self
+                    .buffer_with(destination, BufferUsage::COPY_DST, "COPY_DST")?
+                    .size;
+                if !aligned(&[source_offset, size]) {
+                    return Err(RenderError::Misaligned {
+                        handle: source.to_smolstr(),
+                        message: "copy offset or size",
+                    });
+                }
+                if !aligned(&[destination_offset]) {
+                    return Err(RenderError::Misaligned {
+                        handle: destination.to_smolstr(),
+                        message: "copy offset",
+                    });
+                }
+                if !fits(source_offset, size, source_size) {
+                    return Err(RenderError::OutOfBounds {
+                        handle: source.to_smolstr(),
+                        message: "copy past the end of the buffer",
+                    });
+                }
+                if !fits(destination_offset, size, destination_size) {
+                    return Err(RenderError::OutOfBounds {
+                        handle: destination.to_smolstr(),
+                        message: "copy past the end of the buffer",
+                    });
+                }
+                self.line(
+                    true,
+                    format_args!(
+                        "copy_buffer {source} +{source_offset} -> {destination} +{destination_offset} {size} bytes"
+                    ),
+                );
+            }
+            RenderCommand::CopyTextureToTexture {
+                source,
+                source_origin,
+                destination,
+                destination_origin,
+                size,
+            } => {
+                if pass.is_some() {
+                    return Err(RenderError::InvalidOrder("copy inside a pass"));
+                }
+                let source_descriptor =
+                    self.texture_with(source, TextureUsage::COPY_SRC, "COPY_SRC")?;
+                let destination_descriptor =
+                    self.texture_with(destination, TextureUsage::COPY_DST, "COPY_DST")?;
+                if source_descriptor.format != destination_descriptor.format {
+                    return Err(RenderError::FormatMismatch {
+                        handle: destination.to_smolstr(),
+                        expected: Some(source_descriptor.format),
+                        found: Some(destination_descriptor.format),
+                    });
+                }
+                if !rect_fits(source_origin, size, source_descriptor.size) {
+                    return Err(RenderError::OutOfBounds {
+                        handle: source.to_smolstr(),
+                        message: "copy outside the texture",
+                    });
+                }
+                if !rect_fits(destination_origin, size, destination_descriptor.size) {
+                    return Err(RenderError::OutOfBounds {
+                        handle: destination.to_smolstr(),
+                        message: "copy outside the texture",
+                    });
+                }
+                self.line(
+                    true,
+                    format_args!(
+                        "copy_texture {source} {},{} -> {destination} {},{} {}x{}",
+                        source_origin.x,
+                        source_origin.y,
+                        destination_origin.x,
+                        destination_origin.y,
+                        size.width,
+                        size.height
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Fallible for RecordingBackend {
+    type Error = RenderError;
+}
+
+impl RenderBackend for RecordingBackend {
+    fn create_texture(
+        &mut self,
+        descriptor: &TextureDescriptor,
+    ) -> Result<TextureHandle, Self::Error> {
+        let texture = self.textures.insert(descriptor.clone());
+        self.line(
+            false,
+            format_args!(
+                "create {texture} '{}' {}x{} {:?}",
+                descriptor.label, descriptor.size.width, descriptor.size.height, descriptor.format
+            ),
+        );
+        Ok(texture)
+    }
+
+    fn destroy_texture(&mut self, texture: TextureHandle) -> Result<(), Self::Error> {
+        self.textures.remove(texture)?;
+        self.line(false, format_args!("destroy {texture}"));
+        Ok(())
+    }
+
+    fn create_buffer(
+        &mut self,
+        descriptor: &BufferDescriptor,
+    ) -> Result<BufferHandle, Self::Error> {
+        let buffer = self.buffers.insert(descriptor.clone());
+        self.line(
+            false,
+            format_args!(
+                "create {buffer} '{}' {} bytes",
+                descriptor.label, descriptor.size
+            ),
+        );
+        Ok(buffer)
+    }
+
+    fn destroy_buffer(&mut self, buffer: BufferHandle) -> Result<(), Self::Error> {
+        self.buffers.remove(buffer)?;
+        self.line(false, format_args!("destroy {buffer}"));
+        Ok(())
+    }
+
+    fn create_sampler(
+        &mut self,
+        descriptor: &SamplerDescriptor,
+    ) -> Result<SamplerHandle, Self::Error> {
+        let sampler = self.samplers.insert(*descriptor);
+        self.line(
+            false,
+            format_args!(
+                "create {sampler} {:?} {:?}",
+                descriptor.filter, descriptor.address
+            ),
+        );
+        Ok(sampler)
+    }
+
+    fn destroy_sampler(&mut self, sampler: SamplerHandle) -> Result<(), Self::Error> {
+        self.samplers.remove(sampler)?;
+        self.line(false, format_args!("destroy {sampler}"));
+        Ok(())
+    }
+
+    fn create_shader(
+        &mut self,
+        descriptor: &ShaderDescriptor,
+    ) -> Result<ShaderHandle, Self::Error> {
+        let shader = self.shaders.insert(descriptor.clone());
+        self.line(
+            false,
+            format_args!("create {shader} '{}'", descriptor.label),
+        );
+        Ok(shader)
+    }
+
+    fn destroy_shader(&mut self, shader: ShaderHandle) -> Result<(), Self::Error> {
+        self.shaders.remove(shader)?;
+        self.line(false, format_args!("destroy {shader}"));
+        Ok(())
+    }
+
+    fn create_pipeline(
+        &mut self,
+        descriptor: &PipelineDescriptor,
+    ) -> Result<PipelineHandle, Self::Error> {
+        self.shaders.get(descriptor.shader)?;
+        if descriptor.color_format.is_depth() {
+            return Err(RenderError::FormatMismatch {
+                handle: descriptor.shader.to_smolstr(),
+                expected: None,
+                found: Some(descriptor.color_format),
+            });
+        }
+
+        let pipeline = self.pipelines.insert(descriptor.clone());
+        self.line(
+            false,
+            format_args!(
+                "create {pipeline} '{}' {:?} {:?}",
+                descriptor.label, descriptor.color_format, descriptor.blend
+            ),
+        );
+        Ok(pipeline)
+    }
+
+    fn destroy_pipeline(&mut self, pipeline: PipelineHandle) -> Result<(), Self::Error> {
+        self.pipelines.remove(pipeline)?;
+        self.line(false, format_args!("destroy {pipeline}"));
+        Ok(())
+    }
+
+    fn create_bind_group(
+        &mut self,
+        descriptor: &BindGroupDescriptor,
+    ) -> Result<BindGroupHandle, Self::Error> {
+        self.check_bind_group(descriptor)?;
+        let bind_group = self.bind_groups.insert(descriptor.clone());
+        self.line(
+            false,
+            format_args!(
+                "create {bind_group} '{}' group {} of {}",
+                descriptor.label, descriptor.group, descriptor.pipeline
+            ),
+        );
+        Ok(bind_group)
+    }
+
+    fn destroy_bind_group(&mut self, bind_group: BindGroupHandle) -> Result<(), Self::Error> {
+        self.bind_groups.remove(bind_group)?;
+        self.line(false, format_args!("destroy {bind_group}"));
+        Ok(())
+    }
+
+    fn surface_format(&self) -> Option<TextureFormat> {
+        self.surface.map(|(_, format)| format)
+    }
+
+    fn surface_size(&self) -> Option<Size> {
+        self.surface.map(|(size, _)| size)
+    }
+
+    /// Validate and trace `commands`, the trace ends with the failing command on error.
+    fn submit(&mut self, commands: &CommandBuffer) -> Result<(), Self::Error> {
+        let frame = self.frame_count;
+        self.frame_count += 1;
+        self.line(false, format_args!("frame {frame}"));
+
+        let mut pass = None;
+        for command in commands.commands() {
+            if let Err(err) = self.execute(commands, command, &mut pass) {
+                self.line(true, format_args!("error: {err}"));
+                return Err(err);
+            }
+        }
+
+        if pass.is_some() {
+            let err = RenderError::InvalidOrder("pass not ended");
+            self.line(true, format_args!("error: {err}"));
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resource::{BlendMode, PrimitiveTopology};
+    use staccato_core::color::Color;
+    use staccato_core::rect::Rect;
+
+    fn pipeline(
+        backend: &mut RecordingBackend,
+        format: TextureFormat,
+    ) -> Result<PipelineHandle, RenderError> {
+        let shader = backend.create_shader(&ShaderDescriptor {
+            label: "shapes".into(),
+            wgsl: String::new(),
+        })?;
+        backend.create_pipeline(&PipelineDescriptor {
+            label: "shapes".into(),
+            shader,
+            vertex_entry: "vs_main".into(),
+            fragment_entry: "fs_main".into(),
+            vertex_layouts: Vec::new(),
+            color_format: format,
+            depth_format: None,
+            blend: BlendMode::Alpha,
+            topology: PrimitiveTopology::TriangleList,
+        })
+    }
+
+    #[test]
+    fn traces_a_frame() -> Result<(), RenderError> {
+        let mut backend =
+            RecordingBackend::with_surface(Size::new(64, 32), TextureFormat::Bgra8UnormSrgb);
+        let pipeline = pipeline(&mut backend, TextureFormat::Bgra8UnormSrgb)?;
+        let vertices = backend.create_buffer(&BufferDescriptor {
+            label: "vertices".into(),
+            size: 64,
+            usage: BufferUsage::VERTEX | BufferUsage::COPY_DST,
+        })?;
+        backend.take_trace();
+
+        let mut commands = CommandBuffer::new();
+        commands
+            .write_buffer(vertices, 0, &[0; 48])
+            .begin_pass(RenderTarget::Surface, LoadOp::Clear(Color::BLACK))
+            .set_scissor(Rect::new(0, 0, 64, 32))
+            .set_pipeline(pipeline)
+            .set_vertex_buffer(0, vertices, 0)
+            .draw(0..6, 0..1)
+            .end_pass();
+        backend.submit(&commands)?;
+
+        assert_eq!(
+            backend.trace(),
+            "frame 0\n\
+             \x20 write_buffer BufferHandle(0v0) +0 48 bytes\n\
+             \x20 begin_pass surface clear(0, 0, 0, 1)\n\
+             \x20 set_scissor 0,0 64x32\n\
+             \x20 set_pipeline PipelineHandle(0v0) 'shapes'\n\
+             \x20 set_vertex_buffer 0 BufferHandle(0v0) +0\n\
+             \x20 draw vertices 0..6 instances 0..1\n\
+             \x20 end_pass\n"
+        );
+
+        // raw commands may describe ranges past u32::MAX
+        backend.take_trace();
+        let mut commands = CommandBuffer::new();
+        commands
+            .begin_pass(RenderTarget::Surface, LoadOp::Load)
+            .set_pipeline(pipeline)
+            .push(RenderCommand::Draw {
+                first_vertex: u32::MAX,
+                vertex_count: u32::MAX,
+                first_instance: u32::MAX,
+                instance_count: 1,
+            })
+            .end_pass();
+        backend.submit(&commands)?;
+        assert!(
+            backend.trace().contains(
+                "draw vertices 4294967295..8589934590 instances 4294967295..4294967296\n"
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_misuse() -> Result<(), RenderError> {
+        let mut backend =
+            RecordingBackend::with_surface(Size::new(64, 32), TextureFormat::Bgra8UnormSrgb);
+        let mut commands = CommandBuffer::new();
+
+        let texture = backend.create_texture(&TextureDescriptor {
+            label: "target".into(),
+            size: Size::new(16, 16),
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsage::RENDER_TARGET,
+        })?;
+        backend.destroy_texture(texture)?;
+        // the slot is reused, the old handle stays invalid
+        let reused = backend.create_texture(&TextureDescriptor {
+            label: "reused".into(),
+            size: Size::new(16, 16),
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsage::RENDER_TARGET,
+        })?;
+        assert_eq!(reused.index(), texture.index());
+
+        commands
+            .begin_pass(RenderTarget::Texture(texture), LoadOp::Load)
+            .end_pass();
+        assert!(matches!(
+            backend.submit(&commands),
+            Err(RenderError::UseAfterDestroy { .. })
+        ));
+        assert!(
+            backend
+                .trace()
+                .ends_with("  error: TextureHandle(0v0) was destroyed or never created\n")
+        );
+
+        commands.clear();
+        commands
+            .begin_pass(RenderTarget::Surface, LoadOp::Load)
+            .set_scissor(Rect::new(60, 0, 8, 8))
+            .end_pass();
+        assert!(matches!(
+            backend.submit(&commands),
+            Err(RenderError::ScissorOutOfTarget { .. })
+        ));
+
+        let pipeline = pipeline(&mut backend, TextureFormat::Rgba8Unorm)?;
+        commands.clear();
+        commands
+            .begin_pass(RenderTarget::Surface, LoadOp::Load)
+            .set_pipeline(pipeline)
+            .end_pass();
+        assert!(matches!(
+            backend.submit(&commands),
+            Err(RenderError::FormatMismatch { .. })
+        ));
+
+        let surface_pipeline = self::pipeline(&mut backend, TextureFormat::Bgra8UnormSrgb)?;
+        let other_pipeline = self::pipeline(&mut backend, TextureFormat::Bgra8UnormSrgb)?;
+        let bind_group = backend.create_bind_group(&BindGroupDescriptor {
+            label: "globals".into(),
+            pipeline: surface_pipeline,
+            group: 0,
+            entries: Vec::new(),
+        })?;
+        for (pipeline, slot) in [(other_pipeline, 0), (surface_pipeline, 1)] {
+            commands.clear();
+            commands
+                .begin_pass(RenderTarget::Surface, LoadOp::Load)
+                .set_pipeline(pipeline)
+                .set_bind_group(slot, bind_group)
+                .end_pass();
+            assert!(matches!(
+                backend.submit(&commands),
+                Err(RenderError::BindGroupMismatch { .. })
+            ));
+        }
+
+        let staging = backend.create_buffer(&BufferDescriptor {
+            label: "staging".into(),
+            size: 16,
+            usage: BufferUsage::COPY_SRC | BufferUsage::COPY_DST,
+        })?;
+        let uniform = backend.create_buffer(&BufferDescriptor {
+            label: "uniform".into(),
+            size: 16,
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+        })?;
+        commands.clear();
+        commands.write_buffer(staging, 2, &[1, 2, 3]);
+        assert!(matches!(
+            backend.submit(&commands),
+            Err(RenderError::Misaligned { .. })
+        ));
+        commands.clear();
+        commands.copy_buffer_to_buffer(staging, 0, uniform, 8, 6);
+        assert!(matches!(
+            backend.submit(&commands),
+            Err(RenderError::Misaligned { .. })
+        ));
+
+        let depth = backend.create_texture(&TextureDescriptor {
+            label: "depth".into(),
+            size: Size::new(1, 1),
+            format: TextureFormat::Depth32Float,
+            usage: TextureUsage::RENDER_TARGET | TextureUsage::COPY_DST,
+        })?;
+        commands.clear();
+        commands.write_texture(depth, Point::new(0, 0), Size::new(1, 1), &[0; 4]);
+        assert!(matches!(
+            backend.submit(&commands),
+            Err(RenderError::Unsupported { .. })
+        ));
+
+        commands.clear();
+        commands.begin_pass(RenderTarget::Texture(reused), LoadOp::Load);
+        assert_eq!(
+            backend.submit(&commands),
+            Err(RenderError::InvalidOrder("pass not ended"))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,220 @@
+use crate::handle::{BufferHandle, PipelineHandle, SamplerHandle, ShaderHandle, TextureHandle};
+use bitflags::bitflags;
+use smol_str::SmolStr;
+use staccato_core::rect::Size;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextureFormat {
+    R8Unorm,
+    Rg8Unorm,
+    Rgba8Unorm,
+    Rgba8UnormSrgb,
+    Bgra8Unorm,
+    Bgra8UnormSrgb,
+    Rgb10a2Unorm,
+    Rgba16Float,
+    Depth32Float,
+    Depth24PlusStencil8,
+}
+
+impl TextureFormat {
+    /// Bytes of one pixel in a texture write, `None` for depth formats, which can not be written.
+    pub fn bytes_per_pixel(self) -> Option<u32> {
+        match self {
+            TextureFormat::R8Unorm => Some(1),
+            TextureFormat::Rg8Unorm => Some(2),
+            TextureFormat::Rgba8Unorm
+            | TextureFormat::Rgba8UnormSrgb
+            | TextureFormat::Bgra8Unorm
+            | TextureFormat::Bgra8UnormSrgb
+            | TextureFormat::Rgb10a2Unorm => Some(4),
+            TextureFormat::Rgba16Float => Some(8),
+            TextureFormat::Depth32Float | TextureFormat::Depth24PlusStencil8 => None,
+        }
+    }
+
+    pub fn is_depth(self) -> bool {
+        matches!(
+            self,
+            TextureFormat::Depth32Float | TextureFormat::Depth24PlusStencil8
+        )
+    }
+
+    pub fn is_srgb(self) -> bool {
+        matches!(
+            self,
+            TextureFormat::Rgba8UnormSrgb | TextureFormat::Bgra8UnormSrgb
+        )
+    }
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct TextureUsage: u32 {
+        /// bound to a shader
+        const SAMPLED = 1 << 0;
+        /// drawn into by a render pass
+        const RENDER_TARGET = 1 << 1;
+        const COPY_SRC = 1 << 2;
+        const COPY_DST = 1 << 3;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextureDescriptor {
+    pub label: SmolStr,
+    pub size: Size,
+    pub format: TextureFormat,
+    pub usage: TextureUsage,
+}
+
+bitflags! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct BufferUsage: u32 {
+        const VERTEX = 1 << 0;
+        const INDEX = 1 << 1;
+        const UNIFORM = 1 << 2;
+        const STORAGE = 1 << 3;
+        const COPY_SRC = 1 << 4;
+        const COPY_DST = 1 << 5;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BufferDescriptor {
+    pub label: SmolStr,
+    /// in bytes
+    pub size: u64,
+    pub usage: BufferUsage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FilterMode {
+    Nearest,
+    #[default]
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AddressMode {
+    #[default]
+    ClampToEdge,
+    Repeat,
+    MirrorRepeat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SamplerDescriptor {
+    pub filter: FilterMode,
+    pub address: AddressMode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ShaderDescriptor {
+    pub label: SmolStr,
+    pub wgsl: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VertexFormat {
+    Float32,
+    Float32x2,
+    Float32x3,
+    Float32x4,
+    Uint32,
+    /// four bytes normalized to 0.0..=1.0, e.g. a packed color
+    Unorm8x4,
+}
+
+impl VertexFormat {
+    pub fn size(self) -> u32 {
+        match self {
+            VertexFormat::Float32 | VertexFormat::Uint32 | VertexFormat::Unorm8x4 => 4,
+            VertexFormat::Float32x2 => 8,
+            VertexFormat::Float32x3 => 12,
+            VertexFormat::Float32x4 => 16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VertexAttribute {
+    pub location: u32,
+    /// in bytes from the start of the vertex
+    pub offset: u32,
+    pub format: VertexFormat,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VertexLayout {
+    /// in bytes
+    pub stride: u32,
+    /// advance once per instance instead of once per vertex
+    pub per_instance: bool,
+    pub attributes: Vec<VertexAttribute>,
+}
+
+/// How drawn colors combine with the target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum BlendMode {
+    /// replace the target
+    Opaque,
+    /// straight alpha
+    #[default]
+    Alpha,
+    /// colors are already multiplied by alpha
+    Premultiplied,
+    Additive,
+    Multiply,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PrimitiveTopology {
+    #[default]
+    TriangleList,
+    TriangleStrip,
+    LineList,
+    LineStrip,
+    PointList,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PipelineDescriptor {
+    pub label: SmolStr,
+    pub shader: ShaderHandle,
+    pub vertex_entry: SmolStr,
+    pub fragment_entry: SmolStr,
+    pub vertex_layouts: Vec<VertexLayout>,
+    /// format of the target the pipeline draws into
+    pub color_format: TextureFormat,
+    pub depth_format: Option<TextureFormat>,
+    pub blend: BlendMode,
+    pub topology: PrimitiveTopology,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BindResource {
+    Buffer {
+        buffer: BufferHandle,
+        offset: u64,
+        /// `None` binds the rest of the buffer
+        size: Option<u64>,
+    },
+    Texture(TextureHandle),
+    Sampler(SamplerHandle),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BindEntry {
+    pub binding: u32,
+    pub resource: BindResource,
+}
+
+/// Resources for `@group(group)` of the shader of `pipeline`, the layout comes from the shader.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BindGroupDescriptor {
+    pub label: SmolStr,
+    pub pipeline: PipelineHandle,
+    pub group: u32,
+    pub entries: Vec<BindEntry>,
+}
@@ -0,0 +1,175 @@
+use smol_str::format_smolstr;
+use staccato_core::rect::Size;
+use staccato_render_api::backend::RenderBackend;
+use staccato_render_api::command::{CommandBuffer, IndexFormat};
+use staccato_render_api::handle::BufferHandle;
+use staccato_render_api::resource::{BufferDescriptor, BufferUsage};
+
+/// Size of the globals uniform of the 2D shaders, the target size padded to 16 bytes.
+pub const GLOBALS_SIZE: u64 = 16;
+
+/// Vertex and index buffers kept between frames, plus the globals uniform of the 2D shaders.
+///
+/// The buffers only grow, to the next power of two, when a frame needs more than they hold.
+/// Indices are `u32`.
+#[derive(Debug)]
+pub struct GeometryBuffers {
+    label: &'static str,
+    /// in bytes
+    vertex_size: u32,
+    globals: BufferHandle,
+    vertices: BufferHandle,
+    indices: BufferHandle,
+    vertex_capacity: u32,
+    index_capacity: u32,
+    /// reused for the bytes of every write
+    scratch: Vec<u8>,
+}
+
+impl GeometryBuffers {
+    /// Buffers for `vertex_capacity` vertices of `vertex_size` bytes and `index_capacity` indices.
+    pub fn new<B: RenderBackend>(
+        backend: &mut B,
+        label: &'static str,
+        vertex_size: u32,
+        vertex_capacity: u32,
+        index_capacity: u32,
+    ) -> Result<Self, B::Error> {
+        let globals = backend.create_buffer(&BufferDescriptor {
+            label: format_smolstr!("{label} globals"),
+            size: GLOBALS_SIZE,
+            usage: BufferUsage::UNIFORM | BufferUsage::COPY_DST,
+        })?;
+        let vertices = Self::create_vertex_buffer(backend, label, vertex_size, vertex_capacity)?;
+        let indices = Self::create_index_buffer(backend, label, index_capacity)?;
+
+        Ok(Self {
+            label,
+            vertex_size,
+            globals,
+            vertices,
+            indices,
+            vertex_capacity,
+            index_capacity,
+            scratch: Vec::new(),
+        })
+    }
+
+    /// The uniform to bind as `globals`.
+    pub fn globals(&self) -> BufferHandle {
+        self.globals
+    }
+
+    pub fn vertex_capacity(&self) -> u32 {
+        self.vertex_capacity
+    }
+
+    pub fn index_capacity(&self) -> u32 {
+        self.index_capacity
+    }
+
+    fn create_vertex_buffer<B: RenderBackend>(
+        backend: &mut B,
+        label: &str,
+        vertex_size: u32,
+        capacity: u32,
+    ) -> Result<BufferHandle, B::Error> {
+        backend.create_buffer(&BufferDescriptor {
+            label: format_smolstr!("{label} vertices"),
+            size: u64::from(capacity) * u64::from(vertex_size),
+            usage: BufferUsage::VERTEX | BufferUsage::COPY_DST,
+        })
+    }
+
+    fn create_index_buffer<B: RenderBackend>(
+        backend: &mut B,
+        label: &str,
+        capacity: u32,
+    ) -> Result<BufferHandle, B::Error> {
+        backend.create_buffer(&BufferDescriptor {
+            label: format_smolstr!("{label} indices"),
+            size: u64::from(capacity) * 4,
+            usage: BufferUsage::INDEX | BufferUsage::COPY_DST,
+        })
+    }
+
+    /// Grow the buffers to hold `vertices` and `indices`, returns true if one was replaced.
+    ///
+    /// A replaced buffer loses its content.
+    pub fn reserve<B: RenderBackend>(
+        &mut self,
+        backend: &mut B,
+        vertices: u32,
+        indices: u32,
+    ) -> Result<bool, B::Error> {
+        let mut replaced = false;
+        if vertices > self.vertex_capacity {
+            let capacity = vertices.next_power_of_two();
+            let buffer =
+                Self::create_vertex_buffer(backend, self.label, self.vertex_size, capacity)?;
+            backend.destroy_buffer(std::mem::replace(&mut self.vertices, buffer))?;
+            self.vertex_capacity = capacity;
+            replaced = true;
+        }
+        if indices > self.index_capacity {
+            let capacity = indices.next_power_of_two();
+            let buffer = Self::create_index_buffer(backend, self.label, capacity)?;
+            backend.destroy_buffer(std::mem::replace(&mut self.indices, buffer))?;
+            self.index_capacity = capacity;
+            replaced = true;
+        }
+        Ok(replaced)
+    }
+
+    /// Record the write of `target_size`, in pixels, to the globals.
+    pub fn write_globals(&mut self, commands: &mut CommandBuffer, target_size: Size) {
+        self.scratch.clear();
+        for value in [
+            target_size.width as f32,
+            target_size.height as f32,
+            0.0,
+            0.0,
+        ] {
+            self.scratch.extend_from_slice(&value.to_ne_bytes());
+        }
+        commands.write_buffer(self.globals, 0, &self.scratch);
+    }
+
+    /// Record the write of the vertex bytes `write` appends, from the first vertex on.
+    pub fn write_vertices(
+        &mut self,
+        commands: &mut CommandBuffer,
+        write: impl FnOnce(&mut Vec<u8>),
+    ) {
+        self.scratch.clear();
+        write(&mut self.scratch);
+        commands.write_buffer(self.vertices, 0, &self.scratch);
+    }
+
+    /// Record the write of `indices`, from the first index on.
+    pub fn write_indices(
+        &mut self,
+        commands: &mut CommandBuffer,
+        indices: impl IntoIterator<Item = u32>,
+    ) {
+        self.scratch.clear();
+        for index in indices {
+            self.scratch.extend_from_slice(&index.to_ne_bytes());
+        }
+        commands.write_buffer(self.indices, 0, &self.scratch);
+    }
+
+    /// Record binding the vertex buffer to slot 0 and the index buffer, inside a pass.
+    pub fn bind(&self, commands: &mut CommandBuffer) {
+        commands
+            .set_vertex_buffer(0, self.vertices, 0)
+            .set_index_buffer(self.indices, 0, IndexFormat::Uint32);
+    }
+
+    /// Destroy the buffers.
+    pub fn destroy<B: RenderBackend>(self, backend: &mut B) -> Result<(), B::Error> {
+        backend.destroy_buffer(self.vertices)?;
+        backend.destroy_buffer(self.indices)?;
+        backend.destroy_buffer(self.globals)
+    }
+}
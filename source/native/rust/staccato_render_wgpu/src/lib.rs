@@ -1,14 +1,6 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
-    }
-}
+pub mod geometry_buffers;
+pub mod shape;
+pub mod shape_renderer;
+pub mod sprite;
+pub mod sprite_renderer;
+pub mod wgpu_backend;
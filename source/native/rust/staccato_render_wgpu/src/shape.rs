@@ -0,0 +1,452 @@
+use staccato_core::color::Color;
+use staccato_core::frect::{FPoint, FRect};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+/// Longest segment of the polygon approximating a circle, in pixels.
+const MAX_ARC_SEGMENT: f32 = 4.0;
+
+/// Joins sharper than this fall back to a shorter miter, as a multiple of the half width.
+const MAX_MITER: f32 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShapeStyle {
+    Fill,
+    /// An outline of the width in pixels, centered on the edge.
+    Stroke(f32),
+}
+
+/// The vertex layout of `shape.wgsl`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[repr(C)]
+pub struct ShapeVertex {
+    pub position: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl ShapeVertex {
+    /// In bytes.
+    pub const SIZE: u32 = size_of::<Self>() as u32;
+
+    fn new(point: FPoint, color: Color) -> Self {
+        Self {
+            position: [point.x, point.y],
+            color: color.to_array(),
+        }
+    }
+
+    pub(crate) fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        for value in self.position.iter().chain(&self.color) {
+            bytes.extend_from_slice(&value.to_ne_bytes());
+        }
+    }
+}
+
+fn point(x: f32, y: f32) -> FPoint {
+    FPoint::new(x, y)
+}
+
+fn offset(p: FPoint, normal: FPoint, distance: f32) -> FPoint {
+    point(p.x + normal.x * distance, p.y + normal.y * distance)
+}
+
+/// Number of segments for a full circle of `radius`.
+fn circle_segments(radius: f32) -> usize {
+    ((TAU * radius / MAX_ARC_SEGMENT).ceil() as usize).clamp(8, 256)
+}
+
+/// Immediate-mode 2D shapes tessellated to triangles on the CPU.
+///
+/// Every `draw_*` call appends to one indexed triangle list, clear it once per frame.
+/// Edges are anti-aliased by a fringe of `feather` pixels fading to transparent.
+/// Colors are straight alpha, drawn with `BlendMode::Alpha`.
+///
+/// ```ignore
+/// shapes
+///     .draw_rect(panel, Color::BLACK.with_alpha(0.5), ShapeStyle::Fill)
+///     .draw_circle(cursor, 6.0, Color::RED, ShapeStyle::Stroke(2.0));
+/// ```
+#[derive(Debug)]
+pub struct Shapes {
+    vertices: Vec<ShapeVertex>,
+    indices: Vec<u32>,
+    feather: f32,
+    /// the outline being tessellated
+    path: Vec<FPoint>,
+    /// the miter normal of every point of `path`
+    normals: Vec<FPoint>,
+}
+
+impl Default for Shapes {
+    fn default() -> Self {
+        Self {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            feather: 1.0,
+            path: Vec::new(),
+            normals: Vec::new(),
+        }
+    }
+}
+
+impl Shapes {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Width of the anti-aliasing fringe in pixels, 0.0 disables anti-aliasing.
+    pub fn feather(&self) -> f32 {
+        self.feather
+    }
+
+    pub fn set_feather(&mut self, feather: f32) -> &mut Self {
+        self.feather = feather.max(0.0);
+        self
+    }
+
+    pub fn vertices(&self) -> &[ShapeVertex] {
+        &self.vertices
+    }
+
+    /// Three per triangle.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Remove all shapes and keep the allocations for the next frame.
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.indices.clear();
+    }
+
+    pub fn draw_rect(&mut self, rect: FRect, color: Color, style: ShapeStyle) -> &mut Self {
+        let FRect { position, size } = rect;
+        self.path.clear();
+        self.path.extend([
+            position,
+            point(position.x + size.width, position.y),
+            point(position.x + size.width, position.y + size.height),
+            point(position.x, position.y + size.height),
+        ]);
+        self.finish_path(color, style)
+    }
+
+    /// A line of `width` pixels with flat ends at `from` and `to`.
+    pub fn draw_line(&mut self, from: FPoint, to: FPoint, color: Color, width: f32) -> &mut Self {
+        self.path.clear();
+        self.path.extend([from, to]);
+        self.stroke_path(color, width, false);
+        self
+    }
+
+    pub fn draw_circle(
+        &mut self,
+        center: FPoint,
+        radius: f32,
+        color: Color,
+        style: ShapeStyle,
+    ) -> &mut Self {
+        self.path.clear();
+        self.arc(center, radius, 0.0, TAU, circle_segments(radius), false);
+        self.finish_path(color, style)
+    }
+
+    /// A closed polygon through `points`, a filled polygon must be convex.
+    pub fn draw_polygon(
+        &mut self,
+        points: &[FPoint],
+        color: Color,
+        style: ShapeStyle,
+    ) -> &mut Self {
+        self.path.clear();
+        self.path.extend_from_slice(points);
+        self.finish_path(color, style)
+    }
+
+    /// A rectangle with corners rounded by `radius`, at most half of its shorter side.
+    pub fn draw_rounded_rect(
+        &mut self,
+        rect: FRect,
+        radius: f32,
+        color: Color,
+        style: ShapeStyle,
+    ) -> &mut Self {
+        let FRect { position, size } = rect;
+        let radius = radius.min(size.width * 0.5).min(size.height * 0.5);
+        if radius <= 0.0 {
+            return self.draw_rect(rect, color, style);
+        }
+
+        let segments = (circle_segments(radius) / 4).max(2);
+        let left = position.x + radius;
+        let right = position.x + size.width - radius;
+        let top = position.y + radius;
+        let bottom = position.y + size.height - radius;
+
+        self.path.clear();
+        // clockwise on screen from the top left corner
+        self.arc(point(left, top), radius, PI, PI + FRAC_PI_2, segments, true);
+        self.arc(point(right, top), radius, -FRAC_PI_2, 0.0, segments, true);
+        self.arc(point(right, bottom), radius, 0.0, FRAC_PI_2, segments, true);
+        self.arc(point(left, bottom), radius, FRAC_PI_2, PI, segments, true);
+        self.finish_path(color, style)
+    }
+
+    /// Append `segments` points of an arc to the path, and the end point if `inclusive`.
+    fn arc(
+        &mut self,
+        center: FPoint,
+        radius: f32,
+        start: f32,
+        end: f32,
+        segments: usize,
+        inclusive: bool,
+    ) {
+        let count = if inclusive { segments + 1 } else { segments };
+        self.path.extend((0..count).map(|i| {
+            let angle = start + (end - start) * i as f32 / segments as f32;
+            let (sin, cos) = angle.sin_cos();
+            point(center.x + cos * radius, center.y + sin * radius)
+        }));
+    }
+
+    fn finish_path(&mut self, color: Color, style: ShapeStyle) -> &mut Self {
+        match style {
+            ShapeStyle::Fill => self.fill_path(color),
+            ShapeStyle::Stroke(width) => self.stroke_path(color, width, true),
+        }
+        self
+    }
+
+    /// Remove repeated points and compute the miter normals, return the point count.
+    ///
+    /// Normals point to the right of the path on screen, inwards for a clockwise path.
+    fn prepare_path(&mut self, closed: bool) -> usize {
+        self.path
+            .dedup_by(|a, b| (a.x - b.x).abs() < 1e-4 && (a.y - b.y).abs() < 1e-4);
+        if closed
+            && self.path.len() > 1
+            && let (Some(first), Some(last)) = (self.path.first(), self.path.last())
+            && (first.x - last.x).abs() < 1e-4
+            && (first.y - last.y).abs() < 1e-4
+        {
+            self.path.pop();
+        }
+
+        let count = self.path.len();
+        let edge_normal = |from: FPoint, to: FPoint| {
+            let (dx, dy) = (to.x - from.x, to.y - from.y);
+            let length = (dx * dx + dy * dy).sqrt();
+            point(-dy / length, dx / length)
+        };
+
+        self.normals.clear();
+        for i in 0..count {
+            let p = self.path[i];
+            let previous = match i {
+                0 if closed => Some(edge_normal(self.path[count - 1], p)),
+                0 => None,
+                _ => Some(edge_normal(self.path[i - 1], p)),
+            };
+            let next = match i + 1 {
+                next if next < count => Some(edge_normal(p, self.path[next])),
+                _ if closed => Some(edge_normal(p, self.path[0])),
+                _ => None,
+            };
+
+            let normal = match (previous, next) {
+                (Some(a), Some(b)) => {
+                    let (x, y) = ((a.x + b.x) * 0.5, (a.y + b.y) * 0.5);
+                    let scale = 1.0 / (x * x + y * y).max(1.0 / (MAX_MITER * MAX_MITER));
+                    point(x * scale, y * scale)
+                }
+                (Some(normal), None) | (None, Some(normal)) => normal,
+                (None, None) => point(0.0, 0.0),
+            };
+            self.normals.push(normal);
+        }
+        count
+    }
+
+    fn quad(&mut self, a: u32, b: u32, c: u32, d: u32) {
+        self.indices.extend([a, b, c, c, d, a]);
+    }
+
+    fn fill_path(&mut self, color: Color) {
+        let count = self.prepare_path(true);
+        if count < 3 {
+            return;
+        }
+
+        // twice the signed area, positive for a clockwise path on screen
+        let area: f32 = (0..count)
+            .map(|i| {
+                let (a, b) = (self.path[i], self.path[(i + 1) % count]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum();
+        if area == 0.0 {
+            return;
+        }
+        let outwards = -area.signum();
+
+        let base = self.vertices.len() as u32;
+        let count = count as u32;
+        if self.feather > 0.0 {
+            let half = self.feather * 0.5 * outwards;
+            for (p, normal) in self.path.iter().zip(&self.normals) {
+                self.vertices.extend([
+                    ShapeVertex::new(offset(*p, *normal, -half), color),
+                    ShapeVertex::new(offset(*p, *normal, half), color.with_alpha(0.0)),
+                ]);
+            }
+            for i in 2..count {
+                self.indices
+                    .extend([base, base + (i - 1) * 2, base + i * 2]);
+            }
+            for i in 0..count {
+                let j = (i + 1) % count;
+                self.quad(
+                    base + i * 2,
+                    base + j * 2,
+                    base + j * 2 + 1,
+                    base + i * 2 + 1,
+                );
+            }
+        } else {
+            self.vertices
+                .extend(self.path.iter().map(|p| ShapeVertex::new(*p, color)));
+            for i in 2..count {
+                self.indices.extend([base, base + i - 1, base + i]);
+            }
+        }
+    }
+
+    fn stroke_path(&mut self, color: Color, width: f32, closed: bool) {
+        let closed = closed && self.path.len() > 2;
+        let count = self.prepare_path(closed);
+        if count < 2 || width <= 0.0 {
+            return;
+        }
+
+        let base = self.vertices.len() as u32;
+        let segments = if closed { count } else { count - 1 } as u32;
+        let count = count as u32;
+        if self.feather > 0.0 {
+            // lines thinner than the fringe fade instead of getting thinner
+            let solid = ((width - self.feather) * 0.5).max(0.0);
+            let color = color.with_alpha(color.a * (width / self.feather).min(1.0));
+            let transparent = color.with_alpha(0.0);
+            for (p, normal) in self.path.iter().zip(&self.normals) {
+                self.vertices.extend([
+                    ShapeVertex::new(offset(*p, *normal, solid + self.feather), transparent),
+                    ShapeVertex::new(offset(*p, *normal, solid), color),
+                    ShapeVertex::new(offset(*p, *normal, -solid), color),
+                    ShapeVertex::new(offset(*p, *normal, -solid - self.feather), transparent),
+                ]);
+            }
+            for i in 0..segments {
+                let (a, b) = (base + i * 4, base + (i + 1) % count * 4);
+                for k in 0..3 {
+                    self.quad(a + k, b + k, b + k + 1, a + k + 1);
+                }
+            }
+        } else {
+            let half = width * 0.5;
+            for (p, normal) in self.path.iter().zip(&self.normals) {
+                self.vertices.extend([
+                    ShapeVertex::new(offset(*p, *normal, half), color),
+                    ShapeVertex::new(offset(*p, *normal, -half), color),
+                ]);
+            }
+            for i in 0..segments {
+                let (a, b) = (base + i * 2, base + (i + 1) % count * 2);
+                self.quad(a, b, b + 1, a + 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn positions(shapes: &Shapes) -> Vec<[f32; 2]> {
+        shapes.vertices().iter().map(|v| v.position).collect()
+    }
+
+    #[test]
+    fn fills_rect_with_fringe() {
+        let mut shapes = Shapes::new();
+        let rect = FRect::new(10.0, 20.0, 30.0, 40.0);
+
+        shapes
+            .set_feather(0.0)
+            .draw_rect(rect, Color::RED, ShapeStyle::Fill);
+        assert_eq!(
+            positions(&shapes),
+            [[10.0, 20.0], [40.0, 20.0], [40.0, 60.0], [10.0, 60.0]]
+        );
+        assert_eq!(shapes.indices(), [0, 1, 2, 0, 2, 3]);
+
+        shapes.clear();
+        shapes
+            .set_feather(1.0)
+            .draw_rect(rect, Color::RED, ShapeStyle::Fill);
+        // inner and outer corner of every point, half a pixel from the edge
+        assert_eq!(&positions(&shapes)[..2], [[10.5, 20.5], [9.5, 19.5]]);
+        assert_eq!(shapes.vertices()[0].color, [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(shapes.vertices()[1].color, [1.0, 0.0, 0.0, 0.0]);
+        // two for the inside and two per edge for the fringe
+        assert_eq!(shapes.indices().len(), (2 + 4 * 2) * 3);
+        assert!(shapes.indices().iter().all(|i| (*i as usize) < 8));
+    }
+
+    #[test]
+    fn strokes_lines_and_shapes() {
+        let mut shapes = Shapes::new();
+        shapes.draw_line(
+            FPoint::new(0.0, 0.0),
+            FPoint::new(10.0, 0.0),
+            Color::WHITE,
+            3.0,
+        );
+        // fringe, solid, solid, fringe on both ends
+        assert_eq!(
+            &positions(&shapes)[..4],
+            [[0.0, 2.0], [0.0, 1.0], [0.0, -1.0], [0.0, -2.0]]
+        );
+        assert_eq!(shapes.indices().len(), 3 * 6);
+
+        let vertices = shapes.vertices().len();
+        shapes.draw_circle(
+            FPoint::new(0.0, 0.0),
+            10.0,
+            Color::WHITE,
+            ShapeStyle::Stroke(2.0),
+        );
+        let segments = circle_segments(10.0);
+        assert_eq!(shapes.vertices().len() - vertices, segments * 4);
+        for vertex in &shapes.vertices()[vertices..] {
+            let [x, y] = vertex.position;
+            let distance = (x * x + y * y).sqrt();
+            assert!((8.4..=11.6).contains(&distance));
+        }
+
+        shapes.clear();
+        shapes.draw_rounded_rect(
+            FRect::new(0.0, 0.0, 20.0, 10.0),
+            50.0,
+            Color::WHITE,
+            ShapeStyle::Fill,
+        );
+        assert!(
+            positions(&shapes)
+                .iter()
+                .all(|[x, y]| { (-0.6..=20.6).contains(x) && (-0.6..=10.6).contains(y) })
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use crate::geometry_buffers::GeometryBuffers;
+use crate::shape::{ShapeVertex, Shapes};
+use staccato_core::rect::Size;
+use staccato_render_api::backend::RenderBackend;
+use staccato_render_api::command::CommandBuffer;
+use staccato_render_api::handle::{BindGroupHandle, PipelineHandle, ShaderHandle};
+use staccato_render_api::resource::{
+    BindEntry, BindGroupDescriptor, BindResource, BlendMode, PipelineDescriptor, PrimitiveTopology,
+    ShaderDescriptor, TextureFormat, VertexAttribute, VertexFormat, VertexLayout,
+};
+
+pub const SHAPE_SHADER: &str = include_str!("shape.wgsl");
+
+/// Vertices the vertex buffer holds before it grows for the first time.
+pub const DEFAULT_SHAPE_VERTEX_CAPACITY: u32 = 4096;
+
+/// Draw `Shapes` through a `RenderBackend`.
+///
+/// Like `SpriteRenderer`, the buffers are `GeometryBuffers` kept between frames.
+///
+/// ```ignore
+/// renderer.prepare(&mut backend, &mut commands, &shapes, target_size)?;
+/// commands.begin_pass(RenderTarget::Surface, LoadOp::Load);
+/// renderer.draw(&mut commands);
+/// commands.end_pass();
+/// ```
+#[derive(Debug)]
+pub struct ShapeRenderer {
+    color_format: TextureFormat,
+    shader: ShaderHandle,
+    pipeline: PipelineHandle,
+    buffers: GeometryBuffers,
+    bind_group: BindGroupHandle,
+    /// indices of the last `prepare`
+    index_count: u32,
+}
+
+impl ShapeRenderer {
+    /// A renderer drawing into targets of `color_format`.
+    pub fn new<B: RenderBackend>(
+        backend: &mut B,
+        color_format: TextureFormat,
+    ) -> Result<Self, B::Error> {
+        let shader = backend.create_shader(&ShaderDescriptor {
+            label: "shape".into(),
+            wgsl: SHAPE_SHADER.into(),
+        })?;
+        let pipeline = backend.create_pipeline(&PipelineDescriptor {
+            label: "shape".into(),
+            shader,
+            vertex_entry: "vs_main".into(),
+            fragment_entry: "fs_main".into(),
+            vertex_layouts: vec![VertexLayout {
+                stride: ShapeVertex::SIZE,
+                per_instance: false,
+                attributes: vec![
+                    VertexAttribute {
+                        location: 0,
+                        offset: 0,
+                        format: VertexFormat::Float32x2,
+                    },
+                    VertexAttribute {
+                        location: 1,
+                        offset: 8,
+                        format: VertexFormat::Float32x4,
+                    },
+                ],
+            }],
+            color_format,
+            depth_format: None,
+            blend: BlendMode::Alpha,
+            topology: PrimitiveTopology::TriangleList,
+        })?;
+        let buffers = GeometryBuffers::new(
+            backend,
+            "shape",
+            ShapeVertex::SIZE,
+            DEFAULT_SHAPE_VERTEX_CAPACITY,
+            DEFAULT_SHAPE_VERTEX_CAPACITY * 3,
+        )?;
+        let bind_group = backend.create_bind_group(&BindGroupDescriptor {
+            label: "shape".into(),
+            pipeline,
+            group: 0,
+            entries: vec![BindEntry {
+                binding: 0,
+                resource: BindResource::Buffer {
+                    buffer: buffers.globals(),
+                    offset: 0,
+                    size: None,
+                },
+            }],
+        })?;
+
+        Ok(Self {
+            color_format,
+            shader,
+            pipeline,
+            buffers,
+            bind_group,
+            index_count: 0,
+        })
+    }
+
+    pub fn color_format(&self) -> TextureFormat {
+        self.color_format
+    }
+
+    /// Record the buffer writes for `shapes`, outside of a pass.
+    ///
+    /// Call it once per frame before `draw`, `target_size` is in pixels.
+    pub fn prepare<B: RenderBackend>(
+        &mut self,
+        backend: &mut B,
+        commands: &mut CommandBuffer,
+        shapes: &Shapes,
+        target_size: Size,
+    ) -> Result<(), B::Error> {
+        self.index_count = 0;
+        if shapes.is_empty() {
+            return Ok(());
+        }
+
+        let index_count = shapes.indices().len() as u32;
+        self.buffers
+            .reserve(backend, shapes.vertices().len() as u32, index_count)?;
+
+        self.buffers.write_globals(commands, target_size);
+        self.buffers.write_vertices(commands, |bytes| {
+            for vertex in shapes.vertices() {
+                vertex.write_bytes(bytes);
+            }
+        });
+        self.buffers
+            .write_indices(commands, shapes.indices().iter().copied());
+
+        self.index_count = index_count;
+        Ok(())
+    }
+
+    /// Record the draw of the last `prepare`, inside a pass.
+    pub fn draw(&self, commands: &mut CommandBuffer) {
+        if self.index_count == 0 {
+            return;
+        }
+
+        commands
+            .set_pipeline(self.pipeline)
+            .set_bind_group(0, self.bind_group);
+        self.buffers.bind(commands);
+        commands.draw_indexed(0..self.index_count, 0, 0..1);
+    }
+
+    /// Destroy every resource of the renderer.
+    pub fn destroy<B: RenderBackend>(self, backend: &mut B) -> Result<(), B::Error> {
+        backend.destroy_bind_group(self.bind_group)?;
+        backend.destroy_pipeline(self.pipeline)?;
+        self.buffers.destroy(backend)?;
+        backend.destroy_shader(self.shader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::ShapeStyle;
+    use staccato_core::color::Color;
+    use staccato_core::frect::FRect;
+    use staccato_render_api::command::{LoadOp, RenderCommand, RenderTarget};
+    use staccato_render_api::recording_backend::{RecordingBackend, RenderError};
+
+    #[test]
+    fn reuses_buffers_and_grows_once() -> Result<(), RenderError> {
+        let size = Size::new(320, 240);
+        let mut backend = RecordingBackend::with_surface(size, TextureFormat::Bgra8UnormSrgb);
+        let mut renderer = ShapeRenderer::new(&mut backend, TextureFormat::Bgra8UnormSrgb)?;
+
+        let mut shapes = Shapes::new();
+        let mut commands = CommandBuffer::new();
+        let mut vertex_buffers = Vec::new();
+        for rects in [10, 2000, 0, 20] {
+            shapes.clear();
+            commands.clear();
+            for i in 0..rects {
+                shapes.draw_rect(
+                    FRect::new(i as f32, 0.0, 8.0, 8.0),
+                    Color::WHITE,
+                    ShapeStyle::Fill,
+                );
+            }
+            renderer.prepare(&mut backend, &mut commands, &shapes, size)?;
+            commands.begin_pass(RenderTarget::Surface, LoadOp::Clear(Color::BLACK));
+            renderer.draw(&mut commands);
+            commands.end_pass();
+            backend.submit(&commands)?;
+
+            let draws: Vec<_> = commands
+                .commands()
+                .iter()
+                .filter_map(|c| match c {
+                    RenderCommand::DrawIndexed { index_count, .. } => Some(*index_count),
+                    _ => None,
+                })
+                .collect();
+            if rects == 0 {
+                assert!(draws.is_empty());
+            } else {
+                assert_eq!(draws, [shapes.indices().len() as u32]);
+            }
+            vertex_buffers.extend(commands.commands().iter().filter_map(|c| match c {
+                RenderCommand::SetVertexBuffer { buffer, .. } => Some(*buffer),
+                _ => None,
+            }));
+        }
+
+        // the 2000 rectangles need more than the default capacity
+        assert_eq!(vertex_buffers.len(), 3);
+        assert_ne!(vertex_buffers[0], vertex_buffers[1]);
+        assert_eq!(vertex_buffers[1], vertex_buffers[2]);
+
+        renderer.destroy(&mut backend)?;
+        assert_eq!(backend.live_resources(), 0);
+        Ok(())
+    }
+}
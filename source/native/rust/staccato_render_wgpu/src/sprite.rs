@@ -0,0 +1,298 @@
+use staccato_core::color::Color;
+use staccato_core::frect::{FPoint, FRect};
+use staccato_core::rect::{Rect, Size};
+use staccato_render_api::handle::TextureHandle;
+use staccato_render_api::resource::BlendMode;
+use std::ops::Range;
+
+/// A textured quad.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprite {
+    pub texture: TextureHandle,
+    /// where to draw, in pixels of the target
+    pub rect: FRect,
+    /// the part of the texture to draw, from 0.0 to 1.0
+    pub uv: FRect,
+    /// clockwise, in radians
+    pub rotation: f32,
+    /// the point to rotate around, from (0.0, 0.0) at the top left of `rect` to (1.0, 1.0)
+    pub origin: FPoint,
+    /// multiplied with the texture
+    pub tint: Color,
+    /// sprites with a greater z are drawn over
+    pub z: f32,
+    pub blend: BlendMode,
+}
+
+impl Sprite {
+    /// The whole texture in `rect`, not rotated nor tinted.
+    pub fn new(texture: TextureHandle, rect: FRect) -> Self {
+        Self {
+            texture,
+            rect,
+            uv: FRect::new(0.0, 0.0, 1.0, 1.0),
+            rotation: 0.0,
+            origin: FPoint::new(0.5, 0.5),
+            tint: Color::WHITE,
+            z: 0.0,
+            blend: BlendMode::Alpha,
+        }
+    }
+
+    #[must_use]
+    pub fn with_uv(self, uv: FRect) -> Self {
+        Self { uv, ..self }
+    }
+
+    /// Draw the `region` in pixels of an atlas of `atlas_size`.
+    #[must_use]
+    pub fn with_region(self, region: Rect, atlas_size: Size) -> Self {
+        let width = atlas_size.width.max(1) as f32;
+        let height = atlas_size.height.max(1) as f32;
+        self.with_uv(FRect::new(
+            region.position.x as f32 / width,
+            region.position.y as f32 / height,
+            region.size.width as f32 / width,
+            region.size.height as f32 / height,
+        ))
+    }
+
+    #[must_use]
+    pub fn with_rotation(self, rotation: f32, origin: FPoint) -> Self {
+        Self {
+            rotation,
+            origin,
+            ..self
+        }
+    }
+
+    #[must_use]
+    pub fn with_tint(self, tint: Color) -> Self {
+        Self { tint, ..self }
+    }
+
+    #[must_use]
+    pub fn with_z(self, z: f32) -> Self {
+        Self { z, ..self }
+    }
+
+    #[must_use]
+    pub fn with_blend(self, blend: BlendMode) -> Self {
+        Self { blend, ..self }
+    }
+
+    /// The corners from the top left, clockwise.
+    fn vertices(&self) -> [SpriteVertex; 4] {
+        let FRect { position, size } = self.rect;
+        let pivot = FPoint::new(
+            position.x + self.origin.x * size.width,
+            position.y + self.origin.y * size.height,
+        );
+        let (sin, cos) = self.rotation.sin_cos();
+        let color = match self.blend {
+            BlendMode::Premultiplied => self.tint.premultiplied(),
+            _ => self.tint,
+        }
+        .to_array();
+
+        [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)].map(|(u, v)| {
+            let x = position.x + u * size.width - pivot.x;
+            let y = position.y + v * size.height - pivot.y;
+            SpriteVertex {
+                position: [pivot.x + x * cos - y * sin, pivot.y + x * sin + y * cos],
+                uv: [
+                    self.uv.position.x + u * self.uv.size.width,
+                    self.uv.position.y + v * self.uv.size.height,
+                ],
+                color,
+            }
+        })
+    }
+}
+
+/// The vertex layout of `sprite.wgsl`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[repr(C)]
+pub struct SpriteVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl SpriteVertex {
+    /// In bytes.
+    pub const SIZE: u32 = size_of::<Self>() as u32;
+
+    pub(crate) fn write_bytes(&self, bytes: &mut Vec<u8>) {
+        for value in self.position.iter().chain(&self.uv).chain(&self.color) {
+            bytes.extend_from_slice(&value.to_ne_bytes());
+        }
+    }
+}
+
+/// Consecutive sprites drawn with one draw call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpriteDraw {
+    pub texture: TextureHandle,
+    pub blend: BlendMode,
+    pub sprites: Range<u32>,
+}
+
+impl SpriteDraw {
+    /// The indices of the sprites in the quad index buffer.
+    pub fn indices(&self) -> Range<u32> {
+        self.sprites.start * 6..self.sprites.end * 6
+    }
+}
+
+/// Collect sprites of a frame and turn them into vertices and draw calls.
+///
+/// Sprites are sorted by z, then sprites of the same z by blend mode and texture so
+/// that they share draw calls. Sprites of the same z, blend mode and texture keep the
+/// order they were pushed in. Give overlapping sprites different z to control which
+/// one is on top.
+#[derive(Debug, Default)]
+pub struct SpriteBatch {
+    sprites: Vec<Sprite>,
+    vertices: Vec<SpriteVertex>,
+    draws: Vec<SpriteDraw>,
+}
+
+impl SpriteBatch {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn push(&mut self, sprite: Sprite) -> &mut Self {
+        self.sprites.push(sprite);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Remove all sprites and keep the allocations for the next frame.
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+        self.vertices.clear();
+        self.draws.clear();
+    }
+
+    /// Sort the sprites and rebuild vertices and draws.
+    pub fn build(&mut self) {
+        self.sprites.sort_by(|a, b| {
+            a.z.total_cmp(&b.z)
+                .then(a.blend.cmp(&b.blend))
+                .then(a.texture.cmp(&b.texture))
+        });
+
+        self.vertices.clear();
+        self.draws.clear();
+        for (index, sprite) in self.sprites.iter().enumerate() {
+            self.vertices.extend(sprite.vertices());
+
+            let index = index as u32;
+            match self.draws.last_mut() {
+                Some(draw) if draw.texture == sprite.texture && draw.blend == sprite.blend => {
+                    draw.sprites.end = index + 1;
+                }
+                _ => self.draws.push(SpriteDraw {
+                    texture: sprite.texture,
+                    blend: sprite.blend,
+                    sprites: index..index + 1,
+                }),
+            }
+        }
+    }
+
+    /// Sorted sprites, after `build`.
+    pub fn sprites(&self) -> &[Sprite] {
+        &self.sprites
+    }
+
+    /// Four vertices per sprite, after `build`.
+    pub fn vertices(&self) -> &[SpriteVertex] {
+        &self.vertices
+    }
+
+    /// After `build`.
+    pub fn draws(&self) -> &[SpriteDraw] {
+        &self.draws
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_by_texture_and_blend_in_z_order() {
+        let atlas = TextureHandle::new(0, 0);
+        let other = TextureHandle::new(1, 0);
+        let rect = FRect::new(0.0, 0.0, 10.0, 10.0);
+
+        let mut batch = SpriteBatch::new();
+        batch
+            .push(Sprite::new(other, rect).with_z(1.0))
+            .push(Sprite::new(atlas, rect).with_z(1.0))
+            .push(Sprite::new(atlas, rect).with_z(2.0))
+            .push(Sprite::new(atlas, rect))
+            .push(Sprite::new(other, rect).with_z(1.0))
+            .push(Sprite::new(atlas, rect).with_blend(BlendMode::Additive));
+        batch.build();
+
+        assert_eq!(
+            batch.draws(),
+            [
+                SpriteDraw {
+                    texture: atlas,
+                    blend: BlendMode::Alpha,
+                    sprites: 0..1,
+                },
+                SpriteDraw {
+                    texture: atlas,
+                    blend: BlendMode::Additive,
+                    sprites: 1..2,
+                },
+                SpriteDraw {
+                    texture: atlas,
+                    blend: BlendMode::Alpha,
+                    sprites: 2..3,
+                },
+                SpriteDraw {
+                    texture: other,
+                    blend: BlendMode::Alpha,
+                    sprites: 3..5,
+                },
+                SpriteDraw {
+                    texture: atlas,
+                    blend: BlendMode::Alpha,
+                    sprites: 5..6,
+                },
+            ]
+        );
+        assert_eq!(batch.vertices().len(), 24);
+    }
+
+    #[test]
+    fn rotates_around_origin_with_atlas_uv() {
+        let sprite = Sprite::new(TextureHandle::new(0, 0), FRect::new(10.0, 10.0, 20.0, 10.0))
+            .with_region(Rect::new(32, 0, 32, 16), Size::new(64, 64))
+            .with_rotation(std::f32::consts::FRAC_PI_2, FPoint::new(0.0, 0.0));
+        let vertices = sprite.vertices();
+
+        let close =
+            |a: [f32; 2], b: [f32; 2]| (a[0] - b[0]).abs() < 1e-4 && (a[1] - b[1]).abs() < 1e-4;
+        // a quarter turn clockwise around the top left corner
+        assert!(close(vertices[0].position, [10.0, 10.0]));
+        assert!(close(vertices[1].position, [10.0, 30.0]));
+        assert!(close(vertices[2].position, [0.0, 30.0]));
+        assert_eq!(vertices[0].uv, [0.5, 0.0]);
+        assert_eq!(vertices[2].uv, [1.0, 0.25]);
+    }
+}
@@ -0,0 +1,346 @@
+use crate::geometry_buffers::GeometryBuffers;
+use crate::sprite::{SpriteBatch, SpriteVertex};
+use staccato_core::rect::Size;
+use staccato_render_api::backend::RenderBackend;
+use staccato_render_api::command::CommandBuffer;
+use staccato_render_api::handle::{
+    BindGroupHandle, PipelineHandle, SamplerHandle, ShaderHandle, TextureHandle,
+};
+use staccato_render_api::resource::{
+    AddressMode, BindEntry, BindGroupDescriptor, BindResource, BlendMode, FilterMode,
+    PipelineDescriptor, PrimitiveTopology, SamplerDescriptor, ShaderDescriptor, TextureFormat,
+    VertexAttribute, VertexFormat, VertexLayout,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+pub const SPRITE_SHADER: &str = include_str!("sprite.wgsl");
+
+/// Sprites the buffers hold before they grow for the first time.
+pub const DEFAULT_SPRITE_CAPACITY: u32 = 1024;
+
+/// A sprite draw resolved to the resources it binds.
+#[derive(Debug, Clone)]
+struct PreparedDraw {
+    pipeline: PipelineHandle,
+    bind_group: BindGroupHandle,
+    indices: Range<u32>,
+}
+
+/// Draw `SpriteBatch`es through a `RenderBackend`.
+///
+/// The vertex and index buffers are `GeometryBuffers`, they live as long as the renderer and
+/// only grow when a frame has more sprites than they hold. Pipelines are created per blend mode and
+/// bind groups per texture on first use.
+///
+/// ```ignore
+/// sprites.push(Sprite::new(atlas, FRect::new(0.0, 0.0, 32.0, 32.0)));
+/// renderer.prepare(&mut backend, &mut commands, &mut sprites, target_size)?;
+/// commands.begin_pass(RenderTarget::Surface, LoadOp::Clear(Color::BLACK));
+/// renderer.draw(&mut commands);
+/// commands.end_pass();
+/// backend.submit(&commands)?;
+/// ```
+#[derive(Debug)]
+pub struct SpriteRenderer {
+    color_format: TextureFormat,
+    shader: ShaderHandle,
+    sampler: SamplerHandle,
+    buffers: GeometryBuffers,
+    indices_written: bool,
+    pipelines: BTreeMap<BlendMode, PipelineHandle>,
+    bind_groups: HashMap<(BlendMode, TextureHandle), BindGroupHandle>,
+    draws: Vec<PreparedDraw>,
+}
+
+impl SpriteRenderer {
+    /// A renderer drawing into targets of `color_format`.
+    pub fn new<B: RenderBackend>(
+        backend: &mut B,
+        color_format: TextureFormat,
+    ) -> Result<Self, B::Error> {
+        let shader = backend.create_shader(&ShaderDescriptor {
+            label: "sprite".into(),
+            wgsl: SPRITE_SHADER.into(),
+        })?;
+        let sampler = backend.create_sampler(&SamplerDescriptor {
+            filter: FilterMode::Linear,
+            address: AddressMode::ClampToEdge,
+        })?;
+        let buffers = GeometryBuffers::new(
+            backend,
+            "sprite",
+            SpriteVertex::SIZE,
+            DEFAULT_SPRITE_CAPACITY * 4,
+            DEFAULT_SPRITE_CAPACITY * 6,
+        )?;
+
+        Ok(Self {
+            color_format,
+            shader,
+            sampler,
+            buffers,
+            indices_written: false,
+            pipelines: BTreeMap::new(),
+            bind_groups: HashMap::new(),
+            draws: Vec::new(),
+        })
+    }
+
+    pub fn color_format(&self) -> TextureFormat {
+        self.color_format
+    }
+
+    /// Sprites the buffers hold now.
+    pub fn capacity(&self) -> u32 {
+        (self.buffers.vertex_capacity() / 4).min(self.buffers.index_capacity() / 6)
+    }
+
+    /// Number of draw calls `draw` records.
+    pub fn draw_count(&self) -> usize {
+        self.draws.len()
+    }
+
+    fn pipeline<B: RenderBackend>(
+        &mut self,
+        backend: &mut B,
+        blend: BlendMode,
+    ) -> Result<PipelineHandle, B::Error> {
+        if let Some(pipeline) = self.pipelines.get(&blend) {
+            return Ok(*pipeline);
+        }
+
+        let pipeline = backend.create_pipeline(&PipelineDescriptor {
+            label: "sprite".into(),
+            shader: self.shader,
+            vertex_entry: "vs_main".into(),
+            fragment_entry: "fs_main".into(),
+            vertex_layouts: vec![VertexLayout {
+                stride: SpriteVertex::SIZE,
+                per_instance: false,
+                attributes: vec![
+                    VertexAttribute {
+                        location: 0,
+                        offset: 0,
+                        format: VertexFormat::Float32x2,
+                    },
+                    VertexAttribute {
+                        location: 1,
+                        offset: 8,
+                        format: VertexFormat::Float32x2,
+                    },
+                    VertexAttribute {
+                        location: 2,
+                        offset: 16,
+                        format: VertexFormat::Float32x4,
+                    },
+                ],
+            }],
+            color_format: self.color_format,
+            depth_format: None,
+            blend,
+            topology: PrimitiveTopology::TriangleList,
+        })?;
+        self.pipelines.insert(blend, pipeline);
+        Ok(pipeline)
+    }
+
+    fn bind_group<B: RenderBackend>(
+        &mut self,
+        backend: &mut B,
+        blend: BlendMode,
+        texture: TextureHandle,
+    ) -> Result<BindGroupHandle, B::Error> {
+        if let Some(bind_group) = self.bind_groups.get(&(blend, texture)) {
+            return Ok(*bind_group);
+        }
+
+        let pipeline = self.pipeline(backend, blend)?;
+        let bind_group = backend.create_bind_group(&BindGroupDescriptor {
+            label: "sprite".into(),
+            pipeline,
+            group: 0,
+            entries: vec![
+                BindEntry {
+                    binding: 0,
+                    resource: BindResource::Buffer {
+                        buffer: self.buffers.globals(),
+                        offset: 0,
+                        size: None,
+                    },
+                },
+                BindEntry {
+                    binding: 1,
+                    resource: BindResource::Texture(texture),
+                },
+                BindEntry {
+                    binding: 2,
+                    resource: BindResource::Sampler(self.sampler),
+                },
+            ],
+        })?;
+        self.bind_groups.insert((blend, texture), bind_group);
+        Ok(bind_group)
+    }
+
+    /// Build `batch` and record the buffer writes for it, outside of a pass.
+    ///
+    /// Call it once per frame before `draw`, `target_size` is in pixels.
+    pub fn prepare<B: RenderBackend>(
+        &mut self,
+        backend: &mut B,
+        commands: &mut CommandBuffer,
+        batch: &mut SpriteBatch,
+        target_size: Size,
+    ) -> Result<(), B::Error> {
+        batch.build();
+        self.draws.clear();
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        let sprites = batch.len() as u32;
+        if self.buffers.reserve(backend, sprites * 4, sprites * 6)? {
+            self.indices_written = false;
+        }
+
+        // the quads never change, so the indices are written once per buffer
+        if !self.indices_written {
+            let indices = (0..self.capacity()).flat_map(|sprite| {
+                let first = sprite * 4;
+                [first, first + 1, first + 2, first + 2, first + 3, first]
+            });
+            self.buffers.write_indices(commands, indices);
+            self.indices_written = true;
+        }
+
+        self.buffers.write_globals(commands, target_size);
+        self.buffers.write_vertices(commands, |bytes| {
+            for vertex in batch.vertices() {
+                vertex.write_bytes(bytes);
+            }
+        });
+
+        for draw in batch.draws() {
+            let pipeline = self.pipeline(backend, draw.blend)?;
+            let bind_group = self.bind_group(backend, draw.blend, draw.texture)?;
+            self.draws.push(PreparedDraw {
+                pipeline,
+                bind_group,
+                indices: draw.indices(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Record the draws of the last `prepare`, inside a pass.
+    pub fn draw(&self, commands: &mut CommandBuffer) {
+        if self.draws.is_empty() {
+            return;
+        }
+
+        self.buffers.bind(commands);
+
+        let mut current = None;
+        for draw in &self.draws {
+            if current != Some(draw.pipeline) {
+                commands.set_pipeline(draw.pipeline);
+                current = Some(draw.pipeline);
+            }
+            commands
+                .set_bind_group(0, draw.bind_group)
+                .draw_indexed(draw.indices.clone(), 0, 0..1);
+        }
+    }
+
+    /// Destroy the bind groups of `texture`, call it before destroying the texture.
+    pub fn forget_texture<B: RenderBackend>(
+        &mut self,
+        backend: &mut B,
+        texture: TextureHandle,
+    ) -> Result<(), B::Error> {
+        let mut result = Ok(());
+        self.bind_groups.retain(|(_, bound), bind_group| {
+            if *bound != texture {
+                return true;
+            }
+            if let Err(err) = backend.destroy_bind_group(*bind_group)
+                && result.is_ok()
+            {
+                result = Err(err);
+            }
+            false
+        });
+        self.draws.clear();
+        result
+    }
+
+    /// Destroy every resource of the renderer.
+    pub fn destroy<B: RenderBackend>(mut self, backend: &mut B) -> Result<(), B::Error> {
+        for (_, bind_group) in self.bind_groups.drain() {
+            backend.destroy_bind_group(bind_group)?;
+        }
+        for pipeline in self.pipelines.into_values() {
+            backend.destroy_pipeline(pipeline)?;
+        }
+        self.buffers.destroy(backend)?;
+        backend.destroy_sampler(self.sampler)?;
+        backend.destroy_shader(self.shader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprite::Sprite;
+    use staccato_core::color::Color;
+    use staccato_core::frect::FRect;
+    use staccato_render_api::command::{LoadOp, RenderCommand, RenderTarget};
+    use staccato_render_api::recording_backend::{RecordingBackend, RenderError};
+    use staccato_render_api::resource::{TextureDescriptor, TextureUsage};
+
+    #[test]
+    fn reuses_buffers_and_grows_once() -> Result<(), RenderError> {
+        let size = Size::new(320, 240);
+        let mut backend = RecordingBackend::with_surface(size, TextureFormat::Bgra8UnormSrgb);
+        let mut renderer = SpriteRenderer::new(&mut backend, TextureFormat::Bgra8UnormSrgb)?;
+        let atlas = backend.create_texture(&TextureDescriptor {
+            label: "atlas".into(),
+            size: Size::new(64, 64),
+            format: TextureFormat::Rgba8UnormSrgb,
+            usage: TextureUsage::SAMPLED | TextureUsage::COPY_DST,
+        })?;
+
+        let mut batch = SpriteBatch::new();
+        let mut commands = CommandBuffer::new();
+        let mut vertex_buffers = Vec::new();
+        for sprites in [10, 3000, 20] {
+            batch.clear();
+            commands.clear();
+            for i in 0..sprites {
+                batch.push(Sprite::new(atlas, FRect::new(i as f32, 0.0, 8.0, 8.0)));
+            }
+            renderer.prepare(&mut backend, &mut commands, &mut batch, size)?;
+            commands.begin_pass(RenderTarget::Surface, LoadOp::Clear(Color::BLACK));
+            renderer.draw(&mut commands);
+            commands.end_pass();
+            backend.submit(&commands)?;
+
+            assert_eq!(renderer.draw_count(), 1);
+            vertex_buffers.extend(commands.commands().iter().filter_map(|c| match c {
+                RenderCommand::SetVertexBuffer { buffer, .. } => Some(*buffer),
+                _ => None,
+            }));
+        }
+
+        assert_eq!(renderer.capacity(), 4096);
+        assert_ne!(vertex_buffers[0], vertex_buffers[1]);
+        assert_eq!(vertex_buffers[1], vertex_buffers[2]);
+
+        renderer.forget_texture(&mut backend, atlas)?;
+        backend.destroy_texture(atlas)?;
+        renderer.destroy(&mut backend)?;
+        assert_eq!(backend.live_resources(), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,1022 @@
+use pollster::FutureExt;
+use smol_str::ToSmolStr;
+use staccato_core::color::Color;
+use staccato_core::fallible::Fallible;
+use staccato_core::rect::{Point, Size};
+use staccato_render_api::backend::RenderBackend;
+use staccato_render_api::command::{
+    CommandBuffer, IndexFormat, LoadOp, RenderCommand, RenderTarget,
+};
+use staccato_render_api::error::RenderError;
+use staccato_render_api::handle::{
+    BindGroupHandle, BufferHandle, PipelineHandle, SamplerHandle, ShaderHandle, TextureHandle,
+};
+use staccato_render_api::pool::ResourcePool;
+use staccato_render_api::resource::{
+    AddressMode, BindGroupDescriptor, BindResource, BlendMode, BufferDescriptor, BufferUsage,
+    FilterMode, PipelineDescriptor, PrimitiveTopology, SamplerDescriptor, ShaderDescriptor,
+    TextureDescriptor, TextureFormat, TextureUsage, VertexFormat,
+};
+use std::sync::Arc;
+use thiserror::Error;
+use wgpu::{
+    BlendComponent, BlendFactor, BlendOperation, BlendState, CommandEncoder, Device, ErrorFilter,
+    Extent3d, Origin3d, Queue, RenderPass, TexelCopyBufferLayout, TexelCopyTextureInfo,
+    TextureView,
+};
+
+#[derive(Debug, Error)]
+pub enum WgpuBackendError {
+    #[error(transparent)]
+    Render(#[from] RenderError),
+    #[error("invalid texture size:{width}x{height}")]
+    InvalidSize { width: i32, height: i32 },
+    #[error("wgpu error:{0}")]
+    Wgpu(#[from] wgpu::Error),
+}
+
+pub fn texture_format_to_wgpu(format: TextureFormat) -> wgpu::TextureFormat {
+    match format {
+        TextureFormat::R8Unorm => wgpu::TextureFormat::R8Unorm,
+        TextureFormat::Rg8Unorm => wgpu::TextureFormat::Rg8Unorm,
+        TextureFormat::Rgba8Unorm => wgpu::TextureFormat::Rgba8Unorm,
+        TextureFormat::Rgba8UnormSrgb => wgpu::TextureFormat::Rgba8UnormSrgb,
+        TextureFormat::Bgra8Unorm => wgpu::TextureFormat::Bgra8Unorm,
+        TextureFormat::Bgra8UnormSrgb => wgpu::TextureFormat::Bgra8UnormSrgb,
+        TextureFormat::Rgb10a2Unorm => wgpu::TextureFormat::Rgb10a2Unorm,
+        TextureFormat::Rgba16Float => wgpu::TextureFormat::Rgba16Float,
+        TextureFormat::Depth32Float => wgpu::TextureFormat::Depth32Float,
+        TextureFormat::Depth24PlusStencil8 => wgpu::TextureFormat::Depth24PlusStencil8,
+    }
+}
+
+/// `None` for formats `TextureFormat` has no variant for.
+pub fn texture_format_from_wgpu(format: wgpu::TextureFormat) -> Option<TextureFormat> {
+    match format {
+        wgpu::TextureFormat::R8Unorm => Some(TextureFormat::R8Unorm),
+        wgpu::TextureFormat::Rg8Unorm => Some(TextureFormat::Rg8Unorm),
+        wgpu::TextureFormat::Rgba8Unorm => Some(TextureFormat::Rgba8Unorm),
+        wgpu::TextureFormat::Rgba8UnormSrgb => Some(TextureFormat::Rgba8UnormSrgb),
+        wgpu::TextureFormat::Bgra8Unorm => Some(TextureFormat::Bgra8Unorm),
+        wgpu::TextureFormat::Bgra8UnormSrgb => Some(TextureFormat::Bgra8UnormSrgb),
+        wgpu::TextureFormat::Rgb10a2Unorm => Some(TextureFormat::Rgb10a2Unorm),
+        wgpu::TextureFormat::Rgba16Float => Some(TextureFormat::Rgba16Float),
+        wgpu::TextureFormat::Depth32Float => Some(TextureFormat::Depth32Float),
+        wgpu::TextureFormat::Depth24PlusStencil8 => Some(TextureFormat::Depth24PlusStencil8),
+        _ => None,
+    }
+}
+
+fn texture_usage(usage: TextureUsage) -> wgpu::TextureUsages {
+    let mut usages = wgpu::TextureUsages::empty();
+    for (from, to) in [
+        (TextureUsage::SAMPLED, wgpu::TextureUsages::TEXTURE_BINDING),
+        (
+            TextureUsage::RENDER_TARGET,
+            wgpu::TextureUsages::RENDER_ATTACHMENT,
+        ),
+        (TextureUsage::COPY_SRC, wgpu::TextureUsages::COPY_SRC),
+        (TextureUsage::COPY_DST, wgpu::TextureUsages::COPY_DST),
+    ] {
+        usages.set(to, usage.contains(from));
+    }
+    usages
+}
+
+fn buffer_usage(usage: BufferUsage) -> wgpu::BufferUsages {
+    let mut usages = wgpu::BufferUsages::empty();
+    for (from, to) in [
+        (BufferUsage::VERTEX, wgpu::BufferUsages::VERTEX),
+        (BufferUsage::INDEX, wgpu::BufferUsages::INDEX),
+        (BufferUsage::UNIFORM, wgpu::BufferUsages::UNIFORM),
+        (BufferUsage::STORAGE, wgpu::BufferUsages::STORAGE),
+        (BufferUsage::COPY_SRC, wgpu::BufferUsages::COPY_SRC),
+        (BufferUsage::COPY_DST, wgpu::BufferUsages::COPY_DST),
+    ] {
+        usages.set(to, usage.contains(from));
+    }
+    usages
+}
+
+fn filter_mode(filter: FilterMode) -> wgpu::FilterMode {
+    match filter {
+        FilterMode::Nearest => wgpu::FilterMode::Nearest,
+        FilterMode::Linear => wgpu::FilterMode::Linear,
+    }
+}
+
+fn address_mode(address: AddressMode) -> wgpu::AddressMode {
+    match address {
+        AddressMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+        AddressMode::Repeat => wgpu::AddressMode::Repeat,
+        AddressMode::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+    }
+}
+
+fn vertex_format(format: VertexFormat) -> wgpu::VertexFormat {
+    match format {
+        VertexFormat::Float32 => wgpu::VertexFormat::Float32,
+        VertexFormat::Float32x2 => wgpu::VertexFormat::Float32x2,
+        VertexFormat::Float32x3 => wgpu::VertexFormat::Float32x3,
+        VertexFormat::Float32x4 => wgpu::VertexFormat::Float32x4,
+        VertexFormat::Uint32 => wgpu::VertexFormat::Uint32,
+        VertexFormat::Unorm8x4 => wgpu::VertexFormat::Unorm8x4,
+    }
+}
+
+fn blend_state(blend: BlendMode) -> Option<BlendState> {
+    match blend {
+        BlendMode::Opaque => None,
+        BlendMode::Alpha => Some(BlendState::ALPHA_BLENDING),
+        BlendMode::Premultiplied => Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+        BlendMode::Additive => Some(BlendState {
+            color: BlendComponent {
+                src_factor: BlendFactor::SrcAlpha,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+            alpha: BlendComponent {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+        }),
+        // src * dst, transparent parts keep the target
+        BlendMode::Multiply => Some(BlendState {
+            color: BlendComponent {
+                src_factor: BlendFactor::Dst,
+                dst_factor: BlendFactor::OneMinusSrcAlpha,
+                operation: BlendOperation::Add,
+            },
+            alpha: BlendComponent::OVER,
+        }),
+    }
+}
+
+fn topology(topology: PrimitiveTopology) -> wgpu::PrimitiveTopology {
+    match topology {
+        PrimitiveTopology::TriangleList => wgpu::PrimitiveTopology::TriangleList,
+        PrimitiveTopology::TriangleStrip => wgpu::PrimitiveTopology::TriangleStrip,
+        PrimitiveTopology::LineList => wgpu::PrimitiveTopology::LineList,
+        PrimitiveTopology::LineStrip => wgpu::PrimitiveTopology::LineStrip,
+        PrimitiveTopology::PointList => wgpu::PrimitiveTopology::PointList,
+    }
+}
+
+fn color(color: Color) -> wgpu::Color {
+    wgpu::Color {
+        r: color.r.into(),
+        g: color.g.into(),
+        b: color.b.into(),
+        a: color.a.into(),
+    }
+}
+
+/// `None` if the size is negative.
+fn extent(size: Size) -> Option<Extent3d> {
+    Some(Extent3d {
+        width: u32::try_from(size.width).ok()?,
+        height: u32::try_from(size.height).ok()?,
+        depth_or_array_layers: 1,
+    })
+}
+
+/// `None` if the point is negative.
+fn origin(point: Point) -> Option<Origin3d> {
+    Some(Origin3d {
+        x: u32::try_from(point.x).ok()?,
+        y: u32::try_from(point.y).ok()?,
+        z: 0,
+    })
+}
+
+#[derive(Debug)]
+struct GpuTexture {
+    texture: wgpu::Texture,
+    view: TextureView,
+    size: Size,
+    format: TextureFormat,
+}
+
+/// The pass being encoded.
+#[derive(Debug)]
+struct Pass {
+    pass: RenderPass<'static>,
+    size: Size,
+}
+
+fn in_pass<'pass>(
+    pass: &'pass mut Option<Pass>,
+    message: &'static str,
+) -> Result<&'pass mut Pass, RenderError> {
+    pass.as_mut().ok_or(RenderError::InvalidOrder(message))
+}
+
+/// A `RenderBackend` drawing with wgpu.
+///
+/// Pipelines take their bind group layouts from the shader, so a bind group only works with
+/// the pipeline it was created for. `RenderTarget::Surface` renders into the view given to
+/// `set_surface_frame`, e.g. the current texture of a window:
+///
+/// ```ignore
+/// let mut backend = WgpuBackend::new(context.device().clone(), context.queue().clone());
+/// let frame = surface.get_current_texture()?;
+/// backend.set_surface_frame(frame.texture.create_view(&Default::default()), size, format);
+/// backend.submit(&commands)?;
+/// backend.clear_surface_frame();
+/// frame.present();
+/// ```
+#[derive(Debug)]
+pub struct WgpuBackend {
+    device: Arc<Device>,
+    queue: Arc<Queue>,
+    textures: ResourcePool<TextureHandle, GpuTexture>,
+    buffers: ResourcePool<BufferHandle, wgpu::Buffer>,
+    samplers: ResourcePool<SamplerHandle, wgpu::Sampler>,
+    shaders: ResourcePool<ShaderHandle, wgpu::ShaderModule>,
+    pipelines: ResourcePool<PipelineHandle, wgpu::RenderPipeline>,
+    bind_groups: ResourcePool<BindGroupHandle, wgpu::BindGroup>,
+    surface: Option<(Size, TextureFormat)>,
+    surface_view: Option<TextureView>,
+}
+
+impl WgpuBackend {
+    pub fn new(device: Arc<Device>, queue: Arc<Queue>) -> Self {
+        Self {
+            device,
+            queue,
+            textures: Default::default(),
+            buffers: Default::default(),
+            samplers: Default::default(),
+            shaders: Default::default(),
+            pipelines: Default::default(),
+            bind_groups: Default::default(),
+            surface: None,
+            surface_view: None,
+        }
+    }
+
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    pub fn queue(&self) -> &Arc<Queue> {
+        &self.queue
+    }
+
+    /// Render `RenderTarget::Surface` into `view` until the next call.
+    pub fn set_surface_frame(&mut self, view: TextureView, size: Size, format: TextureFormat) {
+        self.surface = Some((size, format));
+        self.surface_view = Some(view);
+    }
+
+    /// Drop the view before presenting the frame, the size and format stay known.
+    pub fn clear_surface_frame(&mut self) {
+        self.surface_view = None;
+    }
+
+    /// The wgpu texture behind `texture`.
+    pub fn texture(&self, texture: TextureHandle) -> Result<&wgpu::Texture, RenderError> {
+        self.textures.get(texture).map(|texture| &texture.texture)
+    }
+
+    pub fn buffer(&self, buffer: BufferHandle) -> Result<&wgpu::Buffer, RenderError> {
+        self.buffers.get(buffer)
+    }
+
+    /// Run `work` and return the first validation error it caused instead of panicking.
+    fn scoped<T>(&self, work: impl FnOnce() -> T) -> Result<T, WgpuBackendError> {
+        let scope = self.device.push_error_scope(ErrorFilter::Validation);
+        let value = work();
+        match scope.pop().block_on() {
+            Some(err) => Err(err.into()),
+            None => Ok(value),
+        }
+    }
+
+    fn create_bind_group_with(
+        &self,
+        descriptor: &BindGroupDescriptor,
+    ) -> Result<wgpu::BindGroup, WgpuBackendError> {
+        let pipeline = self.pipelines.get(descriptor.pipeline)?;
+        let entries = descriptor
+            .entries
+            .iter()
+            .map(|entry| {
+                let resource = match entry.resource {
+                    BindResource::Buffer {
+                        buffer,
+                        offset,
+                        size,
+                    } => wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                        buffer: self.buffers.get(buffer)?,
+                        offset,
+                        size: size.and_then(wgpu::BufferSize::new),
+                    }),
+                    BindResource::Texture(texture) => {
+                        wgpu::BindingResource::TextureView(&self.textures.get(texture)?.view)
+                    }
+                    BindResource::Sampler(sampler) => {
+                        wgpu::BindingResource::Sampler(self.samplers.get(sampler)?)
+                    }
+                };
+                Ok(wgpu::BindGroupEntry {
+                    binding: entry.binding,
+                    resource,
+                })
+            })
+            .collect::<Result<Vec<_>, RenderError>>()?;
+
+        self.scoped(|| {
+            self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some(&descriptor.label),
+                layout: &pipeline.get_bind_group_layout(descriptor.group),
+                entries: &entries,
+            })
+        })
+    }
+
+    /// Submit what `encoder` recorded so far, queue writes are only ordered between submits.
+    fn flush(&self, encoder: &mut Option<CommandEncoder>) {
+        if let Some(encoder) = encoder.take() {
+            self.queue.submit([encoder.finish()]);
+        }
+    }
+
+    fn encode(&self, commands: &CommandBuffer) -> Result<(), WgpuBackendError> {
+        let mut encoder: Option<CommandEncoder> = None;
+        // declared after `encoder` so it is dropped first on errors
+        let mut pass: Option<Pass> = None;
+
+        for command in commands.commands() {
+            match *command {
+                RenderCommand::BeginPass {
+                    target,
+                    load,
+                    depth,
+                } => {
+                    if pass.is_some() {
+                        return Err(RenderError::InvalidOrder("begin_pass inside a pass").into());
+                    }
+                    let (view, size) = match target {
+                        RenderTarget::Surface => {
+                            let view = self.surface_view.as_ref().ok_or(RenderError::NoSurface)?;
+                            let (size, _) = self.surface.ok_or(RenderError::NoSurface)?;
+                            (view, size)
+                        }
+                        RenderTarget::Texture(texture) => {
+                            let texture = self.textures.get(texture)?;
+                            (&texture.view, texture.size)
+                        }
+                    };
+                    let depth_stencil_attachment = match depth {
+                        Some((texture, clear)) => Some(wgpu::RenderPassDepthStencilAttachment {
+                            view: &self.textures.get(texture)?.view,
+                            depth_ops: Some(wgpu::Operations {
+                                load: clear.map_or(wgpu::LoadOp::Load, wgpu::LoadOp::Clear),
+                                store: wgpu::StoreOp::Store,
+                            }),
+                            stencil_ops: None,
+                        }),
+                        None => None,
+                    };
+
+                    let encoder = encoder.get_or_insert_with(|| {
+                        self.device.create_command_encoder(&Default::default())
+                    });
+                    let render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                        label: None,
+                        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                            view,
+                            depth_slice: None,
+                            resolve_target: None,
+                            ops: wgpu::Operations {
+                                load: match load {
+                                    LoadOp::Load => wgpu::LoadOp::Load,
+                                    LoadOp::Clear(clear) => wgpu::LoadOp::Clear(color(clear)),
+                                },
+                                store: wgpu::StoreOp::Store,
+                            },
+                        })],
+                        depth_stencil_attachment,
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                        multiview_mask: None,
+                    });
+                    pass = Some(Pass {
+                        pass: render_pass.forget_lifetime(),
+                        size,
+                    });
+                }
+                RenderCommand::EndPass => {
+                    in_pass(&mut pass, "end_pass outside a pass")?;
+                    pass = None;
+                }
+                RenderCommand::SetViewport {
+                    rect,
+                    min_depth,
+                    max_depth,
+                } => {
+                    in_pass(&mut pass, "set_viewport outside a pass")?
+                        .pass
+                        .set_viewport(
+                            rect.position.x,
+                            rect.position.y,
+                            rect.size.width,
+                            rect.size.height,
+                            min_depth,
+                            max_depth,
+                        );
+                }
+                RenderCommand::SetScissor { rect } => {
+                    let current = in_pass(&mut pass, "set_scissor outside a pass")?;
+                    let (Some(position), Some(size)) = (origin(rect.position), extent(rect.size))
+                    else {
+                        return Err(RenderError::ScissorOutOfTarget {
+                            rect,
+                            target: current.size,
+                        }
+                        .into());
+                    };
+                    current
+                        .pass
+                        .set_scissor_rect(position.x, position.y, size.width, size.height);
+                }
+                RenderCommand::SetPipeline { pipeline } => {
+                    let pipeline = self.pipelines.get(pipeline)?;
+                    in_pass(&mut pass, "set_pipeline outside a pass")?
+                        .pass
+                        .set_pipeline(pipeline);
+                }
+                RenderCommand::SetBindGroup { slot, bind_group } => {
+                    let bind_group = self.bind_groups.get(bind_group)?;
+                    in_pass(&mut pass, "set_bind_group outside a pass")?
+                        .pass
+                        .set_bind_group(slot, bind_group, &[]);
+                }
+                RenderCommand::SetVertexBuffer {
+                    slot,
+                    buffer,
+                    offset,
+                } => {
+                    let buffer = self.buffers.get(buffer)?;
+                    in_pass(&mut pass, "set_vertex_buffer outside a pass")?
+                        .pass
+                        .set_vertex_buffer(slot, buffer.slice(offset..));
+                }
+                RenderCommand::SetIndexBuffer {
+                    buffer,
+                    offset,
+                    format,
+                } => {
+                    let buffer = self.buffers.get(buffer)?;
+                    let format = match format {
+                        IndexFormat::Uint16 => wgpu::IndexFormat::Uint16,
+                        IndexFormat::Uint32 => wgpu::IndexFormat::Uint32,
+                    };
+                    in_pass(&mut pass, "set_index_buffer outside a pass")?
+                        .pass
+                        .set_index_buffer(buffer.slice(offset..), format);
+                }
+                RenderCommand::Draw {
+                    first_vertex,
+                    vertex_count,
+                    first_instance,
+                    instance_count,
+                } => {
+                    in_pass(&mut pass, "draw outside a pass")?.pass.draw(
+                        first_vertex..first_vertex.saturating_add(vertex_count),
+                        first_instance..first_instance.saturating_add(instance_count),
+                    );
+                }
+                RenderCommand::DrawIndexed {
+                    first_index,
+                    index_count,
+                    base_vertex,
+                    first_instance,
+                    instance_count,
+                } => {
+                    in_pass(&mut pass, "draw_indexed outside a pass")?
+                        .pass
+                        .draw_indexed(
+                            first_index..first_index.saturating_add(index_count),
+                            base_vertex,
+                            first_instance..first_instance.saturating_add(instance_count),
+                        );
+                }
+                RenderCommand::WriteBuffer {
+                    buffer,
+                    offset,
+                    data,
+                } => {
+                    if pass.is_some() {
+                        return Err(RenderError::InvalidOrder("write_buffer inside a pass").into());
+                    }
+                    self.flush(&mut encoder);
+                    self.queue
+                        .write_buffer(self.buffers.get(buffer)?, offset, commands.data(data));
+                }
+                RenderCommand::WriteTexture {
+                    texture,
+                    origin: at,
+                    size,
+                    data,
+                } => {
+                    if pass.is_some() {
+                        return Err(RenderError::InvalidOrder("write_texture inside a pass").into());
+                    }
+                    let descriptor = self.textures.get(texture)?;
+                    let (Some(at), Some(size)) = (origin(at), extent(size)) else {
+                        return Err(RenderError::OutOfBounds {
+                            handle: texture.to_smolstr(),
+                            message: "write outside the texture",
+                        }
+                        .into());
+                    };
+                    let Some(bytes) = descriptor.format.bytes_per_pixel() else {
+                        return Err(RenderError::Unsupported {
+                            handle: texture.to_smolstr(),
+                            message: "depth textures can not be written",
+                        }
+                        .into());
+                    };
+                    self.flush(&mut encoder);
+                    self.queue.write_texture(
+                        TexelCopyTextureInfo {
+                            texture: &descriptor.texture,
+                            mip_level: 0,
+                            origin: at,
+                            aspect: wgpu::TextureAspect::All,
+                        },
+                        commands.data(data),
+                        TexelCopyBufferLayout {
+                            offset: 0,
+                            bytes_per_row: bytes.checked_mul(size.width),
+                            rows_per_image: None,
+                        },
+                        size,
+                    );
+                }
+                RenderCommand::CopyBufferToBuffer {
+                    source,
+                    source_offset,
+                    destination,
+                    destination_offset,
+                    size,
+                } => {
+                    if pass.is_some() {
+                        return Err(RenderError::InvalidOrder("copy inside a pass").into());
+                    }
+                    let source = self.buffers.get(source)?;
+                    let destination = self.buffers.get(destination)?;
+                    encoder
+                        .get_or_insert_with(|| {
+                            self.device.create_command_encoder(&Default::default())
+                        })
+                        .copy_buffer_to_buffer(
+                            source,
+                            source_offset,
+                            destination,
+                            destination_offset,
+                            size,
+                        );
+                }
+                RenderCommand::CopyTextureToTexture {
+                    source,
+                    source_origin,
+                    destination,
+                    destination_origin,
+                    size,
+                } => {
+                    if pass.is_some() {
+                        return Err(RenderError::InvalidOrder("copy inside a pass").into());
+                    }
+                    let out_of_bounds = |texture: TextureHandle| RenderError::OutOfBounds {
+                        handle: texture.to_smolstr(),
+                        message: "copy outside the texture",
+                    };
+                    let source_origin =
+                        origin(source_origin).ok_or_else(|| out_of_bounds(source))?;
+                    let destination_origin =
+                        origin(destination_origin).ok_or_else(|| out_of_bounds(destination))?;
+                    let size = extent(size).ok_or_else(|| out_of_bounds(source))?;
+                    let source = self.textures.get(source)?;
+                    let destination = self.textures.get(destination)?;
+                    encoder
+                        .get_or_insert_with(|| {
+                            self.device.create_command_encoder(&Default::default())
+                        })
+                        .copy_texture_to_texture(
+                            TexelCopyTextureInfo {
+                                texture: &source.texture,
+                                mip_level: 0,
+                                origin: source_origin,
+                                aspect: wgpu::TextureAspect::All,
+                            },
+                            TexelCopyTextureInfo {
+                                texture: &destination.texture,
+                                mip_level: 0,
+                                origin: destination_origin,
+                                aspect: wgpu::TextureAspect::All,
+                            },
+                            size,
+                        );
+                }
+            }
+        }
+
+        if pass.is_some() {
+            return Err(RenderError::InvalidOrder("pass not ended").into());
+        }
+        self.flush(&mut encoder);
+        Ok(())
+    }
+}
+
+impl Fallible for WgpuBackend {
+    type Error = WgpuBackendError;
+}
+
+impl RenderBackend for WgpuBackend {
+    fn create_texture(
+        &mut self,
+        descriptor: &TextureDescriptor,
+    ) -> Result<TextureHandle, Self::Error> {
+        let size = extent(descriptor.size)
+            .filter(|size| size.width > 0 && size.height > 0)
+            .ok_or(WgpuBackendError::InvalidSize {
+                width: descriptor.size.width,
+                height: descriptor.size.height,
+            })?;
+        let texture = self.scoped(|| {
+            self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(&descriptor.label),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: texture_format_to_wgpu(descriptor.format),
+                usage: texture_usage(descriptor.usage),
+                view_formats: &[],
+            })
+        })?;
+        let view = texture.create_view(&Default::default());
+
+        Ok(self.textures.insert(GpuTexture {
+            texture,
+            view,
+            size: descriptor.size,
+            format: descriptor.format,
+        }))
+    }
+
+    fn destroy_texture(&mut self, texture: TextureHandle) -> Result<(), Self::Error> {
+        self.textures.remove(texture)?;
+        Ok(())
+    }
+
+    fn create_buffer(
+        &mut self,
+        descriptor: &BufferDescriptor,
+    ) -> Result<BufferHandle, Self::Error> {
+        let buffer = self.scoped(|| {
+            self.device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(&descriptor.label),
+                size: descriptor.size,
+                usage: buffer_usage(descriptor.usage),
+                mapped_at_creation: false,
+            })
+        })?;
+        Ok(self.buffers.insert(buffer))
+    }
+
+    fn destroy_buffer(&mut self, buffer: BufferHandle) -> Result<(), Self::Error> {
+        self.buffers.remove(buffer)?;
+        Ok(())
+    }
+
+    fn create_sampler(
+        &mut self,
+        descriptor: &SamplerDescriptor,
+    ) -> Result<SamplerHandle, Self::Error> {
+        let filter = filter_mode(descriptor.filter);
+        let address = address_mode(descriptor.address);
+        let sampler = self.scoped(|| {
+            self.device.create_sampler(&wgpu::SamplerDescriptor {
+                address_mode_u: address,
+                address_mode_v: address,
+                address_mode_w: address,
+                mag_filter: filter,
+                min_filter: filter,
+                ..Default::default()
+            })
+        })?;
+        Ok(self.samplers.insert(sampler))
+    }
+
+    fn destroy_sampler(&mut self, sampler: SamplerHandle) -> Result<(), Self::Error> {
+        self.samplers.remove(sampler)?;
+        Ok(())
+    }
+
+    fn create_shader(
+        &mut self,
+        descriptor: &ShaderDescriptor,
+    ) -> Result<ShaderHandle, Self::Error> {
+        let shader = self.scoped(|| {
+            self.device
+                .create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some(&descriptor.label),
+                    source: wgpu::ShaderSource::Wgsl(descriptor.wgsl.as_str().into()),
+                })
+        })?;
+        Ok(self.shaders.insert(shader))
+    }
+
+    fn destroy_shader(&mut self, shader: ShaderHandle) -> Result<(), Self::Error> {
+        self.shaders.remove(shader)?;
+        Ok(())
+    }
+
+    fn create_pipeline(
+        &mut self,
+        descriptor: &PipelineDescriptor,
+    ) -> Result<PipelineHandle, Self::Error> {
+        let shader = self.shaders.get(descriptor.shader)?;
+        let attributes: Vec<Vec<wgpu::VertexAttribute>> = descriptor
+            .vertex_layouts
+            .iter()
+            .map(|layout| {
+                layout
+                    .attributes
+                    .iter()
+                    .map(|attribute| wgpu::VertexAttribute {
+                        format: vertex_format(attribute.format),
+                        offset: attribute.offset.into(),
+                        shader_location: attribute.location,
+                    })
+                    .collect()
+            })
+            .collect();
+        let buffers: Vec<wgpu::VertexBufferLayout<'_>> = descriptor
+            .vertex_layouts
+            .iter()
+            .zip(&attributes)
+            .map(|(layout, attributes)| wgpu::VertexBufferLayout {
+                array_stride: layout.stride.into(),
+                step_mode: if layout.per_instance {
+                    wgpu::VertexStepMode::Instance
+                } else {
+                    wgpu::VertexStepMode::Vertex
+                },
+                attributes,
+            })
+            .collect();
+
+        let pipeline = self.scoped(|| {
+            self.device
+                .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some(&descriptor.label),
+                    layout: None,
+                    vertex: wgpu::VertexState {
+                        module: shader,
+                        entry_point: Some(&descriptor.vertex_entry),
+                        compilation_options: Default::default(),
+                        buffers: &buffers,
+                    },
+                    primitive: wgpu::PrimitiveState {
+                        topology: topology(descriptor.topology),
+                        ..Default::default()
+                    },
+                    depth_stencil: descriptor
+                        .depth_format
+                        .map(|format| wgpu::DepthStencilState {
+                            format: texture_format_to_wgpu(format),
+                            depth_write_enabled: true,
+                            depth_compare: wgpu::CompareFunction::LessEqual,
+                            stencil: Default::default(),
+                            bias: Default::default(),
+                        }),
+                    multisample: Default::default(),
+                    fragment: Some(wgpu::FragmentState {
+                        module: shader,
+                        entry_point: Some(&descriptor.fragment_entry),
+                        compilation_options: Default::default(),
+                        targets: &[Some(wgpu::ColorTargetState {
+                            format: texture_format_to_wgpu(descriptor.color_format),
+                            blend: blend_state(descriptor.blend),
+                            write_mask: wgpu::ColorWrites::ALL,
+                        })],
+                    }),
+                    multiview_mask: None,
+                    cache: None,
+                })
+        })?;
+        Ok(self.pipelines.insert(pipeline))
+    }
+
+    fn destroy_pipeline(&mut self, pipeline: PipelineHandle) -> Result<(), Self::Error> {
+        self.pipelines.remove(pipeline)?;
+        Ok(())
+    }
+
+    fn create_bind_group(
+        &mut self,
+        descriptor: &BindGroupDescriptor,
+    ) -> Result<BindGroupHandle, Self::Error> {
+        let bind_group = self.create_bind_group_with(descriptor)?;
+        Ok(self.bind_groups.insert(bind_group))
+    }
+
+    fn destroy_bind_group(&mut self, bind_group: BindGroupHandle) -> Result<(), Self::Error> {
+        self.bind_groups.remove(bind_group)?;
+        Ok(())
+    }
+
+    fn surface_format(&self) -> Option<TextureFormat> {
+        self.surface.map(|(_, format)| format)
+    }
+
+    fn surface_size(&self) -> Option<Size> {
+        self.surface.map(|(size, _)| size)
+    }
+
+    /// Encode and submit `commands`, wgpu validation errors are returned instead of panicking.
+    fn submit(&mut self, commands: &CommandBuffer) -> Result<(), Self::Error> {
+        self.scoped(|| self.encode(commands))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shape::{ShapeStyle, Shapes};
+    use crate::shape_renderer::ShapeRenderer;
+    use crate::sprite::{Sprite, SpriteBatch};
+    use crate::sprite_renderer::SpriteRenderer;
+    use staccato_core::frect::FRect;
+    use std::error::Error;
+    use std::sync::mpsc;
+    use wgpu::{BufferUsages, MapMode, PollType, TexelCopyBufferInfo};
+
+    const SIZE: Size = Size {
+        width: 8,
+        height: 8,
+    };
+
+    /// A backend on the default adapter, the tests using it are ignored unless asked for.
+    fn backend() -> Result<WgpuBackend, Box<dyn Error>> {
+        let instance = wgpu::Instance::new(&Default::default());
+        let adapter = instance.request_adapter(&Default::default()).block_on()?;
+        let (device, queue) = adapter.request_device(&Default::default()).block_on()?;
+        Ok(WgpuBackend::new(Arc::new(device), Arc::new(queue)))
+    }
+
+    /// A `SIZE` texture presented to the backend as its surface frame.
+    fn surface(backend: &mut WgpuBackend) -> Result<TextureHandle, Box<dyn Error>> {
+        let texture = backend.create_texture(&TextureDescriptor {
+            label: "surface".into(),
+            size: SIZE,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsage::RENDER_TARGET | TextureUsage::COPY_SRC,
+        })?;
+        let view = backend.texture(texture)?.create_view(&Default::default());
+        backend.set_surface_frame(view, SIZE, TextureFormat::Rgba8Unorm);
+        Ok(texture)
+    }
+
+    /// The RGBA8 pixels of a `SIZE` texture, row by row.
+    fn read_pixels(
+        backend: &WgpuBackend,
+        texture: TextureHandle,
+    ) -> Result<Vec<u8>, Box<dyn Error>> {
+        let device = backend.device();
+        let texture = backend.texture(texture)?;
+        // rows of 8 pixels padded to the copy alignment
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: u64::from(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * 8,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&Default::default());
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: None,
+                },
+            },
+            texture.size(),
+        );
+        backend.queue().submit([encoder.finish()]);
+
+        let slice = readback.slice(..);
+        let (sender, receiver) = mpsc::channel();
+        slice.map_async(MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        device.poll(PollType::wait_indefinitely())?;
+        receiver.recv()??;
+        let pixels = slice
+            .get_mapped_range()
+            .chunks_exact(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as usize)
+            .flat_map(|row| row[..32].iter().copied())
+            .collect();
+        Ok(pixels)
+    }
+
+    #[test]
+    #[ignore = "needs a graphics adapter, run with --ignored"]
+    fn draws_sprites_offscreen() -> Result<(), Box<dyn Error>> {
+        let mut backend = backend()?;
+        let target = surface(&mut backend)?;
+
+        let mut renderer = SpriteRenderer::new(&mut backend, TextureFormat::Rgba8Unorm)?;
+        let white = backend.create_texture(&TextureDescriptor {
+            label: "white".into(),
+            size: Size::new(1, 1),
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsage::SAMPLED | TextureUsage::COPY_DST,
+        })?;
+        let mut batch = SpriteBatch::new();
+        batch.push(Sprite::new(white, FRect::new(0.0, 0.0, 4.0, 8.0)).with_tint(Color::RED));
+
+        let mut commands = CommandBuffer::new();
+        commands.write_texture(white, Point::new(0, 0), Size::new(1, 1), &[255; 4]);
+        renderer.prepare(&mut backend, &mut commands, &mut batch, SIZE)?;
+        commands.begin_pass(RenderTarget::Surface, LoadOp::Clear(Color::BLUE));
+        renderer.draw(&mut commands);
+        commands.end_pass();
+        backend.submit(&commands)?;
+
+        // the sprite covers the left half
+        let pixels = read_pixels(&backend, target)?;
+        let pixel = |x: usize, y: usize| pixels.get((y * 8 + x) * 4..(y * 8 + x) * 4 + 4);
+        assert_eq!(pixel(1, 6), Some(&[255, 0, 0, 255][..]));
+        assert_eq!(pixel(6, 1), Some(&[0, 0, 255, 255][..]));
+
+        renderer.forget_texture(&mut backend, white)?;
+        backend.destroy_texture(white)?;
+        renderer.destroy(&mut backend)?;
+        backend.clear_surface_frame();
+        backend.destroy_texture(target)?;
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "needs a graphics adapter, run with --ignored"]
+    fn draws_shapes_offscreen() -> Result<(), Box<dyn Error>> {
+        let mut backend = backend()?;
+        let target = surface(&mut backend)?;
+
+        let mut renderer = ShapeRenderer::new(&mut backend, TextureFormat::Rgba8Unorm)?;
+        let mut shapes = Shapes::new();
+        shapes.set_feather(0.0).draw_rect(
+            FRect::new(0.0, 0.0, 8.0, 4.0),
+            Color::GREEN,
+            ShapeStyle::Fill,
+        );
+
+        let mut commands = CommandBuffer::new();
+        renderer.prepare(&mut backend, &mut commands, &shapes, SIZE)?;
+        commands.begin_pass(RenderTarget::Surface, LoadOp::Clear(Color::BLACK));
+        renderer.draw(&mut commands);
+        commands.end_pass();
+        backend.submit(&commands)?;
+
+        // the rectangle covers the top half
+        let pixels = read_pixels(&backend, target)?;
+        let pixel = |x: usize, y: usize| pixels.get((y * 8 + x) * 4..(y * 8 + x) * 4 + 4);
+        assert_eq!(pixel(3, 1), Some(&[0, 255, 0, 255][..]));
+        assert_eq!(pixel(3, 6), Some(&[0, 0, 0, 255][..]));
+
+        renderer.destroy(&mut backend)?;
+        backend.clear_surface_frame();
+        backend.destroy_texture(target)?;
+        Ok(())
+    }
+
+    #[test]
+    #[ignore = "needs a graphics adapter, run with --ignored"]
+    fn returns_validation_errors() -> Result<(), Box<dyn Error>> {
+        let mut backend = backend()?;
+
+        let shader = backend.create_shader(&ShaderDescriptor {
+            label: "broken".into(),
+            wgsl: "fn main( {".into(),
+        });
+        assert!(matches!(shader, Err(WgpuBackendError::Wgpu(_))));
+
+        let mut commands = CommandBuffer::new();
+        commands.begin_pass(RenderTarget::Surface, LoadOp::Load);
+        assert!(matches!(
+            backend.submit(&commands),
+            Err(WgpuBackendError::Render(RenderError::NoSurface))
+        ));
+        Ok(())
+    }
+}
@@ -1,38 +1,37 @@
 use staccato_application::ApplicationInformation;
 use staccato_application::staccato_core::fallible::Fallible;
 use staccato_application::staccato_core::rect::Size;
-use staccato_application::staccato_core::tickable::Tickable;
-use staccato_application::staccato_core::time_service::StdTimeService;
+use staccato_application::staccato_core::tickable::{FrameTime, Tickable};
 use staccato_application::staccato_hal::sdl_event_source::SdlEventSource;
+use staccato_application::staccato_hal::sdl_time_service::SdlTimeService;
 use staccato_application::staccato_hal::wgpu_context::WgpuRenderContext;
-use staccato_application::staccato_hal::wgpu_window::WgpuWindow;
+use staccato_application::staccato_hal::wgpu_window::{WgpuWindow, WgpuWindowError};
+use staccato_application::staccato_hal::wgpu_windows::WgpuWindows;
 use staccato_application::staccato_hal::window::{Window, WindowOption};
 use staccato_application::staccato_shared::event::{AppEvent, Event, RawEvent};
 use staccato_application::staccato_shared::event_dispatcher::{
     EventDispatcher, EventHandler, EventSource, StdEventDispatcher,
 };
+use staccato_application::staccato_shared::frame_limiter::{FrameLimiter, FrameRate};
 use staccato_application::staccato_shared::ticker::{StdTicker, Ticker};
-use std::convert::Infallible;
 
 #[derive(Debug)]
 pub struct Main<'a> {
-    _window: WgpuWindow<'a>,
-    _context: WgpuRenderContext,
+    windows: WgpuWindows<'a>,
     running: bool,
 }
 
 impl<'w> From<(WgpuRenderContext, WgpuWindow<'w>)> for Main<'w> {
     fn from(value: (WgpuRenderContext, WgpuWindow<'w>)) -> Self {
         Self {
-            _window: value.1,
-            _context: value.0,
+            windows: value.into(),
             running: true,
         }
     }
 }
 
 impl Fallible for Main<'_> {
-    type Error = Infallible;
+    type Error = WgpuWindowError;
 }
 
 impl Tickable for Main<'_> {
@@ -44,17 +43,19 @@ impl Tickable for Main<'_> {
         Ok(())
     }
 
-    fn update(&mut self, _elapse_ns: u64) -> Result<(), Self::Error> {
+    fn update(&mut self, _time: &FrameTime) -> Result<(), Self::Error> {
         Ok(())
     }
 
-    fn post_update(&mut self, _elapse_ns: u64) -> Result<(), Self::Error> {
+    fn post_update(&mut self, _time: &FrameTime) -> Result<(), Self::Error> {
         Ok(())
     }
 }
 
 impl EventHandler for Main<'_> {
     fn handle(&mut self, event: &Event) -> Result<bool, Self::Error> {
+        self.windows.handle(event)?;
+
         if let RawEvent::Quit = event.raw {
             self.running = false;
         }
@@ -64,7 +65,7 @@ impl EventHandler for Main<'_> {
         {
             self.running = false;
         }
-        if let RawEvent::WindowClose { id: _ } = event.raw {
+        if self.windows.is_empty() {
             self.running = false
         }
 
@@ -89,16 +90,19 @@ fn main() -> Result<(), eyre::Report> {
 
     let event_dispatcher = StdEventDispatcher::default();
 
-    let time_service = StdTimeService::new();
+    let time_service = SdlTimeService::new();
 
     let mut ticker: StdTicker<<Main<'_> as Fallible>::Error> = StdTicker::new(&time_service, 50);
 
+    let mut frame_limiter = FrameLimiter::new(&time_service, FrameRate::Limited { fps: 120 });
+
     let window = Window::new(WindowOption {
         title: "hello world".into(),
         size: Size {
             width: 1024,
             height: 768,
         },
+        ..Default::default()
     })?;
 
     let mut main: Main<'_> = WgpuRenderContext::new_with_window(
@@ -106,6 +110,7 @@ fn main() -> Result<(), eyre::Report> {
         &Default::default(),
         &Default::default(),
         &Default::default(),
+        &Default::default(),
     )?
     .into();
 
@@ -113,10 +118,13 @@ fn main() -> Result<(), eyre::Report> {
         let events = event_source.poll();
 
         for event in events {
+            frame_limiter.observe(event);
             event_dispatcher.fire(&mut [&mut main], event)?;
         }
 
         ticker.drive(&time_service, &mut main)?;
+
+        frame_limiter.wait(&time_service);
     }
 
     Ok(())
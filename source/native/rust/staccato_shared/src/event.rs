@@ -1,11 +1,18 @@
-use crate::id::{KeyboardId, MouseId, WindowId};
+use crate::id::{DisplayId, FingerId, JoystickId, KeyboardId, MouseId, PenId, TouchId, WindowId};
+use bitflags::bitflags;
+use smol_str::SmolStr;
 use staccato_core::frect::FPoint;
+use staccato_core::gamepad::{GamepadAxis, GamepadButton, JoystickHat, SensorType};
 use staccato_core::keycode::KeyCode;
 use staccato_core::keymod::Keymod;
 use staccato_core::mouse::{Button, MouseWheelDirection};
+use staccato_core::pen::{PenAxis, PenInputFlags};
 use staccato_core::rect::Point;
 use staccato_core::scancode::Scancode;
+use std::any::{Any, TypeId};
 use std::fmt::Debug;
+use std::mem::MaybeUninit;
+use std::sync::Arc;
 
 pub const INLINE_TEXT_MAX: usize = 64;
 
@@ -15,6 +22,21 @@ pub struct InlineText {
     pub data: [u8; INLINE_TEXT_MAX],
 }
 
+/// Length of the longest prefix of `bytes` that fits in `max` bytes without splitting a
+/// UTF-8 sequence. Falls back to `max` if `bytes` is not UTF-8.
+fn utf8_prefix_len(bytes: &[u8], max: usize) -> usize {
+    if bytes.len() <= max {
+        return bytes.len();
+    }
+
+    let mut len = max;
+    // step back over continuation bytes to the start of the split character
+    while len > 0 && bytes[len] & 0xc0 == 0x80 {
+        len -= 1;
+    }
+    if len == 0 { max } else { len }
+}
+
 impl InlineText {
     pub fn empty() -> Self {
         Self {
@@ -23,17 +45,61 @@ impl InlineText {
         }
     }
 
+    /// Truncate to `INLINE_TEXT_MAX` bytes without splitting a character, see `chunks`
+    /// to keep the rest.
     pub fn from_bytes(bytes: &[u8]) -> Self {
         let mut text = Self::empty();
-        let len = bytes.len().min(INLINE_TEXT_MAX);
+        let len = utf8_prefix_len(bytes, INLINE_TEXT_MAX);
         text.data[..len].copy_from_slice(&bytes[..len]);
         text.len = len as u8;
         text
     }
 
+    /// Split `bytes` into texts of at most `INLINE_TEXT_MAX` bytes at character boundaries.
+    pub fn chunks(bytes: &[u8]) -> InlineTextChunks<'_> {
+        InlineTextChunks { rest: bytes }
+    }
+
     pub fn as_bytes(&self) -> &[u8] {
         &self.data[..self.len as usize]
     }
+
+    /// `None` if the text is not valid UTF-8.
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(self.as_bytes()).ok()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl From<&str> for InlineText {
+    fn from(text: &str) -> Self {
+        Self::from_bytes(text.as_bytes())
+    }
+}
+
+/// See `InlineText::chunks`.
+#[derive(Debug, Clone)]
+pub struct InlineTextChunks<'a> {
+    rest: &'a [u8],
+}
+
+impl Iterator for InlineTextChunks<'_> {
+    type Item = InlineText;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.rest.is_empty() {
+            return None;
+        }
+
+        let (chunk, rest) = self
+            .rest
+            .split_at(utf8_prefix_len(self.rest, INLINE_TEXT_MAX));
+        self.rest = rest;
+        Some(InlineText::from_bytes(chunk))
+    }
 }
 
 impl Default for InlineText {
@@ -42,6 +108,90 @@ impl Default for InlineText {
     }
 }
 
+pub const INLINE_PAYLOAD_MAX: usize = 32;
+
+#[repr(C, align(8))]
+#[derive(Clone, Copy)]
+struct InlinePayload([MaybeUninit<u8>; INLINE_PAYLOAD_MAX]);
+
+#[derive(Clone)]
+enum PayloadStorage {
+    /// a `Copy` value that fits in `INLINE_PAYLOAD_MAX` bytes
+    Inline(InlinePayload),
+    Shared(Arc<dyn Any + Send + Sync>),
+}
+
+/// Type erased payload of a game-defined event.
+///
+/// Small `Copy` payloads are stored inline without allocation.
+#[derive(Clone)]
+pub struct CustomPayload {
+    type_id: TypeId,
+    type_name: &'static str,
+    storage: PayloadStorage,
+}
+
+impl CustomPayload {
+    /// Store `value` inline if it fits, otherwise fall back to `shared`.
+    pub fn new<T: Copy + Send + Sync + 'static>(value: T) -> Self {
+        if size_of::<T>() > INLINE_PAYLOAD_MAX || align_of::<T>() > align_of::<InlinePayload>() {
+            return Self::shared(value);
+        }
+
+        let mut inline = InlinePayload([MaybeUninit::uninit(); INLINE_PAYLOAD_MAX]);
+        // SAFETY: size and alignment are checked above, `T: Copy` so no drop is needed
+        unsafe { inline.0.as_mut_ptr().cast::<T>().write(value) };
+
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            storage: PayloadStorage::Inline(inline),
+        }
+    }
+
+    /// Store `value` behind an `Arc`, works for any type.
+    pub fn shared<T: Send + Sync + 'static>(value: T) -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            storage: PayloadStorage::Shared(Arc::new(value)),
+        }
+    }
+
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+
+    pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
+        if !self.is::<T>() {
+            return None;
+        }
+
+        match &self.storage {
+            // SAFETY: the type id matches the one written in `new`
+            PayloadStorage::Inline(inline) => Some(unsafe { &*inline.0.as_ptr().cast::<T>() }),
+            PayloadStorage::Shared(shared) => shared.downcast_ref::<T>(),
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    pub fn is_inline(&self) -> bool {
+        matches!(self.storage, PayloadStorage::Inline(_))
+    }
+}
+
+impl Debug for CustomPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CustomPayload")
+            .field("type_name", &self.type_name)
+            .field("inline", &self.is_inline())
+            .finish()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum DeviceOperation {
     /// device(e.g. mouse or keyboard) was added
@@ -50,6 +200,32 @@ pub enum DeviceOperation {
     Removed,
 }
 
+#[derive(Clone, Debug)]
+pub enum GamepadDeviceOperation {
+    /// a joystick with a gamepad mapping was opened
+    Added,
+    /// the gamepad was removed
+    Removed,
+    /// the mapping of the gamepad was changed
+    Remapped,
+}
+
+#[derive(Clone, Debug)]
+pub enum TouchOperation {
+    Down,
+    Up,
+    Motion,
+    /// the touch was interrupted by the system, treat it like `Up` without activating anything
+    Canceled,
+}
+
+#[derive(Clone, Debug)]
+pub enum GesturePhase {
+    Begin,
+    Update,
+    End,
+}
+
 #[derive(Clone, Debug)]
 pub enum UserOperation {
     /// Key up
@@ -88,7 +264,7 @@ pub enum WindowEvent {
     FocusLost,
     HitTest,
     IccProfileChanged,
-    DisplayChanged { display_id: u32 },
+    DisplayChanged { display_id: DisplayId },
     DisplayScaleChanged,
     SafeAreaChanged,
     Occluded,
@@ -98,6 +274,28 @@ pub enum WindowEvent {
     HdrStateChanged,
 }
 
+/// Values match `SDL_DisplayOrientation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DisplayOrientation {
+    #[default]
+    Unknown,
+    Landscape,
+    LandscapeFlipped,
+    Portrait,
+    PortraitFlipped,
+}
+
+#[derive(Clone, Debug)]
+pub enum DisplayEvent {
+    Added,
+    Removed,
+    Moved,
+    OrientationChanged { orientation: DisplayOrientation },
+    DesktopModeChanged,
+    CurrentModeChanged,
+    ContentScaleChanged,
+}
+
 #[derive(Clone, Debug)]
 pub enum RawEvent {
     Quit,
@@ -123,16 +321,31 @@ pub enum RawEvent {
         is_repeat: bool,
         user_operation: UserOperation,
     },
+    /// the IME composition changed, an empty text ends the composition
     TextEditing {
         window_id: WindowId,
-        text: InlineText,
+        /// the whole composition, never truncated
+        text: SmolStr,
+        /// cursor position in characters, -1 if not set
         start: i32,
+        /// length of the selected characters after `start`, -1 if not set
         length: i32,
     },
+    /// committed text, longer texts spill into several consecutive events
     TextInput {
         window_id: WindowId,
         text: InlineText,
     },
+    /// the IME candidate list changed, only sent if the game draws the candidates itself
+    TextEditingCandidates {
+        window_id: WindowId,
+        /// empty if the candidate list should be hidden
+        candidates: Arc<[SmolStr]>,
+        /// -1 if no candidate is selected
+        selected: i32,
+        /// whether the list should be laid out horizontally
+        horizontal: bool,
+    },
     KeyboardDevice {
         keyboard_id: KeyboardId,
         operation: DeviceOperation,
@@ -165,20 +378,288 @@ pub enum RawEvent {
         position: FPoint,
         accumulated_scroll: Point,
     },
+    JoystickDevice {
+        joystick_id: JoystickId,
+        operation: DeviceOperation,
+    },
+    JoystickAxisMotion {
+        joystick_id: JoystickId,
+        axis: u8,
+        value: i16,
+    },
+    JoystickHatMotion {
+        joystick_id: JoystickId,
+        hat: u8,
+        state: JoystickHat,
+    },
+    JoystickButton {
+        joystick_id: JoystickId,
+        button: u8,
+        down: bool,
+        user_operation: UserOperation,
+    },
+    GamepadDevice {
+        joystick_id: JoystickId,
+        operation: GamepadDeviceOperation,
+    },
+    GamepadAxisMotion {
+        joystick_id: JoystickId,
+        axis: GamepadAxis,
+        value: i16,
+    },
+    GamepadButton {
+        joystick_id: JoystickId,
+        button: GamepadButton,
+        down: bool,
+        user_operation: UserOperation,
+    },
+    GamepadTouchpad {
+        joystick_id: JoystickId,
+        touchpad: i32,
+        finger: i32,
+        /// normalized, `(0, 0)` is the top left
+        position: FPoint,
+        pressure: f32,
+        operation: TouchOperation,
+    },
+    GamepadSensor {
+        joystick_id: JoystickId,
+        sensor: SensorType,
+        data: [f32; 3],
+        /// timestamp reported by the sensor, 0 if not available
+        sensor_ns_timestamp: u64,
+    },
+    Finger {
+        window_id: WindowId,
+        touch_id: TouchId,
+        finger_id: FingerId,
+        /// normalized, `(0, 0)` is the top left
+        position: FPoint,
+        /// normalized
+        relative: FPoint,
+        pressure: f32,
+        operation: TouchOperation,
+    },
+    Pinch {
+        window_id: WindowId,
+        /// scale relative to the start of the gesture
+        scale: f32,
+        phase: GesturePhase,
+    },
+    PenProximity {
+        window_id: WindowId,
+        pen_id: PenId,
+        /// true if the pen came into proximity, false if it left
+        entered: bool,
+    },
+    PenTouch {
+        window_id: WindowId,
+        pen_id: PenId,
+        state: PenInputFlags,
+        position: FPoint,
+        eraser: bool,
+        down: bool,
+        user_operation: UserOperation,
+    },
+    PenMotion {
+        window_id: WindowId,
+        pen_id: PenId,
+        state: PenInputFlags,
+        position: FPoint,
+    },
+    PenButton {
+        window_id: WindowId,
+        pen_id: PenId,
+        state: PenInputFlags,
+        position: FPoint,
+        button: u8,
+        down: bool,
+        user_operation: UserOperation,
+    },
+    /// pressure, tilt and the other axes, see `PenAxis`
+    PenAxis {
+        window_id: WindowId,
+        pen_id: PenId,
+        state: PenInputFlags,
+        position: FPoint,
+        axis: PenAxis,
+        value: f32,
+    },
+    /// the clipboard or the primary selection changed
+    ClipboardUpdate {
+        /// true if this application set the new content
+        owner: bool,
+        /// MIME types offered by the new content
+        mime_types: Arc<[SmolStr]>,
+    },
+    /// a monitor was connected, disconnected or changed
+    Display {
+        display_id: DisplayId,
+        event: DisplayEvent,
+    },
     Unknown {
         type_id: u64,
     },
-    //Custom(Arc<dyn CustomEvent>)
+    /// game-defined event, see `CustomPayload`
+    Custom {
+        window_id: WindowId,
+        payload: CustomPayload,
+    },
 }
 
-/*
-pub trait CustomEvent : std::any::Any + Debug{
-    fn event_name(&self) -> smol_str::SmolStr;
+bitflags! {
+    /// One bit per `RawEvent` variant, used to filter events.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct EventKind: u64 {
+        const QUIT = 1 << 0;
+        const APP = 1 << 1;
+        const WINDOW = 1 << 2;
+        const WINDOW_CLOSE = 1 << 3;
+        const KEYMAP_CHANGED = 1 << 4;
+        const KEYBOARD = 1 << 5;
+        const TEXT_EDITING = 1 << 6;
+        const TEXT_INPUT = 1 << 7;
+        const KEYBOARD_DEVICE = 1 << 8;
+        const MOUSE_DEVICE = 1 << 9;
+        const MOUSE_MOTION = 1 << 10;
+        const MOUSE_BUTTON = 1 << 11;
+        const MOUSE_WHEEL = 1 << 12;
+        const UNKNOWN = 1 << 13;
+        const CUSTOM = 1 << 14;
+        const JOYSTICK_DEVICE = 1 << 15;
+        const JOYSTICK_AXIS_MOTION = 1 << 16;
+        const JOYSTICK_HAT_MOTION = 1 << 17;
+        const JOYSTICK_BUTTON = 1 << 18;
+        const GAMEPAD_DEVICE = 1 << 19;
+        const GAMEPAD_AXIS_MOTION = 1 << 20;
+        const GAMEPAD_BUTTON = 1 << 21;
+        const GAMEPAD_TOUCHPAD = 1 << 22;
+        const GAMEPAD_SENSOR = 1 << 23;
+        const FINGER = 1 << 24;
+        const PINCH = 1 << 25;
+        const PEN_PROXIMITY = 1 << 26;
+        const PEN_TOUCH = 1 << 27;
+        const PEN_MOTION = 1 << 28;
+        const PEN_BUTTON = 1 << 29;
+        const PEN_AXIS = 1 << 30;
+        const TEXT_EDITING_CANDIDATES = 1 << 31;
+        const CLIPBOARD_UPDATE = 1 << 32;
+        const DISPLAY = 1 << 33;
+
+        const KEYBOARD_INPUT = Self::KEYBOARD.bits() | Self::TEXT_EDITING.bits() | Self::TEXT_INPUT.bits() | Self::TEXT_EDITING_CANDIDATES.bits();
+        const MOUSE_INPUT = Self::MOUSE_MOTION.bits() | Self::MOUSE_BUTTON.bits() | Self::MOUSE_WHEEL.bits();
+        const JOYSTICK_INPUT = Self::JOYSTICK_AXIS_MOTION.bits() | Self::JOYSTICK_HAT_MOTION.bits() | Self::JOYSTICK_BUTTON.bits();
+        const TOUCH_INPUT = Self::FINGER.bits() | Self::PINCH.bits();
+        const PEN_INPUT = Self::PEN_PROXIMITY.bits() | Self::PEN_TOUCH.bits() | Self::PEN_MOTION.bits() | Self::PEN_BUTTON.bits() | Self::PEN_AXIS.bits();
+        const GAMEPAD_INPUT = Self::GAMEPAD_AXIS_MOTION.bits() | Self::GAMEPAD_BUTTON.bits() | Self::GAMEPAD_TOUCHPAD.bits() | Self::GAMEPAD_SENSOR.bits();
+    }
+}
+
+impl RawEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            RawEvent::Quit => EventKind::QUIT,
+            RawEvent::App { .. } => EventKind::APP,
+            RawEvent::Window { .. } => EventKind::WINDOW,
+            RawEvent::WindowClose { .. } => EventKind::WINDOW_CLOSE,
+            RawEvent::KeymapChanged => EventKind::KEYMAP_CHANGED,
+            RawEvent::Keyboard { .. } => EventKind::KEYBOARD,
+            RawEvent::TextEditing { .. } => EventKind::TEXT_EDITING,
+            RawEvent::TextInput { .. } => EventKind::TEXT_INPUT,
+            RawEvent::TextEditingCandidates { .. } => EventKind::TEXT_EDITING_CANDIDATES,
+            RawEvent::KeyboardDevice { .. } => EventKind::KEYBOARD_DEVICE,
+            RawEvent::MouseDevice { .. } => EventKind::MOUSE_DEVICE,
+            RawEvent::MouseMotion { .. } => EventKind::MOUSE_MOTION,
+            RawEvent::MouseButton { .. } => EventKind::MOUSE_BUTTON,
+            RawEvent::MouseWheel { .. } => EventKind::MOUSE_WHEEL,
+            RawEvent::JoystickDevice { .. } => EventKind::JOYSTICK_DEVICE,
+            RawEvent::JoystickAxisMotion { .. } => EventKind::JOYSTICK_AXIS_MOTION,
+            RawEvent::JoystickHatMotion { .. } => EventKind::JOYSTICK_HAT_MOTION,
+            RawEvent::JoystickButton { .. } => EventKind::JOYSTICK_BUTTON,
+            RawEvent::GamepadDevice { .. } => EventKind::GAMEPAD_DEVICE,
+            RawEvent::GamepadAxisMotion { .. } => EventKind::GAMEPAD_AXIS_MOTION,
+            RawEvent::GamepadButton { .. } => EventKind::GAMEPAD_BUTTON,
+            RawEvent::GamepadTouchpad { .. } => EventKind::GAMEPAD_TOUCHPAD,
+            RawEvent::GamepadSensor { .. } => EventKind::GAMEPAD_SENSOR,
+            RawEvent::Finger { .. } => EventKind::FINGER,
+            RawEvent::Pinch { .. } => EventKind::PINCH,
+            RawEvent::PenProximity { .. } => EventKind::PEN_PROXIMITY,
+            RawEvent::PenTouch { .. } => EventKind::PEN_TOUCH,
+            RawEvent::PenMotion { .. } => EventKind::PEN_MOTION,
+            RawEvent::PenButton { .. } => EventKind::PEN_BUTTON,
+            RawEvent::PenAxis { .. } => EventKind::PEN_AXIS,
+            RawEvent::ClipboardUpdate { .. } => EventKind::CLIPBOARD_UPDATE,
+            RawEvent::Display { .. } => EventKind::DISPLAY,
+            RawEvent::Unknown { .. } => EventKind::UNKNOWN,
+            RawEvent::Custom { .. } => EventKind::CUSTOM,
+        }
+    }
 }
-*/
 
 #[derive(Clone, Debug)]
 pub struct Event {
     pub ns_timestamp: u64,
     pub raw: RawEvent,
 }
+
+/// Events that can be filtered by `EventKind`.
+pub trait HasEventKind {
+    fn kind(&self) -> EventKind;
+}
+
+impl HasEventKind for Event {
+    fn kind(&self) -> EventKind {
+        self.raw.kind()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Damage {
+        amount: u32,
+        target: u64,
+    }
+
+    #[test]
+    fn small_payload_is_inline_and_typed() {
+        let payload = CustomPayload::new(Damage {
+            amount: 3,
+            target: 7,
+        });
+
+        assert!(payload.is_inline());
+        assert_eq!(
+            payload.downcast_ref::<Damage>(),
+            Some(&Damage {
+                amount: 3,
+                target: 7
+            })
+        );
+        assert_eq!(payload.downcast_ref::<u32>(), None);
+    }
+
+    #[test]
+    fn inline_text_keeps_characters_whole() {
+        // 3 bytes per character, 64 is not a multiple of 3
+        let text = "漢".repeat(30);
+
+        let truncated = InlineText::from(text.as_str());
+        assert_eq!(truncated.as_str(), Some("漢".repeat(21).as_str()));
+
+        let chunks: Vec<_> = InlineText::chunks(text.as_bytes()).collect();
+        assert_eq!(chunks.len(), 2);
+        let joined: String = chunks.iter().filter_map(InlineText::as_str).collect();
+        assert_eq!(joined, text);
+    }
+
+    #[test]
+    fn large_payload_is_shared() {
+        let payload = CustomPayload::new([0u64; 16]);
+
+        assert!(!payload.is_inline());
+        assert_eq!(payload.clone().downcast_ref::<[u64; 16]>(), Some(&[0; 16]));
+    }
+}
@@ -1,7 +1,10 @@
-use crate::event::Event;
+use crate::event::{Event, EventKind, HasEventKind};
 use staccato_core::fallible::Fallible;
+use std::cell::RefCell;
+use std::convert::Infallible;
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
 pub trait EventSource<E = Event>: Debug {
     fn poll(&mut self) -> &[E];
@@ -61,3 +64,284 @@ impl<Event, Err: std::error::Error + Sync + Send + 'static> EventDispatcher<Even
         Ok(false)
     }
 }
+
+/// Identifies a handler registered in a `HandlerChain`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandlerHandle(u64);
+
+#[derive(Debug)]
+struct Registration<E, Err> {
+    handle: HandlerHandle,
+    priority: i32,
+    filter: EventKind,
+    active: bool,
+    handler: Box<dyn EventHandler<E, Error = Err>>,
+}
+
+#[derive(Debug)]
+struct PendingChanges<E, Err> {
+    next_handle: u64,
+    added: Vec<Registration<E, Err>>,
+    removed: Vec<HandlerHandle>,
+}
+
+/// Queues registration changes for a `HandlerChain`.
+///
+/// Cheap to clone, hand it to handlers that need to add or remove handlers while an event
+/// is being dispatched. Removals take effect immediately, additions from the next event.
+#[derive(Debug)]
+pub struct HandlerRegistry<E, Err> {
+    pending: Rc<RefCell<PendingChanges<E, Err>>>,
+}
+
+impl<E, Err> Clone for HandlerRegistry<E, Err> {
+    fn clone(&self) -> Self {
+        Self {
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+impl<E, Err> HandlerRegistry<E, Err> {
+    fn new() -> Self {
+        Self {
+            pending: Rc::new(RefCell::new(PendingChanges {
+                next_handle: 0,
+                added: Vec::new(),
+                removed: Vec::new(),
+            })),
+        }
+    }
+
+    /// Higher priorities are called first, equal priorities in registration order.
+    /// The handler only receives events whose kind intersects `filter`.
+    pub fn add(
+        &self,
+        priority: i32,
+        filter: EventKind,
+        handler: Box<dyn EventHandler<E, Error = Err>>,
+    ) -> HandlerHandle {
+        let mut pending = self.pending.borrow_mut();
+        let handle = HandlerHandle(pending.next_handle);
+        pending.next_handle += 1;
+        pending.added.push(Registration {
+            handle,
+            priority,
+            filter,
+            active: true,
+            handler,
+        });
+        handle
+    }
+
+    pub fn remove(&self, handle: HandlerHandle) {
+        self.pending.borrow_mut().removed.push(handle);
+    }
+}
+
+/// An `EventDispatcher` that owns its handlers.
+#[derive(Debug)]
+pub struct HandlerChain<E = Event, Err: std::error::Error + Sync + Send + 'static = Infallible> {
+    registrations: Vec<Registration<E, Err>>,
+    registry: HandlerRegistry<E, Err>,
+}
+
+impl<E, Err: std::error::Error + Sync + Send + 'static> Default for HandlerChain<E, Err> {
+    fn default() -> Self {
+        Self {
+            registrations: Vec::new(),
+            registry: HandlerRegistry::new(),
+        }
+    }
+}
+
+impl<E, Err: std::error::Error + Sync + Send + 'static> Fallible for HandlerChain<E, Err> {
+    type Error = Err;
+}
+
+impl<E, Err: std::error::Error + Sync + Send + 'static> HandlerChain<E, Err> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn registry(&self) -> HandlerRegistry<E, Err> {
+        self.registry.clone()
+    }
+
+    /// See `HandlerRegistry::add`, takes effect immediately.
+    pub fn add(
+        &mut self,
+        priority: i32,
+        filter: EventKind,
+        handler: Box<dyn EventHandler<E, Error = Err>>,
+    ) -> HandlerHandle {
+        let handle = self.registry.add(priority, filter, handler);
+        self.apply_pending();
+        handle
+    }
+
+    pub fn remove(&mut self, handle: HandlerHandle) {
+        self.registry.remove(handle);
+        self.apply_pending();
+    }
+
+    pub fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+
+    fn deactivate_removed(&mut self) {
+        let pending = self.registry.pending.borrow();
+        if pending.removed.is_empty() {
+            return;
+        }
+
+        for registration in &mut self.registrations {
+            if pending.removed.contains(&registration.handle) {
+                registration.active = false;
+            }
+        }
+    }
+
+    fn apply_pending(&mut self) {
+        let mut pending = self.registry.pending.borrow_mut();
+        let pending = &mut *pending;
+
+        if !pending.removed.is_empty() {
+            let removed = &pending.removed;
+            self.registrations
+                .retain(|registration| !removed.contains(&registration.handle));
+            pending
+                .added
+                .retain(|registration| !removed.contains(&registration.handle));
+            pending.removed.clear();
+        }
+
+        for registration in pending.added.drain(..) {
+            let index = self
+                .registrations
+                .partition_point(|exist| exist.priority >= registration.priority);
+            self.registrations.insert(index, registration);
+        }
+    }
+}
+
+impl<E: HasEventKind, Err: std::error::Error + Sync + Send + 'static> HandlerChain<E, Err> {
+    /// Call handlers by priority until one returns `true`. Return is_handled.
+    pub fn fire(&mut self, event: &E) -> Result<bool, Err> {
+        let kind = event.kind();
+        let mut handled = false;
+
+        for index in 0..self.registrations.len() {
+            let registration = &mut self.registrations[index];
+            if !registration.active || !registration.filter.intersects(kind) {
+                continue;
+            }
+
+            let result = registration.handler.handle(event);
+            self.deactivate_removed();
+
+            match result {
+                Ok(true) => {
+                    handled = true;
+                    break;
+                }
+                Ok(false) => {}
+                Err(err) => {
+                    self.apply_pending();
+                    return Err(err);
+                }
+            }
+        }
+
+        self.apply_pending();
+        Ok(handled)
+    }
+}
+
+impl<E: HasEventKind + Debug, Err: std::error::Error + Sync + Send + 'static> EventHandler<E>
+    for HandlerChain<E, Err>
+{
+    fn handle(&mut self, event: &E) -> Result<bool, Self::Error> {
+        self.fire(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::RawEvent;
+
+    #[derive(Debug)]
+    struct Recorder {
+        name: char,
+        consume: bool,
+        log: Rc<RefCell<String>>,
+        remove_on_call: Option<(HandlerRegistry<Event, Infallible>, HandlerHandle)>,
+    }
+
+    impl Fallible for Recorder {
+        type Error = Infallible;
+    }
+
+    impl EventHandler for Recorder {
+        fn handle(&mut self, _event: &Event) -> Result<bool, Self::Error> {
+            self.log.borrow_mut().push(self.name);
+            if let Some((registry, handle)) = self.remove_on_call.take() {
+                registry.remove(handle);
+            }
+            Ok(self.consume)
+        }
+    }
+
+    fn recorder(name: char, consume: bool, log: &Rc<RefCell<String>>) -> Box<Recorder> {
+        Box::new(Recorder {
+            name,
+            consume,
+            log: log.clone(),
+            remove_on_call: None,
+        })
+    }
+
+    fn quit() -> Event {
+        Event {
+            ns_timestamp: 0,
+            raw: RawEvent::Quit,
+        }
+    }
+
+    #[test]
+    fn calls_by_priority_and_stops_when_handled() -> Result<(), Infallible> {
+        let log = Rc::new(RefCell::new(String::new()));
+        let mut chain = HandlerChain::<Event, Infallible>::new();
+
+        chain.add(0, EventKind::all(), recorder('c', true, &log));
+        chain.add(10, EventKind::all(), recorder('a', false, &log));
+        chain.add(0, EventKind::all(), recorder('d', false, &log));
+        chain.add(5, EventKind::KEYBOARD, recorder('b', true, &log));
+
+        assert!(chain.fire(&quit())?);
+        assert_eq!(log.borrow().as_str(), "ac");
+        Ok(())
+    }
+
+    #[test]
+    fn removal_during_dispatch_takes_effect_immediately() -> Result<(), Infallible> {
+        let log = Rc::new(RefCell::new(String::new()));
+        let mut chain = HandlerChain::<Event, Infallible>::new();
+
+        let victim = chain.add(0, EventKind::all(), recorder('b', false, &log));
+        let mut first = recorder('a', false, &log);
+        first.remove_on_call = Some((chain.registry(), victim));
+        chain.add(1, EventKind::all(), first);
+
+        assert!(!chain.fire(&quit())?);
+        assert!(!chain.fire(&quit())?);
+        assert_eq!(log.borrow().as_str(), "aa");
+        assert_eq!(chain.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,224 @@
+use crate::event::{AppEvent, Event, RawEvent};
+use staccato_core::time_service::TimeService;
+use std::time::{Duration, Instant};
+use tracing::trace;
+
+/// Default frame rate used while the application is in background.
+pub const DEFAULT_BACKGROUND_FPS: u32 = 10;
+
+/// Default time before the deadline where sleeping stops and spinning starts (2ms).
+pub const DEFAULT_SPIN_THRESHOLD_NS: u64 = 2_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameRate {
+    /// Do not wait at all, e.g. when vsync already paces the loop.
+    Unlimited,
+    /// Wait until `1 / fps` seconds passed since the last frame. `fps == 0` means unlimited.
+    Limited { fps: u32 },
+}
+
+impl FrameRate {
+    /// `None` if unlimited.
+    pub fn frame_time_ns(&self) -> Option<u64> {
+        match *self {
+            FrameRate::Unlimited | FrameRate::Limited { fps: 0 } => None,
+            FrameRate::Limited { fps } => Some(1_000_000_000 / fps as u64),
+        }
+    }
+}
+
+/// Timing of the last frame, all in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FrameStats {
+    pub frame_index: u64,
+    /// time spent between two `wait` calls
+    pub work_ns: u64,
+    /// time spent sleeping and spinning in `wait`
+    pub wait_ns: u64,
+    /// `work_ns + wait_ns`
+    pub frame_ns: u64,
+}
+
+/// Limits how fast the main loop spins, call `wait` once at the end of every frame.
+///
+/// Sleeps for most of the remaining frame time then spins for the last `spin_threshold_ns`.
+/// The wait never lasts longer than the remaining frame time in real time, so a `TimeService`
+/// that does not follow the real time, like `ManualTimeService`, can not block it forever.
+#[derive(Debug)]
+pub struct FrameLimiter {
+    foreground: FrameRate,
+    background: FrameRate,
+    in_background: bool,
+    spin_threshold_ns: u64,
+    frame_start: u64,
+    stats: FrameStats,
+}
+
+impl FrameLimiter {
+    pub fn new(time_service: &dyn TimeService, foreground: FrameRate) -> Self {
+        Self {
+            foreground,
+            background: FrameRate::Limited {
+                fps: DEFAULT_BACKGROUND_FPS,
+            },
+            in_background: false,
+            spin_threshold_ns: DEFAULT_SPIN_THRESHOLD_NS,
+            frame_start: time_service.get_timestamp_ns(),
+            stats: FrameStats::default(),
+        }
+    }
+
+    pub fn foreground(&self) -> FrameRate {
+        self.foreground
+    }
+
+    pub fn set_foreground(&mut self, set_to: FrameRate) {
+        self.foreground = set_to
+    }
+
+    pub fn background(&self) -> FrameRate {
+        self.background
+    }
+
+    pub fn set_background(&mut self, set_to: FrameRate) {
+        self.background = set_to
+    }
+
+    pub fn is_in_background(&self) -> bool {
+        self.in_background
+    }
+
+    pub fn set_in_background(&mut self, set_to: bool) {
+        self.in_background = set_to
+    }
+
+    pub fn spin_threshold_ns(&self) -> u64 {
+        self.spin_threshold_ns
+    }
+
+    pub fn set_spin_threshold_ns(&mut self, set_to: u64) {
+        self.spin_threshold_ns = set_to
+    }
+
+    /// The frame rate in effect right now.
+    pub fn current(&self) -> FrameRate {
+        if self.in_background {
+            self.background
+        } else {
+            self.foreground
+        }
+    }
+
+    pub fn stats(&self) -> &FrameStats {
+        &self.stats
+    }
+
+    /// Switch to the background frame rate on `DidEnterBackground` and back on `WillEnterForeground`.
+    pub fn observe(&mut self, event: &Event) {
+        match event.raw {
+            RawEvent::App {
+                event: AppEvent::DidEnterBackground,
+            } => self.in_background = true,
+            RawEvent::App {
+                event: AppEvent::WillEnterForeground | AppEvent::DidEnterForeground,
+            } => self.in_background = false,
+            _ => {}
+        }
+    }
+
+    /// Block until the current frame reaches the target frame time.
+    pub fn wait(&mut self, time_service: &dyn TimeService) -> FrameStats {
+        let work_end = time_service.get_timestamp_ns();
+        let wait_start = Instant::now();
+
+        if let Some(frame_time) = self.current().frame_time_ns() {
+            let deadline = self.frame_start.saturating_add(frame_time);
+            let remaining = Duration::from_nanos(deadline.saturating_sub(work_end));
+
+            let sleep_until = deadline.saturating_sub(self.spin_threshold_ns);
+            if work_end < sleep_until {
+                std::thread::sleep(Duration::from_nanos(sleep_until - work_end));
+            }
+
+            while time_service.get_timestamp_ns() < deadline && wait_start.elapsed() < remaining {
+                std::hint::spin_loop();
+            }
+        }
+
+        let frame_end = time_service.get_timestamp_ns();
+
+        self.stats = FrameStats {
+            frame_index: self.stats.frame_index.wrapping_add(1),
+            work_ns: work_end.saturating_sub(self.frame_start),
+            wait_ns: frame_end.saturating_sub(work_end),
+            frame_ns: frame_end.saturating_sub(self.frame_start),
+        };
+        self.frame_start = frame_end;
+
+        trace!(
+            frame_index = self.stats.frame_index,
+            work_ns = self.stats.work_ns,
+            wait_ns = self.stats.wait_ns,
+            frame_ns = self.stats.frame_ns,
+            background = self.in_background,
+            "frame finished"
+        );
+
+        self.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use staccato_core::time_service::{ManualTimeService, StdTimeService};
+
+    #[test]
+    fn waits_for_target_frame_time() {
+        let time = StdTimeService::new();
+        let mut limiter = FrameLimiter::new(&time, FrameRate::Limited { fps: 500 });
+
+        let stats = limiter.wait(&time);
+
+        assert!(stats.frame_ns >= 2_000_000);
+        assert_eq!(stats.frame_index, 1);
+    }
+
+    #[test]
+    fn does_not_hang_on_a_stopped_clock() {
+        let time = ManualTimeService::starting_at(1_000);
+        let mut limiter = FrameLimiter::new(&time, FrameRate::Limited { fps: 500 });
+
+        let stats = limiter.wait(&time);
+
+        assert_eq!(stats.frame_ns, 0);
+        assert_eq!(stats.frame_index, 1);
+    }
+
+    #[test]
+    fn switches_to_background_rate() {
+        let time = StdTimeService::new();
+        let mut limiter = FrameLimiter::new(&time, FrameRate::Unlimited);
+
+        limiter.observe(&Event {
+            ns_timestamp: 0,
+            raw: RawEvent::App {
+                event: AppEvent::DidEnterBackground,
+            },
+        });
+        assert_eq!(
+            limiter.current(),
+            FrameRate::Limited {
+                fps: DEFAULT_BACKGROUND_FPS
+            }
+        );
+
+        limiter.observe(&Event {
+            ns_timestamp: 0,
+            raw: RawEvent::App {
+                event: AppEvent::DidEnterForeground,
+            },
+        });
+        assert_eq!(limiter.current(), FrameRate::Unlimited);
+    }
+}
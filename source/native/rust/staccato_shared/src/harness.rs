@@ -0,0 +1,243 @@
+use crate::event_dispatcher::{EventHandler, EventSource};
+use crate::scripted_event_source::ScriptedEventSource;
+use crate::ticker::{StdTicker, Ticker};
+use staccato_core::tickable::Tickable;
+use staccato_core::time_service::{ManualTimeService, TimeService};
+use std::error::Error;
+
+/// Default frame time of a `Harness` (60 frames per second).
+pub const DEFAULT_HARNESS_FRAME_NS: u64 = 1_000_000_000 / 60;
+
+/// Drives a `Tickable + EventHandler` frame by frame without a display.
+///
+/// Every `step` moves the manual clock forward by one frame, hands the scripted events that
+/// became due to `EventHandler::handle` and then drives the ticker, like the main loop does.
+#[derive(Debug)]
+pub struct Harness<T>
+where
+    T: Tickable + EventHandler,
+    T::Error: Error + Sync + Send + 'static,
+{
+    target: T,
+    events: ScriptedEventSource,
+    time_service: ManualTimeService,
+    ticker: StdTicker<T::Error>,
+    frame_ns: u64,
+    frame_index: u64,
+}
+
+impl<T> Harness<T>
+where
+    T: Tickable + EventHandler,
+    T::Error: Error + Sync + Send + 'static,
+{
+    /// Start at time zero with `DEFAULT_HARNESS_FRAME_NS` frames.
+    pub fn new(target: T, tick_per_second: u64) -> Self {
+        let time_service = ManualTimeService::new();
+        Self {
+            target,
+            events: ScriptedEventSource::new(),
+            ticker: StdTicker::new(&time_service, tick_per_second),
+            time_service,
+            frame_ns: DEFAULT_HARNESS_FRAME_NS,
+            frame_index: 0,
+        }
+    }
+
+    pub fn target(&self) -> &T {
+        &self.target
+    }
+
+    pub fn target_mut(&mut self) -> &mut T {
+        &mut self.target
+    }
+
+    pub fn into_target(self) -> T {
+        self.target
+    }
+
+    /// Script events here, timestamps are on the harness clock.
+    pub fn events(&mut self) -> &mut ScriptedEventSource {
+        &mut self.events
+    }
+
+    pub fn ticker(&self) -> &StdTicker<T::Error> {
+        &self.ticker
+    }
+
+    pub fn ticker_mut(&mut self) -> &mut StdTicker<T::Error> {
+        &mut self.ticker
+    }
+
+    /// Pausing or scaling it affects the frame time seen by the ticker.
+    pub fn time_service_mut(&mut self) -> &mut ManualTimeService {
+        &mut self.time_service
+    }
+
+    pub fn time(&self) -> u64 {
+        self.time_service.get_timestamp_ns()
+    }
+
+    pub fn frame_ns(&self) -> u64 {
+        self.frame_ns
+    }
+
+    pub fn set_frame_ns(&mut self, set_to: u64) {
+        self.frame_ns = set_to
+    }
+
+    /// Number of frames run so far.
+    pub fn frame_index(&self) -> u64 {
+        self.frame_index
+    }
+
+    /// Run one frame.
+    pub fn step(&mut self) -> Result<(), T::Error> {
+        self.time_service.advance(self.frame_ns);
+        self.events.set_time(self.time_service.get_timestamp_ns());
+
+        for event in self.events.poll() {
+            self.target.handle(event)?;
+        }
+
+        self.ticker.drive(&self.time_service, &mut self.target)?;
+        self.frame_index += 1;
+        Ok(())
+    }
+
+    pub fn run_frames(&mut self, frames: u64) -> Result<(), T::Error> {
+        for _ in 0..frames {
+            self.step()?;
+        }
+        Ok(())
+    }
+
+    /// Run frames until the clock reaches `ns`.
+    ///
+    /// Stops early after a frame that does not move the clock, e.g. when it is paused,
+    /// scaled to zero or `frame_ns` is 0.
+    pub fn run_until(&mut self, ns: u64) -> Result<(), T::Error> {
+        while self.time() < ns {
+            let before = self.time();
+            self.step()?;
+            if self.time() == before {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Run frames while `condition` holds, at most `max_frames`. Returns the frames run.
+    pub fn run_while(
+        &mut self,
+        max_frames: u64,
+        mut condition: impl FnMut(&T) -> bool,
+    ) -> Result<u64, T::Error> {
+        let mut frames = 0;
+        while frames < max_frames && condition(&self.target) {
+            self.step()?;
+            frames += 1;
+        }
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Event, RawEvent};
+    use staccato_core::fallible::Fallible;
+    use staccato_core::keycode::KeyCode;
+    use staccato_core::scancode::Scancode;
+    use staccato_core::tickable::FrameTime;
+    use std::convert::Infallible;
+
+    #[derive(Debug, Default)]
+    struct Game {
+        running: bool,
+        moving: bool,
+        distance: u32,
+    }
+
+    impl Fallible for Game {
+        type Error = Infallible;
+    }
+
+    impl Tickable for Game {
+        fn pre_update(&mut self, _elapse_ns: u64) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn fixed_update(&mut self, _elapse_ns: u64) -> Result<(), Self::Error> {
+            if self.moving {
+                self.distance += 1;
+            }
+            Ok(())
+        }
+
+        fn update(&mut self, _time: &FrameTime) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn post_update(&mut self, _time: &FrameTime) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl EventHandler for Game {
+        fn handle(&mut self, event: &Event) -> Result<bool, Self::Error> {
+            match event.raw {
+                RawEvent::Keyboard {
+                    scan_code: Scancode::W,
+                    is_down,
+                    ..
+                } => self.moving = is_down,
+                RawEvent::WindowClose { .. } => self.running = false,
+                _ => return Ok(false),
+            }
+            Ok(true)
+        }
+    }
+
+    #[test]
+    fn drives_scripted_playthrough() -> Result<(), Infallible> {
+        let mut harness = Harness::new(
+            Game {
+                running: true,
+                ..Default::default()
+            },
+            100,
+        );
+        harness.set_frame_ns(10_000_000);
+        harness
+            .events()
+            .key(100_000_000, 1, Scancode::W, KeyCode::W, true)
+            .key(200_000_000, 1, Scancode::W, KeyCode::W, false)
+            .close_window(300_000_000, 1);
+
+        harness.run_until(150_000_000)?;
+        assert!(harness.target().moving);
+
+        let frames = harness.run_while(100, |game| game.running)?;
+        assert_eq!(frames, 15);
+        assert_eq!(harness.target().distance, 10);
+        assert_eq!(harness.frame_index(), 30);
+        Ok(())
+    }
+
+    #[test]
+    fn stops_when_the_clock_does_not_move() -> Result<(), Infallible> {
+        let mut harness = Harness::new(Game::default(), 100);
+
+        harness.time_service_mut().pause();
+        harness.run_until(1_000_000_000)?;
+        assert_eq!(harness.frame_index(), 1);
+
+        harness.time_service_mut().resume();
+        harness.set_frame_ns(0);
+        harness.run_until(1_000_000_000)?;
+        assert_eq!(harness.frame_index(), 2);
+        assert_eq!(harness.time(), 0);
+        Ok(())
+    }
+}
@@ -3,3 +3,13 @@ pub type WindowId = u64;
 pub type KeyboardId = u64;
 
 pub type MouseId = u64;
+
+pub type JoystickId = u64;
+
+pub type TouchId = u64;
+
+pub type FingerId = u64;
+
+pub type PenId = u64;
+
+pub type DisplayId = u64;
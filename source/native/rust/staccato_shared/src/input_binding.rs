@@ -0,0 +1,1032 @@
+//! Named actions and axes bound to physical inputs.
+//!
+//! Bindings are grouped in contexts (e.g. `menu`, `gameplay`) which are activated through a
+//! stack, the top context wins and a blocking context hides everything below it.
+//!
+//! The text format is line based, `#` starts a comment:
+//!
+//! ```text
+//! context gameplay
+//! action jump = key:Space, button:South
+//! action save = key:Ctrl+S
+//! axis zoom = wheel:Down/wheel:Up
+//! axis steer = axis:LeftX@0.15, key:A/key:D
+//! axis2d move = stick:Left@0.2, key:W/key:S/key:A/key:D
+//!
+//! context menu blocking
+//! action confirm = key:Return, button:South, axis:RightTrigger+@0.5
+//! ```
+//!
+//! Composite axes list the negative source first, composite 2D axes list up, down, left, right.
+
+use crate::input_state::InputState;
+use num_traits::FromPrimitive;
+use smol_str::SmolStr;
+use staccato_core::frect::FPoint;
+use staccato_core::gamepad::{GamepadAxis, GamepadButton};
+use staccato_core::keymod::Keymod;
+use staccato_core::mouse::Button;
+use staccato_core::scancode::Scancode;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Default press threshold of a half gamepad axis used as a button.
+pub const DEFAULT_AXIS_THRESHOLD: f32 = 0.5;
+
+/// Default dead zone of gamepad axes and sticks.
+pub const DEFAULT_DEAD_ZONE: f32 = 0.15;
+
+/// Modifiers that can be part of a key chord, in the order they are written.
+const CHORD_MODIFIERS: [(&str, Keymod); 4] = [
+    ("Ctrl", Keymod::CTRL),
+    ("Shift", Keymod::SHIFT),
+    ("Alt", Keymod::ALT),
+    ("Gui", Keymod::GUI),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BindingError {
+    #[error("line {line}: binding outside of a context")]
+    NoContext { line: usize },
+    #[error("line {line}: unknown input `{token}`")]
+    UnknownInput { line: usize, token: String },
+    #[error("line {line}: {message}")]
+    Syntax { line: usize, message: &'static str },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WheelDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stick {
+    Left,
+    Right,
+}
+
+/// A physical input that is either pressed or not.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputSource {
+    /// `modifiers` must be held as well, e.g. `Ctrl+S`
+    Key {
+        scancode: Scancode,
+        modifiers: Keymod,
+    },
+    Mouse(Button),
+    Wheel(WheelDirection),
+    /// a button of any gamepad
+    GamepadButton(GamepadButton),
+    /// one half of a gamepad axis, pressed above `threshold`
+    GamepadAxis {
+        axis: GamepadAxis,
+        positive: bool,
+        threshold: f32,
+    },
+}
+
+impl InputSource {
+    /// The first input pressed this frame, used to rebind controls.
+    pub fn capture(input: &InputState) -> Option<Self> {
+        if let Some(scancode) = input.pressed_scancodes().next() {
+            return Some(InputSource::Key {
+                scancode,
+                modifiers: Keymod::NONE,
+            });
+        }
+
+        if let Some(button) = Button::all()
+            .iter()
+            .find(|button| input.button_just_pressed(*button))
+        {
+            return Some(InputSource::Mouse(button));
+        }
+
+        input.gamepads().find_map(|gamepad| {
+            (0..GamepadButton::COUNT as i32)
+                .filter_map(GamepadButton::from_i32)
+                .find(|button| gamepad.just_pressed(*button))
+                .map(InputSource::GamepadButton)
+        })
+    }
+
+    /// Strength in `[0, 1]`, wheel sources report the scroll amount of this frame.
+    pub fn value(&self, input: &InputState) -> f32 {
+        match *self {
+            InputSource::Key {
+                scancode,
+                modifiers,
+            } => {
+                let chord = CHORD_MODIFIERS
+                    .iter()
+                    .filter(|(_, modifier)| modifiers.intersects(*modifier))
+                    .all(|(_, modifier)| input.keymod().intersects(*modifier));
+                if chord && input.is_down(scancode) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            InputSource::Mouse(button) => {
+                if input.buttons().intersects(button) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            InputSource::Wheel(direction) => {
+                let wheel = input.wheel();
+                let value = match direction {
+                    WheelDirection::Up => wheel.y,
+                    WheelDirection::Down => -wheel.y,
+                    WheelDirection::Left => -wheel.x,
+                    WheelDirection::Right => wheel.x,
+                };
+                value.max(0.0)
+            }
+            InputSource::GamepadButton(button) => {
+                if input.gamepads().any(|gamepad| gamepad.is_down(button)) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            InputSource::GamepadAxis {
+                axis,
+                positive,
+                threshold,
+            } => input
+                .gamepads()
+                .map(|gamepad| {
+                    let value = gamepad.axis_normalized(axis);
+                    let value = if positive { value } else { -value };
+                    if value >= threshold { value } else { 0.0 }
+                })
+                .fold(0.0, f32::max),
+        }
+    }
+
+    pub fn is_down(&self, input: &InputState) -> bool {
+        self.value(input) > 0.0
+    }
+}
+
+/// Rescale `value` so that `[dead_zone, 1]` maps to `[0, 1]`, keeping the sign.
+fn apply_dead_zone(value: f32, dead_zone: f32) -> f32 {
+    let magnitude = value.abs();
+    if magnitude <= dead_zone || dead_zone >= 1.0 {
+        return 0.0;
+    }
+    (value.signum() * (magnitude - dead_zone) / (1.0 - dead_zone)).clamp(-1.0, 1.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AxisSource {
+    Gamepad {
+        axis: GamepadAxis,
+        dead_zone: f32,
+    },
+    Composite {
+        negative: InputSource,
+        positive: InputSource,
+    },
+}
+
+impl AxisSource {
+    pub fn value(&self, input: &InputState) -> f32 {
+        match self {
+            AxisSource::Gamepad { axis, dead_zone } => input
+                .gamepads()
+                .map(|gamepad| apply_dead_zone(gamepad.axis_normalized(*axis), *dead_zone))
+                .fold(
+                    0.0,
+                    |max, value| {
+                        if value.abs() > max.abs() { value } else { max }
+                    },
+                ),
+            AxisSource::Composite { negative, positive } => {
+                (positive.value(input) - negative.value(input)).clamp(-1.0, 1.0)
+            }
+        }
+    }
+}
+
+/// 2D axis, y points down like window coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Axis2dSource {
+    Stick {
+        stick: Stick,
+        dead_zone: f32,
+    },
+    Composite {
+        up: InputSource,
+        down: InputSource,
+        left: InputSource,
+        right: InputSource,
+    },
+}
+
+impl Axis2dSource {
+    pub fn value(&self, input: &InputState) -> FPoint {
+        match self {
+            Axis2dSource::Stick { stick, dead_zone } => {
+                let (x_axis, y_axis) = match stick {
+                    Stick::Left => (GamepadAxis::LeftX, GamepadAxis::LeftY),
+                    Stick::Right => (GamepadAxis::RightX, GamepadAxis::RightY),
+                };
+                input
+                    .gamepads()
+                    .map(|gamepad| {
+                        let x = gamepad.axis_normalized(x_axis);
+                        let y = gamepad.axis_normalized(y_axis);
+                        let length = (x * x + y * y).sqrt();
+                        let scaled = apply_dead_zone(length.min(1.0), *dead_zone);
+                        if length > 0.0 {
+                            FPoint::new(x / length * scaled, y / length * scaled)
+                        } else {
+                            FPoint::zero()
+                        }
+                    })
+                    .fold(FPoint::zero(), longest)
+            }
+            Axis2dSource::Composite {
+                up,
+                down,
+                left,
+                right,
+            } => {
+                let x = right.value(input).min(1.0) - left.value(input).min(1.0);
+                let y = down.value(input).min(1.0) - up.value(input).min(1.0);
+                let length = (x * x + y * y).sqrt();
+                if length > 1.0 {
+                    FPoint::new(x / length, y / length)
+                } else {
+                    FPoint::new(x, y)
+                }
+            }
+        }
+    }
+}
+
+fn longest(a: FPoint, b: FPoint) -> FPoint {
+    if b.x * b.x + b.y * b.y > a.x * a.x + a.y * a.y {
+        b
+    } else {
+        a
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Action {
+    name: SmolStr,
+    sources: Vec<InputSource>,
+    down: bool,
+    was_down: bool,
+    value: f32,
+}
+
+impl Action {
+    pub fn new(name: impl Into<SmolStr>) -> Self {
+        Self {
+            name: name.into(),
+            sources: Vec::new(),
+            down: false,
+            was_down: false,
+            value: 0.0,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn sources(&self) -> &[InputSource] {
+        &self.sources
+    }
+
+    pub fn sources_mut(&mut self) -> &mut Vec<InputSource> {
+        &mut self.sources
+    }
+
+    fn update(&mut self, input: &InputState) {
+        self.was_down = self.down;
+        self.value = self
+            .sources
+            .iter()
+            .map(|source| source.value(input))
+            .fold(0.0, f32::max);
+        self.down = self.value > 0.0;
+    }
+
+    fn reset(&mut self) {
+        self.down = false;
+        self.was_down = false;
+        self.value = 0.0;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+    name: SmolStr,
+    sources: Vec<AxisSource>,
+}
+
+impl Axis {
+    pub fn new(name: impl Into<SmolStr>) -> Self {
+        Self {
+            name: name.into(),
+            sources: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn sources(&self) -> &[AxisSource] {
+        &self.sources
+    }
+
+    pub fn sources_mut(&mut self) -> &mut Vec<AxisSource> {
+        &mut self.sources
+    }
+
+    /// The source with the largest magnitude wins.
+    pub fn value(&self, input: &InputState) -> f32 {
+        self.sources
+            .iter()
+            .map(|source| source.value(input))
+            .fold(
+                0.0,
+                |max, value| {
+                    if value.abs() > max.abs() { value } else { max }
+                },
+            )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis2d {
+    name: SmolStr,
+    sources: Vec<Axis2dSource>,
+}
+
+impl Axis2d {
+    pub fn new(name: impl Into<SmolStr>) -> Self {
+        Self {
+            name: name.into(),
+            sources: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn sources(&self) -> &[Axis2dSource] {
+        &self.sources
+    }
+
+    pub fn sources_mut(&mut self) -> &mut Vec<Axis2dSource> {
+        &mut self.sources
+    }
+
+    /// The source with the largest magnitude wins.
+    pub fn value(&self, input: &InputState) -> FPoint {
+        self.sources
+            .iter()
+            .map(|source| source.value(input))
+            .fold(FPoint::zero(), longest)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputContext {
+    name: SmolStr,
+    /// hide the contexts below this one while active
+    pub blocking: bool,
+    actions: Vec<Action>,
+    axes: Vec<Axis>,
+    axes_2d: Vec<Axis2d>,
+}
+
+impl InputContext {
+    pub fn new(name: impl Into<SmolStr>, blocking: bool) -> Self {
+        Self {
+            name: name.into(),
+            blocking,
+            actions: Vec::new(),
+            axes: Vec::new(),
+            axes_2d: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn action(&self, name: &str) -> Option<&Action> {
+        self.actions.iter().find(|action| action.name == name)
+    }
+
+    /// Get or create the action `name`.
+    pub fn action_mut(&mut self, name: &str) -> &mut Action {
+        let index = match self.actions.iter().position(|action| action.name == name) {
+            Some(index) => index,
+            None => {
+                self.actions.push(Action::new(name));
+                self.actions.len() - 1
+            }
+        };
+        &mut self.actions[index]
+    }
+
+    pub fn axis(&self, name: &str) -> Option<&Axis> {
+        self.axes.iter().find(|axis| axis.name == name)
+    }
+
+    /// Get or create the axis `name`.
+    pub fn axis_mut(&mut self, name: &str) -> &mut Axis {
+        let index = match self.axes.iter().position(|axis| axis.name == name) {
+            Some(index) => index,
+            None => {
+                self.axes.push(Axis::new(name));
+                self.axes.len() - 1
+            }
+        };
+        &mut self.axes[index]
+    }
+
+    pub fn axis_2d(&self, name: &str) -> Option<&Axis2d> {
+        self.axes_2d.iter().find(|axis| axis.name == name)
+    }
+
+    /// Get or create the 2D axis `name`.
+    pub fn axis_2d_mut(&mut self, name: &str) -> &mut Axis2d {
+        let index = match self.axes_2d.iter().position(|axis| axis.name == name) {
+            Some(index) => index,
+            None => {
+                self.axes_2d.push(Axis2d::new(name));
+                self.axes_2d.len() - 1
+            }
+        };
+        &mut self.axes_2d[index]
+    }
+
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    pub fn axes(&self) -> &[Axis] {
+        &self.axes
+    }
+
+    pub fn axes_2d(&self) -> &[Axis2d] {
+        &self.axes_2d
+    }
+}
+
+/// All contexts plus the stack of active ones.
+///
+/// Call `update` once per frame after `InputState::update`, then query by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActionMap {
+    contexts: Vec<InputContext>,
+    /// indices into `contexts`, the last one is the top
+    stack: Vec<usize>,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn contexts(&self) -> &[InputContext] {
+        &self.contexts
+    }
+
+    pub fn context(&self, name: &str) -> Option<&InputContext> {
+        self.contexts.iter().find(|context| context.name == name)
+    }
+
+    /// Get or create the context `name`.
+    pub fn context_mut(&mut self, name: &str) -> &mut InputContext {
+        let index = self.context_index_or_insert(name);
+        &mut self.contexts[index]
+    }
+
+    fn context_index_or_insert(&mut self, name: &str) -> usize {
+        match self
+            .contexts
+            .iter()
+            .position(|context| context.name == name)
+        {
+            Some(index) => index,
+            None => {
+                self.contexts.push(InputContext::new(name, false));
+                self.contexts.len() - 1
+            }
+        }
+    }
+
+    /// Activate `name` on top of the stack, creating it if needed.
+    pub fn push_context(&mut self, name: &str) {
+        let index = self.context_index_or_insert(name);
+        self.stack.push(index);
+    }
+
+    pub fn pop_context(&mut self) -> Option<&InputContext> {
+        let index = self.stack.pop()?;
+        self.contexts.get(index)
+    }
+
+    pub fn active_contexts(&self) -> impl Iterator<Item = &InputContext> {
+        self.stack.iter().rev().map(|index| &self.contexts[*index])
+    }
+
+    /// Indices of the contexts visible from the top of the stack.
+    fn visible(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut blocked = false;
+        self.stack.iter().rev().copied().take_while(move |index| {
+            let visible = !blocked;
+            blocked |= self.contexts[*index].blocking;
+            visible
+        })
+    }
+
+    /// Refresh the actions of the visible contexts, hidden ones are released silently.
+    pub fn update(&mut self, input: &InputState) {
+        for index in 0..self.contexts.len() {
+            let visible = self.visible().any(|visible| visible == index);
+            for action in &mut self.contexts[index].actions {
+                if visible {
+                    action.update(input);
+                } else {
+                    action.reset();
+                }
+            }
+        }
+    }
+
+    fn find_action(&self, name: &str) -> Option<&Action> {
+        self.visible()
+            .find_map(|index| self.contexts[index].action(name))
+    }
+
+    pub fn is_down(&self, action: &str) -> bool {
+        self.find_action(action).is_some_and(|action| action.down)
+    }
+
+    pub fn just_pressed(&self, action: &str) -> bool {
+        self.find_action(action)
+            .is_some_and(|action| action.down && !action.was_down)
+    }
+
+    pub fn just_released(&self, action: &str) -> bool {
+        self.find_action(action)
+            .is_some_and(|action| !action.down && action.was_down)
+    }
+
+    /// Analog strength of the action in `[0, 1]`.
+    pub fn action_value(&self, action: &str) -> f32 {
+        self.find_action(action).map_or(0.0, |action| action.value)
+    }
+
+    pub fn axis(&self, input: &InputState, axis: &str) -> f32 {
+        self.visible()
+            .find_map(|index| self.contexts[index].axis(axis))
+            .map_or(0.0, |axis| axis.value(input))
+    }
+
+    pub fn axis_2d(&self, input: &InputState, axis: &str) -> FPoint {
+        self.visible()
+            .find_map(|index| self.contexts[index].axis_2d(axis))
+            .map_or(FPoint::zero(), |axis| axis.value(input))
+    }
+}
+
+fn from_debug_name<T: FromPrimitive + Debug>(name: &str, count: usize) -> Option<T> {
+    (0..count)
+        .filter_map(T::from_usize)
+        .find(|value| format!("{value:?}") == name)
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputSource::Key {
+                scancode,
+                modifiers,
+            } => {
+                write!(f, "key:")?;
+                for (name, modifier) in CHORD_MODIFIERS {
+                    if modifiers.intersects(modifier) {
+                        write!(f, "{name}+")?;
+                    }
+                }
+                write!(f, "{scancode:?}")
+            }
+            InputSource::Mouse(button) => match button.iter_names().next() {
+                Some((name, _)) => write!(f, "mouse:{name}"),
+                None => write!(f, "mouse:{}", button.bits()),
+            },
+            InputSource::Wheel(direction) => write!(f, "wheel:{direction:?}"),
+            InputSource::GamepadButton(button) => write!(f, "button:{button:?}"),
+            InputSource::GamepadAxis {
+                axis,
+                positive,
+                threshold,
+            } => {
+                let sign = if *positive { '+' } else { '-' };
+                write!(f, "axis:{axis:?}{sign}@{threshold}")
+            }
+        }
+    }
+}
+
+impl Display for AxisSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AxisSource::Gamepad { axis, dead_zone } => write!(f, "axis:{axis:?}@{dead_zone}"),
+            AxisSource::Composite { negative, positive } => write!(f, "{negative}/{positive}"),
+        }
+    }
+}
+
+impl Display for Axis2dSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Axis2dSource::Stick { stick, dead_zone } => write!(f, "stick:{stick:?}@{dead_zone}"),
+            Axis2dSource::Composite {
+                up,
+                down,
+                left,
+                right,
+            } => write!(f, "{up}/{down}/{left}/{right}"),
+        }
+    }
+}
+
+fn write_sources<T: Display>(
+    f: &mut Formatter<'_>,
+    kind: &str,
+    name: &str,
+    sources: &[T],
+) -> std::fmt::Result {
+    write!(f, "{kind} {name} =")?;
+    for (index, source) in sources.iter().enumerate() {
+        let separator = if index == 0 { " " } else { ", " };
+        write!(f, "{separator}{source}")?;
+    }
+    writeln!(f)
+}
+
+impl Display for ActionMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, context) in self.contexts.iter().enumerate() {
+            if index != 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "context {}", context.name)?;
+            if context.blocking {
+                write!(f, " blocking")?;
+            }
+            writeln!(f)?;
+
+            for action in &context.actions {
+                write_sources(f, "action", &action.name, &action.sources)?;
+            }
+            for axis in &context.axes {
+                write_sources(f, "axis", &axis.name, &axis.sources)?;
+            }
+            for axis in &context.axes_2d {
+                write_sources(f, "axis2d", &axis.name, &axis.sources)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Split `token` at the optional `@value` suffix, `value` must be in `[0, 1]`.
+fn parse_amount(line: usize, token: &str, default: f32) -> Result<(&str, f32), BindingError> {
+    match token.split_once('@') {
+        None => Ok((token, default)),
+        Some((token, amount)) => match amount.parse::<f32>() {
+            Ok(amount) if (0.0..=1.0).contains(&amount) => Ok((token, amount)),
+            _ => Err(BindingError::Syntax {
+                line,
+                message: "amount must be a number in [0, 1]",
+            }),
+        },
+    }
+}
+
+fn parse_source(line: usize, token: &str) -> Result<InputSource, BindingError> {
+    let unknown = || BindingError::UnknownInput {
+        line,
+        token: token.to_string(),
+    };
+
+    let (kind, value) = token.split_once(':').ok_or_else(unknown)?;
+    let source = match kind {
+        "key" => {
+            let mut modifiers = Keymod::NONE;
+            let mut parts = value.rsplit('+');
+            let scancode = parts
+                .next()
+                .and_then(|name| from_debug_name(name, Scancode::COUNT))
+                .ok_or_else(unknown)?;
+            for part in parts {
+                let (_, modifier) = CHORD_MODIFIERS
+                    .iter()
+                    .find(|(name, _)| *name == part)
+                    .ok_or_else(unknown)?;
+                modifiers |= *modifier;
+            }
+            InputSource::Key {
+                scancode,
+                modifiers,
+            }
+        }
+        "mouse" => InputSource::Mouse(Button::from_name(value).ok_or_else(unknown)?),
+        "wheel" => InputSource::Wheel(match value {
+            "Up" => WheelDirection::Up,
+            "Down" => WheelDirection::Down,
+            "Left" => WheelDirection::Left,
+            "Right" => WheelDirection::Right,
+            _ => return Err(unknown()),
+        }),
+        "button" => InputSource::GamepadButton(
+            from_debug_name(value, GamepadButton::COUNT).ok_or_else(unknown)?,
+        ),
+        "axis" => {
+            let (value, threshold) = parse_amount(line, value, DEFAULT_AXIS_THRESHOLD)?;
+            let (axis, positive) = if let Some(axis) = value.strip_suffix('+') {
+                (axis, true)
+            } else if let Some(axis) = value.strip_suffix('-') {
+                (axis, false)
+            } else {
+                return Err(BindingError::Syntax {
+                    line,
+                    message: "an axis used as a button needs a `+` or `-` direction",
+                });
+            };
+            InputSource::GamepadAxis {
+                axis: from_debug_name(axis, GamepadAxis::COUNT).ok_or_else(unknown)?,
+                positive,
+                threshold,
+            }
+        }
+        _ => return Err(unknown()),
+    };
+    Ok(source)
+}
+
+/// Parse `N` sources separated by `/`.
+fn parse_composite<const N: usize>(
+    line: usize,
+    token: &str,
+) -> Result<[InputSource; N], BindingError> {
+    let mut parts = token.split('/');
+    let mut sources = [InputSource::Wheel(WheelDirection::Up); N];
+    for source in &mut sources {
+        let part = parts.next().ok_or(BindingError::Syntax {
+            line,
+            message: "too few inputs in composite binding",
+        })?;
+        *source = parse_source(line, part.trim())?;
+    }
+    if parts.next().is_some() {
+        return Err(BindingError::Syntax {
+            line,
+            message: "too many inputs in composite binding",
+        });
+    }
+    Ok(sources)
+}
+
+fn parse_axis_source(line: usize, token: &str) -> Result<AxisSource, BindingError> {
+    if let Some(value) = token.strip_prefix("axis:") {
+        let (axis, dead_zone) = parse_amount(line, value, DEFAULT_DEAD_ZONE)?;
+        if let Some(axis) = from_debug_name(axis, GamepadAxis::COUNT) {
+            return Ok(AxisSource::Gamepad { axis, dead_zone });
+        }
+    }
+
+    let [negative, positive] = parse_composite(line, token)?;
+    Ok(AxisSource::Composite { negative, positive })
+}
+
+fn parse_axis_2d_source(line: usize, token: &str) -> Result<Axis2dSource, BindingError> {
+    if let Some(value) = token.strip_prefix("stick:") {
+        let (stick, dead_zone) = parse_amount(line, value, DEFAULT_DEAD_ZONE)?;
+        let stick = match stick {
+            "Left" => Stick::Left,
+            "Right" => Stick::Right,
+            _ => {
+                return Err(BindingError::UnknownInput {
+                    line,
+                    token: token.to_string(),
+                });
+            }
+        };
+        return Ok(Axis2dSource::Stick { stick, dead_zone });
+    }
+
+    let [up, down, left, right] = parse_composite(line, token)?;
+    Ok(Axis2dSource::Composite {
+        up,
+        down,
+        left,
+        right,
+    })
+}
+
+impl FromStr for ActionMap {
+    type Err = BindingError;
+
+    /// Load bindings from the text format, the context stack starts empty.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut map = ActionMap::new();
+        let mut current: Option<usize> = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+            if keyword == "context" {
+                let mut words = rest.split_whitespace();
+                let name = words.next().ok_or(BindingError::Syntax {
+                    line: line_number,
+                    message: "missing context name",
+                })?;
+                let blocking = match words.next() {
+                    None => false,
+                    Some("blocking") => true,
+                    Some(_) => {
+                        return Err(BindingError::Syntax {
+                            line: line_number,
+                            message: "expected `blocking` after the context name",
+                        });
+                    }
+                };
+                let context = map.context_index_or_insert(name);
+                map.contexts[context].blocking = blocking;
+                current = Some(context);
+                continue;
+            }
+
+            let (name, sources) = rest.split_once('=').ok_or(BindingError::Syntax {
+                line: line_number,
+                message: "expected `=` after the binding name",
+            })?;
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return Err(BindingError::Syntax {
+                    line: line_number,
+                    message: "binding names must be a single word",
+                });
+            }
+
+            let context = current.ok_or(BindingError::NoContext { line: line_number })?;
+            let context = &mut map.contexts[context];
+            let sources = sources.split(',').map(str::trim).filter(|s| !s.is_empty());
+
+            match keyword {
+                "action" => {
+                    let action = context.action_mut(name);
+                    for source in sources {
+                        action.sources.push(parse_source(line_number, source)?);
+                    }
+                }
+                "axis" => {
+                    let axis = context.axis_mut(name);
+                    for source in sources {
+                        axis.sources.push(parse_axis_source(line_number, source)?);
+                    }
+                }
+                "axis2d" => {
+                    let axis = context.axis_2d_mut(name);
+                    for source in sources {
+                        axis.sources
+                            .push(parse_axis_2d_source(line_number, source)?);
+                    }
+                }
+                _ => {
+                    return Err(BindingError::Syntax {
+                        line: line_number,
+                        message: "expected `context`, `action`, `axis` or `axis2d`",
+                    });
+                }
+            }
+        }
+
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{Event, RawEvent, UserOperation};
+    use staccato_core::keycode::KeyCode;
+
+    const BINDINGS: &str = "\
+# comment
+context gameplay
+action jump = key:Space, button:South
+action save = key:Ctrl+S
+axis steer = axis:LeftX@0.2, key:A/key:D
+axis2d move = stick:Left, key:W/key:S/key:A/key:D
+
+context menu blocking
+action confirm = key:Return, mouse:Left, axis:RightTrigger+@0.25
+";
+
+    fn key(scan_code: Scancode, is_down: bool) -> Event {
+        Event {
+            ns_timestamp: 0,
+            raw: RawEvent::Keyboard {
+                window_id: 1,
+                keyboard_id: 0,
+                scan_code,
+                key_code: KeyCode::Unknown,
+                keymod: Keymod::NONE,
+                raw_scancode: 0,
+                is_down,
+                is_repeat: false,
+                user_operation: if is_down {
+                    UserOperation::Down
+                } else {
+                    UserOperation::Up
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn text_round_trip() -> Result<(), BindingError> {
+        let map: ActionMap = BINDINGS.parse()?;
+
+        let gameplay = map.context("gameplay");
+        assert_eq!(
+            gameplay
+                .and_then(|context| context.action("save"))
+                .map(Action::sources),
+            Some(
+                &[InputSource::Key {
+                    scancode: Scancode::S,
+                    modifiers: Keymod::CTRL,
+                }][..]
+            )
+        );
+        assert_eq!(
+            map.context("menu").map(|context| context.blocking),
+            Some(true)
+        );
+
+        let reparsed: ActionMap = map.to_string().parse()?;
+        assert_eq!(reparsed, map);
+
+        assert!(matches!(
+            "context a\naction b = key:Nope".parse::<ActionMap>(),
+            Err(BindingError::UnknownInput { line: 2, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn blocking_context_hides_lower_contexts() -> Result<(), BindingError> {
+        let mut map: ActionMap = BINDINGS.parse()?;
+        let mut input = InputState::new();
+        map.push_context("gameplay");
+
+        input.update(&[key(Scancode::Space, true), key(Scancode::D, true)]);
+        map.update(&input);
+        assert!(map.just_pressed("jump"));
+        assert_eq!(map.axis(&input, "steer"), 1.0);
+        assert_eq!(map.axis_2d(&input, "move"), FPoint::new(1.0, 0.0));
+
+        map.push_context("menu");
+        input.update(&[]);
+        map.update(&input);
+        assert!(!map.is_down("jump"));
+        assert_eq!(map.axis(&input, "steer"), 0.0);
+
+        map.pop_context();
+        input.update(&[]);
+        map.update(&input);
+        assert!(map.just_pressed("jump"));
+        Ok(())
+    }
+}
@@ -0,0 +1,442 @@
+use crate::event::{Event, GamepadDeviceOperation, RawEvent, WindowEvent};
+use crate::id::JoystickId;
+use num_traits::FromPrimitive;
+use staccato_core::frect::FPoint;
+use staccato_core::gamepad::{GamepadAxis, GamepadButton};
+use staccato_core::keycode::KeyCode;
+use staccato_core::keymod::Keymod;
+use staccato_core::mouse::Button;
+use staccato_core::scancode::Scancode;
+
+/// Max gamepads tracked at the same time.
+pub const MAX_GAMEPADS: usize = 8;
+
+const SCANCODE_WORDS: usize = Scancode::COUNT / 64;
+
+/// A key identified either by its physical position or by its layout meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Scan(Scancode),
+    Code(KeyCode),
+}
+
+impl From<Scancode> for Key {
+    fn from(value: Scancode) -> Self {
+        Key::Scan(value)
+    }
+}
+
+impl From<KeyCode> for Key {
+    fn from(value: KeyCode) -> Self {
+        Key::Code(value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct ScancodeSet([u64; SCANCODE_WORDS]);
+
+impl ScancodeSet {
+    fn insert(&mut self, index: usize) {
+        if index < Scancode::COUNT {
+            self.0[index / 64] |= 1 << (index % 64);
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        if index < Scancode::COUNT {
+            self.0[index / 64] &= !(1 << (index % 64));
+        }
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        index < Scancode::COUNT && self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(word_index, word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| word_index * 64 + bit)
+        })
+    }
+
+    fn union_with(&mut self, other: &Self) {
+        for (word, other) in self.0.iter_mut().zip(other.0.iter()) {
+            *word |= other;
+        }
+    }
+
+    fn clear(&mut self) {
+        self.0 = [0; SCANCODE_WORDS];
+    }
+}
+
+/// State of one gamepad, see `InputState::gamepad`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GamepadState {
+    joystick_id: JoystickId,
+    down: u32,
+    pressed: u32,
+    released: u32,
+    axes: [i16; GamepadAxis::COUNT],
+}
+
+impl GamepadState {
+    fn new(joystick_id: JoystickId) -> Self {
+        Self {
+            joystick_id,
+            down: 0,
+            pressed: 0,
+            released: 0,
+            axes: [0; GamepadAxis::COUNT],
+        }
+    }
+
+    pub fn joystick_id(&self) -> JoystickId {
+        self.joystick_id
+    }
+
+    pub fn is_down(&self, button: GamepadButton) -> bool {
+        self.down & (1 << button as u32) != 0
+    }
+
+    pub fn just_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed & (1 << button as u32) != 0
+    }
+
+    pub fn just_released(&self, button: GamepadButton) -> bool {
+        self.released & (1 << button as u32) != 0
+    }
+
+    /// Raw value, sticks in `[-32768, 32767]`, triggers in `[0, 32767]`.
+    pub fn axis(&self, axis: GamepadAxis) -> i16 {
+        self.axes[axis as usize]
+    }
+
+    /// Axis value mapped to `[-1, 1]`.
+    pub fn axis_normalized(&self, axis: GamepadAxis) -> f32 {
+        (self.axis(axis) as f32 / i16::MAX as f32).max(-1.0)
+    }
+}
+
+/// Polled input state rebuilt from the events of each frame.
+///
+/// Call `update` with the batch returned by `EventSource::poll` once per frame, then query.
+/// `just_pressed` and `just_released` only report edges of the last `update`.
+#[derive(Debug, Clone)]
+pub struct InputState {
+    keys_down: ScancodeSet,
+    keys_pressed: ScancodeSet,
+    keys_released: ScancodeSet,
+    /// last `KeyCode` reported for each scancode
+    key_codes: [KeyCode; Scancode::COUNT],
+    keymod: Keymod,
+    mouse_position: FPoint,
+    mouse_delta: FPoint,
+    buttons: Button,
+    buttons_pressed: Button,
+    buttons_released: Button,
+    wheel: FPoint,
+    gamepads: [Option<GamepadState>; MAX_GAMEPADS],
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self {
+            keys_down: ScancodeSet::default(),
+            keys_pressed: ScancodeSet::default(),
+            keys_released: ScancodeSet::default(),
+            key_codes: [KeyCode::Unknown; Scancode::COUNT],
+            keymod: Keymod::NONE,
+            mouse_position: FPoint::zero(),
+            mouse_delta: FPoint::zero(),
+            buttons: Button::empty(),
+            buttons_pressed: Button::empty(),
+            buttons_released: Button::empty(),
+            wheel: FPoint::zero(),
+            gamepads: [None; MAX_GAMEPADS],
+        }
+    }
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Start a new frame and apply its events.
+    pub fn update(&mut self, events: &[Event]) {
+        self.begin_frame();
+        for event in events {
+            self.handle(event);
+        }
+    }
+
+    /// Clear the per-frame edges and deltas.
+    pub fn begin_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.mouse_delta = FPoint::zero();
+        self.buttons_pressed = Button::empty();
+        self.buttons_released = Button::empty();
+        self.wheel = FPoint::zero();
+        for gamepad in self.gamepads.iter_mut().flatten() {
+            gamepad.pressed = 0;
+            gamepad.released = 0;
+        }
+    }
+
+    /// Apply a single event to the current frame.
+    pub fn handle(&mut self, event: &Event) {
+        match &event.raw {
+            RawEvent::Keyboard {
+                scan_code,
+                key_code,
+                keymod,
+                is_down,
+                is_repeat,
+                ..
+            } => {
+                let index = *scan_code as usize;
+                self.keymod = *keymod;
+                if index < Scancode::COUNT {
+                    self.key_codes[index] = *key_code;
+                }
+
+                if *is_repeat {
+                    return;
+                }
+
+                if *is_down {
+                    self.keys_down.insert(index);
+                    self.keys_pressed.insert(index);
+                } else {
+                    self.keys_down.remove(index);
+                    self.keys_released.insert(index);
+                }
+            }
+            RawEvent::MouseMotion {
+                position,
+                relative,
+                state,
+                ..
+            } => {
+                self.mouse_position = *position;
+                self.mouse_delta = self.mouse_delta + *relative;
+                self.buttons = *state;
+            }
+            RawEvent::MouseButton {
+                button,
+                down,
+                position,
+                ..
+            } => {
+                self.mouse_position = *position;
+                if *down {
+                    self.buttons |= *button;
+                    self.buttons_pressed |= *button;
+                } else {
+                    self.buttons &= !*button;
+                    self.buttons_released |= *button;
+                }
+            }
+            RawEvent::MouseWheel {
+                scroll, position, ..
+            } => {
+                self.mouse_position = *position;
+                self.wheel = self.wheel + *scroll;
+            }
+            RawEvent::Window {
+                event: WindowEvent::FocusLost,
+                ..
+            } => self.release_all(),
+            RawEvent::GamepadDevice {
+                joystick_id,
+                operation,
+            } => match operation {
+                GamepadDeviceOperation::Added | GamepadDeviceOperation::Remapped => {
+                    self.gamepad_slot(*joystick_id);
+                }
+                GamepadDeviceOperation::Removed => {
+                    for slot in &mut self.gamepads {
+                        if slot.is_some_and(|gamepad| gamepad.joystick_id == *joystick_id) {
+                            *slot = None;
+                        }
+                    }
+                }
+            },
+            RawEvent::GamepadButton {
+                joystick_id,
+                button,
+                down,
+                ..
+            } => {
+                if let Some(gamepad) = self.gamepad_slot(*joystick_id) {
+                    let bit = 1 << *button as u32;
+                    if *down {
+                        gamepad.down |= bit;
+                        gamepad.pressed |= bit;
+                    } else {
+                        gamepad.down &= !bit;
+                        gamepad.released |= bit;
+                    }
+                }
+            }
+            RawEvent::GamepadAxisMotion {
+                joystick_id,
+                axis,
+                value,
+            } => {
+                if let Some(gamepad) = self.gamepad_slot(*joystick_id) {
+                    gamepad.axes[*axis as usize] = *value;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Release every key and mouse button, the releases are reported as edges of this frame.
+    pub fn release_all(&mut self) {
+        self.keys_released.union_with(&self.keys_down);
+        self.keys_down.clear();
+        self.keymod = Keymod::NONE;
+        self.buttons_released |= self.buttons;
+        self.buttons = Button::empty();
+    }
+
+    fn gamepad_slot(&mut self, joystick_id: JoystickId) -> Option<&mut GamepadState> {
+        let index = self
+            .gamepads
+            .iter()
+            .position(|slot| slot.is_some_and(|gamepad| gamepad.joystick_id == joystick_id))
+            .or_else(|| self.gamepads.iter().position(Option::is_none))?;
+
+        Some(self.gamepads[index].get_or_insert_with(|| GamepadState::new(joystick_id)))
+    }
+
+    fn matches(&self, set: &ScancodeSet, key: Key) -> bool {
+        match key {
+            Key::Scan(scancode) => set.contains(scancode as usize),
+            Key::Code(key_code) => set.iter().any(|index| self.key_codes[index] == key_code),
+        }
+    }
+
+    pub fn is_down(&self, key: impl Into<Key>) -> bool {
+        self.matches(&self.keys_down, key.into())
+    }
+
+    pub fn just_pressed(&self, key: impl Into<Key>) -> bool {
+        self.matches(&self.keys_pressed, key.into())
+    }
+
+    pub fn just_released(&self, key: impl Into<Key>) -> bool {
+        self.matches(&self.keys_released, key.into())
+    }
+
+    /// Scancodes pressed this frame.
+    pub fn pressed_scancodes(&self) -> impl Iterator<Item = Scancode> + '_ {
+        self.keys_pressed.iter().filter_map(Scancode::from_usize)
+    }
+
+    pub fn keymod(&self) -> Keymod {
+        self.keymod
+    }
+
+    pub fn mouse_position(&self) -> FPoint {
+        self.mouse_position
+    }
+
+    /// Relative mouse motion of this frame.
+    pub fn mouse_delta(&self) -> FPoint {
+        self.mouse_delta
+    }
+
+    pub fn buttons(&self) -> Button {
+        self.buttons
+    }
+
+    pub fn button_just_pressed(&self, button: Button) -> bool {
+        self.buttons_pressed.intersects(button)
+    }
+
+    pub fn button_just_released(&self, button: Button) -> bool {
+        self.buttons_released.intersects(button)
+    }
+
+    /// Wheel scroll of this frame.
+    pub fn wheel(&self) -> FPoint {
+        self.wheel
+    }
+
+    pub fn gamepad(&self, joystick_id: JoystickId) -> Option<&GamepadState> {
+        self.gamepads
+            .iter()
+            .flatten()
+            .find(|gamepad| gamepad.joystick_id == joystick_id)
+    }
+
+    pub fn gamepads(&self) -> impl Iterator<Item = &GamepadState> {
+        self.gamepads.iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::UserOperation;
+
+    fn key(scan_code: Scancode, key_code: KeyCode, is_down: bool) -> Event {
+        Event {
+            ns_timestamp: 0,
+            raw: RawEvent::Keyboard {
+                window_id: 1,
+                keyboard_id: 0,
+                scan_code,
+                key_code,
+                keymod: Keymod::NONE,
+                raw_scancode: 0,
+                is_down,
+                is_repeat: false,
+                user_operation: if is_down {
+                    UserOperation::Down
+                } else {
+                    UserOperation::Up
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn key_edges_last_one_frame() {
+        let mut input = InputState::new();
+
+        input.update(&[key(Scancode::W, KeyCode::W, true)]);
+        assert!(input.is_down(Scancode::W));
+        assert!(input.just_pressed(KeyCode::W));
+
+        input.update(&[]);
+        assert!(input.is_down(KeyCode::W));
+        assert!(!input.just_pressed(Scancode::W));
+
+        input.update(&[key(Scancode::W, KeyCode::W, false)]);
+        assert!(!input.is_down(Scancode::W));
+        assert!(input.just_released(Scancode::W));
+    }
+
+    #[test]
+    fn focus_lost_releases_everything() {
+        let mut input = InputState::new();
+        input.update(&[key(Scancode::A, KeyCode::A, true)]);
+
+        input.update(&[Event {
+            ns_timestamp: 0,
+            raw: RawEvent::Window {
+                window_id: 1,
+                event: WindowEvent::FocusLost,
+            },
+        }]);
+
+        assert!(!input.is_down(Scancode::A));
+        assert!(input.just_released(KeyCode::A));
+    }
+}
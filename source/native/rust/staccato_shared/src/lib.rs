@@ -1,4 +1,11 @@
 pub mod event;
 pub mod event_dispatcher;
+pub mod frame_limiter;
+pub mod harness;
 pub mod id;
+pub mod input_binding;
+pub mod input_state;
+pub mod replay;
+pub mod scripted_event_source;
+pub mod text_composition;
 pub mod ticker;
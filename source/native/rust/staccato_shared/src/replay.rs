@@ -0,0 +1,844 @@
+//! Recording of the event stream and frame boundaries, and deterministic replay.
+//!
+//! A recording starts with `REPLAY_MAGIC`, the format version and the start time, followed by
+//! records. Every record is a zigzag varint timestamp delta to the previous record and a tag
+//! byte. Event records use the bit index of their `EventKind` as tag and are followed by the
+//! fields, `FRAME_TAG` marks the end of a frame. Integers are varints, floats are little endian.
+//!
+//! Both sides drive the ticker with a `ManualTimeService` set to the recorded frame time,
+//! so fixed updates happen at exactly the same points:
+//!
+//! ```ignore
+//! let mut ticker = StdTicker::new(recorder.time_service(), 50);
+//! loop {
+//!     let events = event_source.poll();
+//!     recorder.record_all(events)?;
+//!     // dispatch events ...
+//!     ticker.drive(recorder.frame(&time_service)?, &mut main)?;
+//! }
+//! ```
+//!
+//! `RawEvent::Custom` is not recorded, its payload is opaque and the game sends it again
+//! during replay anyway.
+
+use crate::event::{
+    AppEvent, DeviceOperation, DisplayEvent, DisplayOrientation, Event, GamepadDeviceOperation,
+    GesturePhase, InlineText, RawEvent, TouchOperation, UserOperation, WindowEvent,
+};
+use crate::event_dispatcher::EventSource;
+use num_traits::{FromPrimitive, ToPrimitive};
+use smol_str::SmolStr;
+use staccato_core::frect::FPoint;
+use staccato_core::gamepad::{GamepadAxis, GamepadButton, JoystickHat, SensorType};
+use staccato_core::keycode::KeyCode;
+use staccato_core::keymod::Keymod;
+use staccato_core::mouse::{Button, MouseWheelDirection};
+use staccato_core::pen::{PenAxis, PenInputFlags};
+use staccato_core::rect::Point;
+use staccato_core::scancode::Scancode;
+use staccato_core::time_service::{ManualTimeService, TimeService};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use thiserror::Error;
+
+pub const REPLAY_MAGIC: [u8; 8] = *b"STCREPL\0";
+
+pub const REPLAY_VERSION: u16 = 2;
+
+/// Tag of the record that ends a frame.
+const FRAME_TAG: u8 = 0xff;
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("replay io error")]
+    Io(#[from] std::io::Error),
+    #[error("not a staccato replay")]
+    BadMagic,
+    #[error("unsupported replay version {0}")]
+    UnsupportedVersion(u16),
+    #[error("corrupted replay at byte {offset}: {message}")]
+    Corrupted {
+        offset: usize,
+        message: &'static str,
+    },
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn corrupted(&self, message: &'static str) -> ReplayError {
+        ReplayError::Corrupted {
+            offset: self.offset,
+            message,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset >= self.data.len()
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], ReplayError> {
+        let end = self
+            .offset
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| self.corrupted("unexpected end of data"))?;
+        let bytes = &self.data[self.offset..end];
+        self.offset = end;
+        Ok(bytes)
+    }
+}
+
+trait Encode {
+    fn encode(&self, out: &mut Vec<u8>);
+}
+
+trait Decode: Sized {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError>;
+}
+
+impl Encode for u8 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self);
+    }
+}
+
+impl Decode for u8 {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        Ok(input.bytes(1)?[0])
+    }
+}
+
+impl Encode for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+}
+
+impl Decode for bool {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        match u8::decode(input)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(input.corrupted("invalid bool")),
+        }
+    }
+}
+
+impl Encode for u64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let mut value = *self;
+        while value >= 0x80 {
+            out.push((value as u8) | 0x80);
+            value >>= 7;
+        }
+        out.push(value as u8);
+    }
+}
+
+impl Decode for u64 {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = u8::decode(input)?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(input.corrupted("varint too long"))
+    }
+}
+
+impl Encode for i64 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (((*self << 1) ^ (*self >> 63)) as u64).encode(out);
+    }
+}
+
+impl Decode for i64 {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        let value = u64::decode(input)?;
+        Ok(((value >> 1) as i64) ^ -((value & 1) as i64))
+    }
+}
+
+/// Integers narrower than 64 bits, stored as varints.
+macro_rules! varint {
+    ($($ty:ty as $wide:ty),* $(,)?) => {$(
+        impl Encode for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                <$wide>::from(*self).encode(out);
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+                let value = <$wide>::decode(input)?;
+                <$ty>::try_from(value).map_err(|_| input.corrupted("integer out of range"))
+            }
+        }
+    )*};
+}
+
+varint!(u16 as u64, u32 as u64, i16 as i64, i32 as i64);
+
+impl Encode for f32 {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&self.to_le_bytes());
+    }
+}
+
+impl Decode for f32 {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(input.bytes(4)?);
+        Ok(f32::from_le_bytes(bytes))
+    }
+}
+
+impl<const N: usize> Encode for [f32; N] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        for value in self {
+            value.encode(out);
+        }
+    }
+}
+
+impl<const N: usize> Decode for [f32; N] {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        let mut values = [0.0; N];
+        for value in &mut values {
+            *value = f32::decode(input)?;
+        }
+        Ok(values)
+    }
+}
+
+impl Encode for FPoint {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.x.encode(out);
+        self.y.encode(out);
+    }
+}
+
+impl Decode for FPoint {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        Ok(FPoint::new(f32::decode(input)?, f32::decode(input)?))
+    }
+}
+
+impl Encode for Point {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.x.encode(out);
+        self.y.encode(out);
+    }
+}
+
+impl Decode for Point {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        Ok(Point {
+            x: i32::decode(input)?,
+            y: i32::decode(input)?,
+        })
+    }
+}
+
+impl Encode for InlineText {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len.encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl Decode for InlineText {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        let len = u8::decode(input)?;
+        Ok(InlineText::from_bytes(input.bytes(len.into())?))
+    }
+}
+
+impl Encode for SmolStr {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u64).encode(out);
+        out.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl Decode for SmolStr {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        let len = u64::decode(input)?;
+        let len = usize::try_from(len).map_err(|_| input.corrupted("text too long"))?;
+        let bytes = input.bytes(len)?;
+        std::str::from_utf8(bytes)
+            .map(SmolStr::new)
+            .map_err(|_| input.corrupted("invalid UTF-8"))
+    }
+}
+
+impl Encode for Arc<[SmolStr]> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u64).encode(out);
+        for text in self.iter() {
+            text.encode(out);
+        }
+    }
+}
+
+impl Decode for Arc<[SmolStr]> {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        let count = u64::decode(input)?;
+        (0..count).map(|_| SmolStr::decode(input)).collect()
+    }
+}
+
+/// Enums deriving `ToPrimitive` and `FromPrimitive`.
+macro_rules! primitive_enum {
+    ($($ty:ty),* $(,)?) => {$(
+        impl Encode for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                self.to_i64().unwrap_or_default().encode(out);
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+                let value = i64::decode(input)?;
+                <$ty>::from_i64(value).ok_or_else(|| input.corrupted("invalid enum value"))
+            }
+        }
+    )*};
+}
+
+primitive_enum!(
+    Scancode,
+    KeyCode,
+    MouseWheelDirection,
+    GamepadAxis,
+    GamepadButton,
+    SensorType,
+    PenAxis,
+);
+
+macro_rules! flags {
+    ($($ty:ty),* $(,)?) => {$(
+        impl Encode for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                self.bits().encode(out);
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+                Ok(<$ty>::from_bits_retain(Decode::decode(input)?))
+            }
+        }
+    )*};
+}
+
+flags!(Keymod, Button, JoystickHat, PenInputFlags);
+
+/// Field-less enums, encoded as one byte.
+macro_rules! unit_enum {
+    ($ty:ident { $($variant:ident = $code:literal),* $(,)? }) => {
+        impl Encode for $ty {
+            fn encode(&self, out: &mut Vec<u8>) {
+                let code: u8 = match self {
+                    $($ty::$variant => $code,)*
+                };
+                code.encode(out);
+            }
+        }
+
+        impl Decode for $ty {
+            fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+                match u8::decode(input)? {
+                    $($code => Ok($ty::$variant),)*
+                    _ => Err(input.corrupted(concat!("invalid ", stringify!($ty)))),
+                }
+            }
+        }
+    };
+}
+
+unit_enum!(UserOperation { Up = 0, Down = 1 });
+unit_enum!(DeviceOperation { Added = 0, Removed = 1 });
+unit_enum!(GamepadDeviceOperation {
+    Added = 0,
+    Removed = 1,
+    Remapped = 2,
+});
+unit_enum!(TouchOperation {
+    Down = 0,
+    Up = 1,
+    Motion = 2,
+    Canceled = 3,
+});
+unit_enum!(GesturePhase {
+    Begin = 0,
+    Update = 1,
+    End = 2,
+});
+unit_enum!(AppEvent {
+    Terminating = 0,
+    LowMemory = 1,
+    WillEnterBackground = 2,
+    DidEnterBackground = 3,
+    WillEnterForeground = 4,
+    DidEnterForeground = 5,
+    LocaleChanged = 6,
+    SystemThemeChanged = 7,
+});
+
+unit_enum!(DisplayOrientation {
+    Unknown = 0,
+    Landscape = 1,
+    LandscapeFlipped = 2,
+    Portrait = 3,
+    PortraitFlipped = 4,
+});
+
+/// Enums whose variants may carry named fields, the tag is one byte.
+///
+/// Every variant needs a tag or must be listed in `skip`, skipped variants encode to nothing
+/// and must be filtered out before their timestamp is written.
+macro_rules! tagged_enum {
+    (
+        $ty:ident, $name:literal, $encode:ident, $decode:ident {
+            $($tag:literal => $variant:ident { $($field:ident),* }),* $(,)?
+        }
+        $(skip { $($skip:ident),* })?
+    ) => {
+        fn $encode(value: &$ty, out: &mut Vec<u8>) {
+            match value {
+                $($ty::$variant { $($field),* } => {
+                    ($tag as u8).encode(out);
+                    $($field.encode(out);)*
+                })*
+                $($($ty::$skip { .. } => {})*)?
+            }
+        }
+
+        fn $decode(tag: u8, input: &mut Reader<'_>) -> Result<$ty, ReplayError> {
+            match tag {
+                $($tag => Ok($ty::$variant {
+                    $($field: Decode::decode(input)?),*
+                }),)*
+                _ => Err(input.corrupted(concat!("invalid ", $name, " tag"))),
+            }
+        }
+    };
+}
+
+tagged_enum!(WindowEvent, "WindowEvent", encode_window_event, decode_window_event {
+    0 => Shown {},
+    1 => Hidden {},
+    2 => Exposed { is_live_resize },
+    3 => Moved { position },
+    4 => Resized { size },
+    5 => PixelSizeChanged { size },
+    6 => MetalViewResized {},
+    7 => Minimized {},
+    8 => Maximized {},
+    9 => Restored {},
+    10 => MouseEnter {},
+    11 => MouseLeave {},
+    12 => FocusGained {},
+    13 => FocusLost {},
+    14 => HitTest {},
+    15 => IccProfileChanged {},
+    16 => DisplayChanged { display_id },
+    17 => DisplayScaleChanged {},
+    18 => SafeAreaChanged {},
+    19 => Occluded {},
+    20 => EnterFullscreen {},
+    21 => LeaveFullscreen {},
+    22 => Destroyed {},
+    23 => HdrStateChanged {},
+});
+
+impl Encode for WindowEvent {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_window_event(self, out);
+    }
+}
+
+impl Decode for WindowEvent {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        let tag = u8::decode(input)?;
+        decode_window_event(tag, input)
+    }
+}
+
+tagged_enum!(DisplayEvent, "DisplayEvent", encode_display_event, decode_display_event {
+    0 => Added {},
+    1 => Removed {},
+    2 => Moved {},
+    3 => OrientationChanged { orientation },
+    4 => DesktopModeChanged {},
+    5 => CurrentModeChanged {},
+    6 => ContentScaleChanged {},
+});
+
+impl Encode for DisplayEvent {
+    fn encode(&self, out: &mut Vec<u8>) {
+        encode_display_event(self, out);
+    }
+}
+
+impl Decode for DisplayEvent {
+    fn decode(input: &mut Reader<'_>) -> Result<Self, ReplayError> {
+        let tag = u8::decode(input)?;
+        decode_display_event(tag, input)
+    }
+}
+
+// tags are the bit index of the matching `EventKind`
+tagged_enum!(RawEvent, "event", encode_raw_event, decode_raw_event {
+    0 => Quit {},
+    1 => App { event },
+    2 => Window { window_id, event },
+    3 => WindowClose { id },
+    4 => KeymapChanged {},
+    5 => Keyboard {
+        window_id, keyboard_id, scan_code, key_code, keymod, raw_scancode, is_down, is_repeat,
+        user_operation
+    },
+    6 => TextEditing { window_id, text, start, length },
+    7 => TextInput { window_id, text },
+    8 => KeyboardDevice { keyboard_id, operation },
+    9 => MouseDevice { mouse_id, operation },
+    10 => MouseMotion { window_id, mouse_id, state, position, relative },
+    11 => MouseButton { window_id, mouse_id, button, down, clicks, position, user_operation },
+    12 => MouseWheel { window_id, mouse_id, scroll, direction, position, accumulated_scroll },
+    13 => Unknown { type_id },
+    15 => JoystickDevice { joystick_id, operation },
+    16 => JoystickAxisMotion { joystick_id, axis, value },
+    17 => JoystickHatMotion { joystick_id, hat, state },
+    18 => JoystickButton { joystick_id, button, down, user_operation },
+    19 => GamepadDevice { joystick_id, operation },
+    20 => GamepadAxisMotion { joystick_id, axis, value },
+    21 => GamepadButton { joystick_id, button, down, user_operation },
+    22 => GamepadTouchpad { joystick_id, touchpad, finger, position, pressure, operation },
+    23 => GamepadSensor { joystick_id, sensor, data, sensor_ns_timestamp },
+    24 => Finger { window_id, touch_id, finger_id, position, relative, pressure, operation },
+    25 => Pinch { window_id, scale, phase },
+    26 => PenProximity { window_id, pen_id, entered },
+    27 => PenTouch { window_id, pen_id, state, position, eraser, down, user_operation },
+    28 => PenMotion { window_id, pen_id, state, position },
+    29 => PenButton { window_id, pen_id, state, position, button, down, user_operation },
+    30 => PenAxis { window_id, pen_id, state, position, axis, value },
+    31 => TextEditingCandidates { window_id, candidates, selected, horizontal },
+    32 => ClipboardUpdate { owner, mime_types },
+    33 => Display { display_id, event },
+} skip { Custom });
+
+/// Writes events and frame boundaries, see the module documentation.
+#[derive(Debug)]
+pub struct EventRecorder<W: Write> {
+    writer: W,
+    buffer: Vec<u8>,
+    last_ns: u64,
+    time_service: ManualTimeService,
+}
+
+impl<W: Write> EventRecorder<W> {
+    /// Write the header, the recording starts at the current time of `time_service`.
+    pub fn new(mut writer: W, time_service: &dyn TimeService) -> Result<Self, ReplayError> {
+        let start_ns = time_service.get_timestamp_ns();
+
+        let mut buffer = Vec::with_capacity(64);
+        buffer.extend_from_slice(&REPLAY_MAGIC);
+        buffer.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+        start_ns.encode(&mut buffer);
+        writer.write_all(&buffer)?;
+
+        Ok(Self {
+            writer,
+            buffer,
+            last_ns: start_ns,
+            time_service: ManualTimeService::starting_at(start_ns),
+        })
+    }
+
+    /// The time of the last recorded frame, drive the ticker with it.
+    pub fn time_service(&self) -> &ManualTimeService {
+        &self.time_service
+    }
+
+    fn write_timestamp(&mut self, ns_timestamp: u64) {
+        (ns_timestamp.wrapping_sub(self.last_ns) as i64).encode(&mut self.buffer);
+        self.last_ns = ns_timestamp;
+    }
+
+    pub fn record(&mut self, event: &Event) -> Result<(), ReplayError> {
+        if let RawEvent::Custom { .. } = event.raw {
+            return Ok(());
+        }
+
+        self.buffer.clear();
+        self.write_timestamp(event.ns_timestamp);
+        encode_raw_event(&event.raw, &mut self.buffer);
+        self.writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    pub fn record_all(&mut self, events: &[Event]) -> Result<(), ReplayError> {
+        for event in events {
+            self.record(event)?;
+        }
+        Ok(())
+    }
+
+    /// End the frame at the current time of `time_service`.
+    ///
+    /// Returns the time service to drive the ticker with.
+    pub fn frame(
+        &mut self,
+        time_service: &dyn TimeService,
+    ) -> Result<&ManualTimeService, ReplayError> {
+        let now = time_service.get_timestamp_ns();
+
+        self.buffer.clear();
+        self.write_timestamp(now);
+        FRAME_TAG.encode(&mut self.buffer);
+        self.writer.write_all(&self.buffer)?;
+
+        self.time_service.set(now);
+        Ok(&self.time_service)
+    }
+
+    /// Flush and return the writer.
+    pub fn finish(mut self) -> Result<W, ReplayError> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    /// index after the last event of the frame
+    end: usize,
+    ns_timestamp: u64,
+}
+
+/// Replays a recording, one frame of events per `poll`.
+///
+/// The whole recording is decoded up front so replaying does not allocate. Drive the ticker
+/// with `time_service`, it follows the recorded frame times.
+#[derive(Debug)]
+pub struct ReplayEventSource {
+    events: Vec<Event>,
+    frames: Vec<Frame>,
+    next_frame: usize,
+    time_service: ManualTimeService,
+}
+
+impl ReplayEventSource {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ReplayError> {
+        let mut input = Reader { data, offset: 0 };
+
+        if input.bytes(REPLAY_MAGIC.len()).ok() != Some(&REPLAY_MAGIC[..]) {
+            return Err(ReplayError::BadMagic);
+        }
+        let version = input.bytes(2)?;
+        let version = u16::from_le_bytes([version[0], version[1]]);
+        if version != REPLAY_VERSION {
+            return Err(ReplayError::UnsupportedVersion(version));
+        }
+
+        let start_ns = u64::decode(&mut input)?;
+        let mut last_ns = start_ns;
+        let mut events = Vec::new();
+        let mut frames = Vec::new();
+
+        while !input.is_empty() {
+            let ns_timestamp = last_ns.wrapping_add(i64::decode(&mut input)? as u64);
+            last_ns = ns_timestamp;
+
+            let tag = u8::decode(&mut input)?;
+            if tag == FRAME_TAG {
+                frames.push(Frame {
+                    end: events.len(),
+                    ns_timestamp,
+                });
+            } else {
+                events.push(Event {
+                    ns_timestamp,
+                    raw: decode_raw_event(tag, &mut input)?,
+                });
+            }
+        }
+
+        // events of an unfinished frame still get delivered
+        if frames.last().map_or(0, |frame| frame.end) < events.len() {
+            frames.push(Frame {
+                end: events.len(),
+                ns_timestamp: last_ns,
+            });
+        }
+
+        Ok(Self {
+            events,
+            frames,
+            next_frame: 0,
+            time_service: ManualTimeService::starting_at(start_ns),
+        })
+    }
+
+    /// Set to the time of the frame returned by the last `poll`.
+    pub fn time_service(&self) -> &ManualTimeService {
+        &self.time_service
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Number of frames already returned by `poll`.
+    pub fn current_frame(&self) -> usize {
+        self.next_frame
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_frame >= self.frames.len()
+    }
+
+    /// Start over from the first frame.
+    pub fn rewind(&mut self) {
+        self.next_frame = 0;
+    }
+}
+
+impl EventSource for ReplayEventSource {
+    fn poll(&mut self) -> &[Event] {
+        let Some(frame) = self.frames.get(self.next_frame).copied() else {
+            return &[];
+        };
+
+        let start = match self.next_frame {
+            0 => 0,
+            index => self.frames[index - 1].end,
+        };
+        self.next_frame += 1;
+        self.time_service.set(frame.ns_timestamp);
+
+        &self.events[start..frame.end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(ns_timestamp: u64, raw: RawEvent) -> Event {
+        Event { ns_timestamp, raw }
+    }
+
+    #[test]
+    fn replays_recorded_frames() -> Result<(), ReplayError> {
+        let mut time = ManualTimeService::starting_at(1_000);
+        let mut recorder = EventRecorder::new(Vec::new(), &time)?;
+
+        let first = [
+            event(
+                1_100,
+                RawEvent::Keyboard {
+                    window_id: 1,
+                    keyboard_id: 2,
+                    scan_code: Scancode::W,
+                    key_code: KeyCode::W,
+                    keymod: Keymod::LSHIFT,
+                    raw_scancode: 26,
+                    is_down: true,
+                    is_repeat: false,
+                    user_operation: UserOperation::Down,
+                },
+            ),
+            event(
+                1_200,
+                RawEvent::TextInput {
+                    window_id: 1,
+                    text: InlineText::from_bytes("wä".as_bytes()),
+                },
+            ),
+            event(
+                1_250,
+                RawEvent::TextEditing {
+                    window_id: 1,
+                    text: SmolStr::new("かな".repeat(20)),
+                    start: 3,
+                    length: -1,
+                },
+            ),
+            event(
+                1_300,
+                RawEvent::Window {
+                    window_id: 1,
+                    event: WindowEvent::Resized {
+                        size: Point { x: 640, y: -480 },
+                    },
+                },
+            ),
+        ];
+        recorder.record_all(&first)?;
+        time.set(2_000);
+        assert_eq!(recorder.frame(&time)?.get_timestamp_ns(), 2_000);
+
+        time.set(3_000);
+        recorder.frame(&time)?;
+
+        let second = [event(
+            2_900,
+            RawEvent::GamepadSensor {
+                joystick_id: 4,
+                sensor: SensorType::Gyroscope,
+                data: [0.5, -1.0, 3.25],
+                sensor_ns_timestamp: u64::MAX,
+            },
+        )];
+        recorder.record_all(&second)?;
+
+        let data = recorder.finish()?;
+        let mut replay = ReplayEventSource::from_bytes(&data)?;
+        assert_eq!(replay.time_service().get_timestamp_ns(), 1_000);
+        assert_eq!(replay.frame_count(), 3);
+
+        let polled = replay.poll();
+        assert_eq!(format!("{polled:?}"), format!("{first:?}"));
+        assert_eq!(replay.time_service().get_timestamp_ns(), 2_000);
+
+        assert!(replay.poll().is_empty());
+        assert_eq!(replay.time_service().get_timestamp_ns(), 3_000);
+
+        let polled = replay.poll();
+        assert_eq!(format!("{polled:?}"), format!("{second:?}"));
+        assert!(replay.is_finished());
+        assert!(replay.poll().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_foreign_data() {
+        assert!(matches!(
+            ReplayEventSource::from_bytes(b"not a replay"),
+            Err(ReplayError::BadMagic)
+        ));
+
+        let mut data = REPLAY_MAGIC.to_vec();
+        data.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+        data.extend_from_slice(&[0, 0, 42]);
+        assert!(matches!(
+            ReplayEventSource::from_bytes(&data),
+            Err(ReplayError::Corrupted { .. })
+        ));
+    }
+}
@@ -0,0 +1,248 @@
+use crate::event::{Event, InlineText, RawEvent, UserOperation};
+use crate::event_dispatcher::EventSource;
+use crate::id::{JoystickId, WindowId};
+use staccato_core::frect::FPoint;
+use staccato_core::gamepad::{GamepadAxis, GamepadButton};
+use staccato_core::keycode::KeyCode;
+use staccato_core::keymod::Keymod;
+use staccato_core::mouse::{Button, MouseWheelDirection};
+use staccato_core::rect::Point;
+use staccato_core::scancode::Scancode;
+
+fn user_operation(down: bool) -> UserOperation {
+    if down {
+        UserOperation::Down
+    } else {
+        UserOperation::Up
+    }
+}
+
+/// In-memory `EventSource` for headless tests.
+///
+/// Events are scripted with a timestamp and `poll` returns the ones that are due at the time
+/// set by `set_time`, in timestamp order:
+///
+/// ```ignore
+/// let mut source = ScriptedEventSource::new();
+/// source
+///     .key(100_000_000, 1, Scancode::W, KeyCode::W, true)
+///     .mouse_motion(150_000_000, 1, FPoint::new(10.0, 20.0), FPoint::new(1.0, 0.0))
+///     .close_window(200_000_000, 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct ScriptedEventSource {
+    /// sorted by timestamp, everything before `cursor` was already polled
+    events: Vec<Event>,
+    cursor: usize,
+    now_ns: u64,
+}
+
+impl ScriptedEventSource {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn time(&self) -> u64 {
+        self.now_ns
+    }
+
+    /// Events with a timestamp up to `ns` are returned by the next `poll`.
+    pub fn set_time(&mut self, ns: u64) {
+        self.now_ns = ns
+    }
+
+    /// Number of events not polled yet.
+    pub fn pending(&self) -> usize {
+        self.events.len() - self.cursor
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending() == 0
+    }
+
+    /// Schedule `event`, events scheduled in the past are returned by the next `poll`.
+    pub fn push_event(&mut self, event: Event) -> &mut Self {
+        let index = self.events[self.cursor..]
+            .partition_point(|pending| pending.ns_timestamp <= event.ns_timestamp);
+        self.events.insert(self.cursor + index, event);
+        self
+    }
+
+    pub fn push(&mut self, ns_timestamp: u64, raw: RawEvent) -> &mut Self {
+        self.push_event(Event { ns_timestamp, raw })
+    }
+
+    pub fn quit(&mut self, ns_timestamp: u64) -> &mut Self {
+        self.push(ns_timestamp, RawEvent::Quit)
+    }
+
+    pub fn close_window(&mut self, ns_timestamp: u64, window_id: WindowId) -> &mut Self {
+        self.push(ns_timestamp, RawEvent::WindowClose { id: window_id })
+    }
+
+    pub fn key(
+        &mut self,
+        ns_timestamp: u64,
+        window_id: WindowId,
+        scan_code: Scancode,
+        key_code: KeyCode,
+        down: bool,
+    ) -> &mut Self {
+        self.push(
+            ns_timestamp,
+            RawEvent::Keyboard {
+                window_id,
+                keyboard_id: 0,
+                scan_code,
+                key_code,
+                keymod: Keymod::NONE,
+                raw_scancode: 0,
+                is_down: down,
+                is_repeat: false,
+                user_operation: user_operation(down),
+            },
+        )
+    }
+
+    /// Texts longer than `INLINE_TEXT_MAX` spill into several events, like SDL input does.
+    pub fn text_input(&mut self, ns_timestamp: u64, window_id: WindowId, text: &str) -> &mut Self {
+        for text in InlineText::chunks(text.as_bytes()) {
+            self.push(ns_timestamp, RawEvent::TextInput { window_id, text });
+        }
+        self
+    }
+
+    pub fn mouse_motion(
+        &mut self,
+        ns_timestamp: u64,
+        window_id: WindowId,
+        position: FPoint,
+        relative: FPoint,
+    ) -> &mut Self {
+        self.push(
+            ns_timestamp,
+            RawEvent::MouseMotion {
+                window_id,
+                mouse_id: 0,
+                state: Button::empty(),
+                position,
+                relative,
+            },
+        )
+    }
+
+    pub fn mouse_button(
+        &mut self,
+        ns_timestamp: u64,
+        window_id: WindowId,
+        button: Button,
+        down: bool,
+        position: FPoint,
+    ) -> &mut Self {
+        self.push(
+            ns_timestamp,
+            RawEvent::MouseButton {
+                window_id,
+                mouse_id: 0,
+                button,
+                down,
+                clicks: 1,
+                position,
+                user_operation: user_operation(down),
+            },
+        )
+    }
+
+    pub fn mouse_wheel(
+        &mut self,
+        ns_timestamp: u64,
+        window_id: WindowId,
+        scroll: FPoint,
+    ) -> &mut Self {
+        self.push(
+            ns_timestamp,
+            RawEvent::MouseWheel {
+                window_id,
+                mouse_id: 0,
+                scroll,
+                direction: MouseWheelDirection::Normal,
+                position: FPoint::zero(),
+                accumulated_scroll: Point { x: 0, y: 0 },
+            },
+        )
+    }
+
+    pub fn gamepad_button(
+        &mut self,
+        ns_timestamp: u64,
+        joystick_id: JoystickId,
+        button: GamepadButton,
+        down: bool,
+    ) -> &mut Self {
+        self.push(
+            ns_timestamp,
+            RawEvent::GamepadButton {
+                joystick_id,
+                button,
+                down,
+                user_operation: user_operation(down),
+            },
+        )
+    }
+
+    pub fn gamepad_axis(
+        &mut self,
+        ns_timestamp: u64,
+        joystick_id: JoystickId,
+        axis: GamepadAxis,
+        value: i16,
+    ) -> &mut Self {
+        self.push(
+            ns_timestamp,
+            RawEvent::GamepadAxisMotion {
+                joystick_id,
+                axis,
+                value,
+            },
+        )
+    }
+}
+
+impl EventSource for ScriptedEventSource {
+    fn poll(&mut self) -> &[Event] {
+        let start = self.cursor;
+        let due = self.events[start..].partition_point(|event| event.ns_timestamp <= self.now_ns);
+        self.cursor += due;
+        &self.events[start..self.cursor]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polls_due_events_in_order() {
+        let mut source = ScriptedEventSource::new();
+        source
+            .close_window(200, 1)
+            .quit(300)
+            .key(100, 1, Scancode::W, KeyCode::W, true);
+
+        source.set_time(50);
+        assert!(source.poll().is_empty());
+
+        source.set_time(200);
+        let events = source.poll();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0].raw, RawEvent::Keyboard { .. }));
+        assert!(matches!(events[1].raw, RawEvent::WindowClose { id: 1 }));
+
+        // scheduled in the past, delivered with the next poll
+        source.mouse_wheel(10, 1, FPoint::new(0.0, 1.0));
+        let events = source.poll();
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0].raw, RawEvent::MouseWheel { .. }));
+        assert_eq!(source.pending(), 1);
+    }
+}
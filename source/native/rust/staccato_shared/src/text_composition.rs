@@ -0,0 +1,183 @@
+use crate::event::{Event, RawEvent, WindowEvent};
+use crate::id::WindowId;
+use smol_str::SmolStr;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Byte offset of the `index`-th character, clamped to the end of `text`.
+fn byte_offset(text: &str, index: usize) -> usize {
+    text.char_indices()
+        .nth(index)
+        .map_or(text.len(), |(offset, _)| offset)
+}
+
+/// The IME composition of the focused window, kept up to date from text events.
+///
+/// Draw `text` with the `selection` highlighted and, if the game draws the candidates itself,
+/// the `candidates` list next to the text input area.
+#[derive(Debug, Clone)]
+pub struct TextComposition {
+    window_id: Option<WindowId>,
+    text: String,
+    start: i32,
+    length: i32,
+    candidates: Option<Arc<[SmolStr]>>,
+    selected_candidate: i32,
+    horizontal: bool,
+}
+
+impl Default for TextComposition {
+    fn default() -> Self {
+        Self {
+            window_id: None,
+            text: String::new(),
+            start: -1,
+            length: -1,
+            candidates: None,
+            selected_candidate: -1,
+            horizontal: false,
+        }
+    }
+}
+
+impl TextComposition {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Update from `event`, returns true if the composition changed.
+    pub fn handle(&mut self, event: &Event) -> bool {
+        match &event.raw {
+            RawEvent::TextEditing {
+                window_id,
+                text,
+                start,
+                length,
+            } => {
+                self.window_id = Some(*window_id);
+                self.text.clear();
+                self.text.push_str(text);
+                self.start = *start;
+                self.length = *length;
+                true
+            }
+            RawEvent::TextEditingCandidates {
+                window_id,
+                candidates,
+                selected,
+                horizontal,
+            } => {
+                self.window_id = Some(*window_id);
+                self.candidates = Some(candidates.clone());
+                self.selected_candidate = *selected;
+                self.horizontal = *horizontal;
+                true
+            }
+            // the composition was committed
+            RawEvent::TextInput { .. } => self.clear(),
+            RawEvent::Window {
+                event: WindowEvent::FocusLost,
+                ..
+            } => self.clear(),
+            _ => false,
+        }
+    }
+
+    /// Forget the composition, returns true if there was one.
+    pub fn clear(&mut self) -> bool {
+        let changed = self.is_composing() || self.candidates.is_some();
+        self.window_id = None;
+        self.text.clear();
+        self.start = -1;
+        self.length = -1;
+        self.candidates = None;
+        self.selected_candidate = -1;
+        changed
+    }
+
+    pub fn is_composing(&self) -> bool {
+        !self.text.is_empty()
+    }
+
+    /// The window the composition belongs to.
+    pub fn window_id(&self) -> Option<WindowId> {
+        self.window_id
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Cursor as a byte offset into `text`, `None` if the IME did not report it.
+    pub fn cursor(&self) -> Option<usize> {
+        let start = usize::try_from(self.start).ok()?;
+        Some(byte_offset(&self.text, start))
+    }
+
+    /// Selected part of `text` as a byte range, `None` if nothing is selected.
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let start = usize::try_from(self.start).ok()?;
+        let length = usize::try_from(self.length)
+            .ok()
+            .filter(|length| *length > 0)?;
+        Some(byte_offset(&self.text, start)..byte_offset(&self.text, start + length))
+    }
+
+    pub fn candidates(&self) -> &[SmolStr] {
+        self.candidates.as_deref().unwrap_or_default()
+    }
+
+    pub fn selected_candidate(&self) -> Option<usize> {
+        usize::try_from(self.selected_candidate)
+            .ok()
+            .filter(|index| *index < self.candidates().len())
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        self.horizontal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::InlineText;
+
+    fn event(raw: RawEvent) -> Event {
+        Event {
+            ns_timestamp: 0,
+            raw,
+        }
+    }
+
+    #[test]
+    fn tracks_composition_until_commit() {
+        let mut composition = TextComposition::new();
+
+        composition.handle(&event(RawEvent::TextEditing {
+            window_id: 1,
+            text: SmolStr::new("にほんご"),
+            start: 2,
+            length: 2,
+        }));
+        composition.handle(&event(RawEvent::TextEditingCandidates {
+            window_id: 1,
+            candidates: Arc::from([SmolStr::new("日本語"), SmolStr::new("二本語")]),
+            selected: 0,
+            horizontal: false,
+        }));
+
+        assert_eq!(composition.text(), "にほんご");
+        assert_eq!(composition.cursor(), Some(6));
+        assert_eq!(composition.selection(), Some(6..12));
+        assert_eq!(composition.selected_candidate(), Some(0));
+        assert_eq!(composition.candidates()[0], "日本語");
+
+        assert!(composition.handle(&event(RawEvent::TextInput {
+            window_id: 1,
+            text: InlineText::from("日本語"),
+        })));
+        assert!(!composition.is_composing());
+        assert!(composition.candidates().is_empty());
+    }
+}
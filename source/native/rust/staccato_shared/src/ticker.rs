@@ -1,12 +1,28 @@
+use staccato_core::clock::{ClockChannel, Clocks};
 use staccato_core::fallible::Fallible;
-use staccato_core::tickable::Tickable;
+use staccato_core::tickable::{FrameTime, Tickable};
 use staccato_core::time_service::TimeService;
 use std::error::Error;
 use std::marker::PhantomData;
 
+/// Default upper bound of `fixed_update` calls in a single `drive`.
+pub const DEFAULT_MAX_STEPS_PER_FRAME: u32 = 8;
+
+/// Default upper bound of the frame time fed into the accumulator (250ms).
+pub const DEFAULT_MAX_FRAME_TIME_NS: u64 = 250_000_000;
+
 pub trait Ticker: Fallible {
     fn tick_per_second(&self) -> u64;
     fn set_tick_per_second(&mut self, set_to: u64);
+    /// Max `fixed_update` calls per frame, the remaining backlog is dropped.
+    fn max_steps_per_frame(&self) -> u32;
+    fn set_max_steps_per_frame(&mut self, set_to: u32);
+    /// Frame time above this value is clamped before it enters the accumulator.
+    fn max_frame_time_ns(&self) -> u64;
+    fn set_max_frame_time_ns(&mut self, set_to: u64);
+    /// Clocks advanced by every `drive`, see `ClockChannel`.
+    fn clocks(&self) -> &Clocks;
+    fn clocks_mut(&mut self) -> &mut Clocks;
     fn drive(
         &mut self,
         time_service: &dyn TimeService,
@@ -14,11 +30,19 @@ pub trait Ticker: Fallible {
     ) -> Result<(), Self::Error>;
 }
 
+/// Fixed timestep ticker.
+///
+/// `fixed_update` runs as many times as needed to consume the accumulated time,
+/// bounded by `max_steps_per_frame` and `max_frame_time_ns` to avoid the death spiral.
+/// The accumulator is fed by the `Game` clock, so scaling or pausing it slows or stops physics.
 #[derive(Debug)]
 pub struct StdTicker<E: Error + Sync + Send + 'static> {
     tick_per_second: u64,
-    last_tick: u64,
+    max_steps_per_frame: u32,
+    max_frame_time_ns: u64,
+    accumulator: u64,
     last_update: u64,
+    clocks: Clocks,
     error_type: PhantomData<E>,
 }
 
@@ -28,28 +52,25 @@ impl<E: Error + Sync + Send + 'static> Fallible for StdTicker<E> {
 
 impl<E: Error + Sync + Send + 'static> StdTicker<E> {
     pub fn new(time_service: &dyn TimeService, tick_per_second: u64) -> Self {
-        let last_tick = 0;
         let last_update = time_service.get_timestamp_ns();
         Self {
             tick_per_second,
-            last_tick,
+            max_steps_per_frame: DEFAULT_MAX_STEPS_PER_FRAME,
+            max_frame_time_ns: DEFAULT_MAX_FRAME_TIME_NS,
+            accumulator: 0,
             last_update,
+            clocks: Clocks::new(),
             error_type: Default::default(),
         }
     }
 
-    fn compute_next_tick_ns(&self) -> u64 {
+    /// `None` if fixed update is disabled (`tick_per_second == 0`).
+    fn fixed_step_ns(&self) -> Option<u64> {
         if self.tick_per_second == 0 {
-            return u64::MAX;
+            return None;
         }
 
-        let elapse = 1_000_000_000 / self.tick_per_second;
-
-        self.last_tick + elapse
-    }
-
-    fn should_tick(&self, current: u64) -> bool {
-        current >= self.compute_next_tick_ns()
+        Some((1_000_000_000 / self.tick_per_second).max(1))
     }
 }
 
@@ -62,25 +83,196 @@ impl<E: Error + Sync + Send + 'static> Ticker for StdTicker<E> {
         self.tick_per_second = set_to
     }
 
+    fn max_steps_per_frame(&self) -> u32 {
+        self.max_steps_per_frame
+    }
+
+    fn set_max_steps_per_frame(&mut self, set_to: u32) {
+        self.max_steps_per_frame = set_to
+    }
+
+    fn max_frame_time_ns(&self) -> u64 {
+        self.max_frame_time_ns
+    }
+
+    fn set_max_frame_time_ns(&mut self, set_to: u64) {
+        self.max_frame_time_ns = set_to
+    }
+
+    fn clocks(&self) -> &Clocks {
+        &self.clocks
+    }
+
+    fn clocks_mut(&mut self) -> &mut Clocks {
+        &mut self.clocks
+    }
+
     fn drive(
         &mut self,
         time_service: &dyn TimeService,
         tickable: &mut dyn Tickable<Error = Self::Error>,
     ) -> Result<(), Self::Error> {
         let current = time_service.get_timestamp_ns();
-        let elapsed = self.last_update.saturating_sub(current);
-        let should_tick = self.should_tick(current);
+        let elapsed = current
+            .saturating_sub(self.last_update)
+            .min(self.max_frame_time_ns);
+        self.last_update = current;
+
+        let deltas = self.clocks.advance(elapsed);
+
+        tickable.pre_update(deltas[ClockChannel::Real as usize])?;
+
+        let alpha = match self.fixed_step_ns() {
+            Some(step) => {
+                self.accumulator = self
+                    .accumulator
+                    .saturating_add(deltas[ClockChannel::Game as usize]);
+
+                let mut steps = 0;
+                while self.accumulator >= step && steps < self.max_steps_per_frame {
+                    tickable.fixed_update(step)?;
+                    self.accumulator -= step;
+                    steps += 1;
+                }
+
+                // out of budget: drop the backlog but keep the partial step
+                self.accumulator %= step;
+
+                self.accumulator as f64 / step as f64
+            }
+            None => {
+                self.accumulator = 0;
+                0.0
+            }
+        };
+
+        let time = FrameTime { deltas, alpha };
 
-        tickable.pre_update(elapsed)?;
+        tickable.update(&time)?;
 
-        if should_tick {
-            tickable.fixed_update(elapsed)?;
+        tickable.post_update(&time)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use staccato_core::time_service::ManualTimeService;
+    use std::convert::Infallible;
+
+    #[derive(Debug, Default)]
+    struct Counter {
+        fixed: u32,
+        alpha: f64,
+        time: FrameTime,
+    }
+
+    impl Fallible for Counter {
+        type Error = Infallible;
+    }
+
+    impl Tickable for Counter {
+        fn pre_update(&mut self, _elapse_ns: u64) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn fixed_update(&mut self, _elapse_ns: u64) -> Result<(), Self::Error> {
+            self.fixed += 1;
+            Ok(())
+        }
+
+        fn update(&mut self, time: &FrameTime) -> Result<(), Self::Error> {
+            self.alpha = time.alpha;
+            self.time = *time;
+            Ok(())
+        }
+
+        fn post_update(&mut self, _time: &FrameTime) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn catches_up_and_reports_alpha() -> Result<(), Infallible> {
+        let mut time = ManualTimeService::new();
+        let mut ticker = StdTicker::new(&time, 100);
+        let mut counter = Counter::default();
+
+        time.advance(35_000_000);
+        ticker.drive(&time, &mut counter)?;
+
+        assert_eq!(counter.fixed, 3);
+        assert!((counter.alpha - 0.5).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn caps_catch_up_work() -> Result<(), Infallible> {
+        let mut time = ManualTimeService::new();
+        let mut ticker = StdTicker::new(&time, 100);
+        ticker.set_max_steps_per_frame(4);
+        let mut counter = Counter::default();
+
+        time.advance(10_000_000_000);
+        ticker.drive(&time, &mut counter)?;
+        assert_eq!(counter.fixed, 4);
+
+        // the backlog must not leak into the next frame
+        time.advance(5_000_000);
+        ticker.drive(&time, &mut counter)?;
+        assert_eq!(counter.fixed, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn exact_frame_count_at_60fps() -> Result<(), Infallible> {
+        let mut time = ManualTimeService::new();
+        let mut ticker = StdTicker::new(&time, 50);
+        let mut counter = Counter::default();
+
+        // 60 frames of 1/60s is exactly one second of simulated time
+        for frame in 0..60u64 {
+            time.set((frame + 1) * 1_000_000_000 / 60);
+            ticker.drive(&time, &mut counter)?;
         }
 
-        tickable.update(elapsed)?;
+        assert_eq!(counter.fixed, 50);
+        Ok(())
+    }
+
+    #[test]
+    fn paused_game_clock_keeps_ui_running() -> Result<(), Infallible> {
+        let mut time = ManualTimeService::new();
+        let mut ticker = StdTicker::new(&time, 100);
+        let mut counter = Counter::default();
+
+        ticker.clocks_mut().get_mut(ClockChannel::Game).pause();
+        ticker.clocks_mut().get_mut(ClockChannel::Ui).set_scale(0.5);
+
+        time.advance(20_000_000);
+        ticker.drive(&time, &mut counter)?;
+
+        assert_eq!(counter.fixed, 0);
+        assert_eq!(counter.time.elapse_ns(ClockChannel::Game), 0);
+        assert_eq!(counter.time.elapse_ns(ClockChannel::Ui), 10_000_000);
+        assert_eq!(counter.time.elapse_ns(ClockChannel::Real), 20_000_000);
+
+        ticker.clocks_mut().get_mut(ClockChannel::Game).resume();
+        ticker
+            .clocks_mut()
+            .get_mut(ClockChannel::Game)
+            .set_scale(0.5);
 
-        tickable.post_update(elapsed)?;
+        time.advance(40_000_000);
+        ticker.drive(&time, &mut counter)?;
 
+        assert_eq!(counter.fixed, 2);
+        assert_eq!(
+            ticker.clocks().get(ClockChannel::Game).total_ns(),
+            20_000_000
+        );
         Ok(())
     }
 }